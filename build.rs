@@ -0,0 +1,36 @@
+//! Build script: records the git commit being built as `GIT_HASH`
+//!
+//! Read by GET /version through `env!("GIT_HASH")`. A `GIT_HASH` set in the build
+//! environment wins (e.g. for Docker builds without the `.git` directory);
+//! otherwise `git rev-parse` is asked, and "unknown" is used when that fails.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+fn main() {
+    let hash = env::var("GIT_HASH")
+        .ok()
+        .filter(|hash| !hash.trim().is_empty())
+        .or_else(git_head)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_HASH={}", hash.trim());
+
+    // Rebuild when HEAD moves: a checkout rewrites .git/HEAD, a commit the branch ref
+    println!("cargo:rerun-if-env-changed=GIT_HASH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Ok(head) = fs::read_to_string(".git/HEAD")
+        && let Some(branch_ref) = head.strip_prefix("ref: ")
+    {
+        println!("cargo:rerun-if-changed=.git/{}", branch_ref.trim());
+    }
+}
+
+/// Short hash of the checked-out commit, if this is a git checkout
+fn git_head() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok().map(|hash| hash.trim().to_string()).filter(|hash| !hash.is_empty())
+}
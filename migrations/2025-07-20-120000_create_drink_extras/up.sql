@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Extras a drink can be customized with. A drink without rows here accepts every extra.
+CREATE TABLE drink_extras (
+    drink_id TEXT NOT NULL,
+    extra_id TEXT NOT NULL,
+    PRIMARY KEY (drink_id, extra_id),
+    FOREIGN KEY (drink_id) REFERENCES drinks(id),
+    FOREIGN KEY (extra_id) REFERENCES extras(id)
+);
\ No newline at end of file
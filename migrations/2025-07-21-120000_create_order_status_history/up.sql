@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE order_status_history (
+    id TEXT PRIMARY KEY,
+    order_id TEXT NOT NULL,
+    from_status TEXT NOT NULL CHECK(from_status IN ('pending', 'paid', 'preparing', 'ready', 'completed', 'cancelled')),
+    to_status TEXT NOT NULL CHECK(to_status IN ('pending', 'paid', 'preparing', 'ready', 'completed', 'cancelled')),
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id)
+);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Comma-separated sizes a drink can be ordered in (e.g. 'standard' for espresso).
+-- Existing drinks keep accepting every size.
+ALTER TABLE drinks ADD COLUMN available_sizes TEXT NOT NULL DEFAULT 'small,medium,large,standard';
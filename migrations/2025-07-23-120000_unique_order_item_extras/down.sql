@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX order_item_extras_item_extra_unique;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- An extra can be attached to an order item only once. Drop existing duplicates first.
+DELETE FROM order_item_extras
+WHERE rowid NOT IN (
+    SELECT MIN(rowid) FROM order_item_extras GROUP BY order_item_id, extra_id
+);
+
+CREATE UNIQUE INDEX order_item_extras_item_extra_unique ON order_item_extras (order_item_id, extra_id);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Index of the item in the order as it was placed; items are returned in this sequence.
+-- Existing items share position 0 and fall back to their id.
+ALTER TABLE order_items ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE drink_price_history;
+ALTER TABLE drinks DROP COLUMN category;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Optional menu grouping (e.g. 'coffee', 'tea'), stored lowercase
+ALTER TABLE drinks ADD COLUMN category TEXT;
+
+-- One row per drink price change
+CREATE TABLE drink_price_history (
+    id TEXT PRIMARY KEY,
+    drink_id TEXT NOT NULL,
+    old_price TEXT NOT NULL,
+    new_price TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY (drink_id) REFERENCES drinks(id)
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE order_item_modifiers;
+DROP TABLE drink_modifiers;
+DROP TABLE modifier_options;
+DROP TABLE modifiers;
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+-- Single-select customizations (e.g. milk: whole/oat/soy), distinct from additive extras
+CREATE TABLE modifiers (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE modifier_options (
+    id TEXT PRIMARY KEY,
+    modifier_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    is_available BOOLEAN NOT NULL DEFAULT 1,
+    FOREIGN KEY (modifier_id) REFERENCES modifiers(id)
+);
+
+-- Modifiers a drink offers. Unlike extras, a drink without rows here takes no modifiers.
+CREATE TABLE drink_modifiers (
+    drink_id TEXT NOT NULL,
+    modifier_id TEXT NOT NULL,
+    PRIMARY KEY (drink_id, modifier_id),
+    FOREIGN KEY (drink_id) REFERENCES drinks(id),
+    FOREIGN KEY (modifier_id) REFERENCES modifiers(id)
+);
+
+-- Option chosen for an order item, at most one per modifier
+CREATE TABLE order_item_modifiers (
+    id TEXT PRIMARY KEY,
+    order_item_id TEXT NOT NULL,
+    modifier_id TEXT NOT NULL,
+    modifier_option_id TEXT NOT NULL,
+    UNIQUE (order_item_id, modifier_id),
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id),
+    FOREIGN KEY (modifier_id) REFERENCES modifiers(id),
+    FOREIGN KEY (modifier_option_id) REFERENCES modifier_options(id)
+);
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+-- Extra names are unique so imports can upsert by name. Existing duplicates are
+-- renamed (not deleted, order items may reference them).
+UPDATE extras
+SET name = name || ' (' || id || ')'
+WHERE rowid NOT IN (
+    SELECT MIN(rowid) FROM extras GROUP BY name
+);
+
+CREATE UNIQUE INDEX extras_name_unique ON extras (name);
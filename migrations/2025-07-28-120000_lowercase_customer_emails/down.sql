@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+-- The original casing of normalized emails is not kept, nothing to undo.
+SELECT 1;
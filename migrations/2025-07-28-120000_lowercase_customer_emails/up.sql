@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Customer emails are stored trimmed and lowercase so the UNIQUE constraint
+-- compares them case-insensitively. Rows whose normalized email would collide
+-- with another customer's are left as they are; the API still treats them as
+-- the same address.
+UPDATE customers
+SET email = lower(trim(email))
+WHERE email <> lower(trim(email))
+  AND NOT EXISTS (
+    SELECT 1 FROM customers AS other
+    WHERE other.id <> customers.id
+      AND lower(trim(other.email)) = lower(trim(customers.email))
+  );
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Idempotency-Key of a POST /orders request, so a retried request returns the
+-- order it already created instead of placing a duplicate
+CREATE TABLE idempotency_keys (
+    key TEXT PRIMARY KEY,
+    order_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id)
+);
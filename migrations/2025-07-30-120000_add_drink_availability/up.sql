@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Whether the drink can currently be ordered (e.g. false while out of stock);
+-- unlike a soft delete the drink stays on the menu
+ALTER TABLE drinks ADD COLUMN is_available BOOLEAN NOT NULL DEFAULT 1;
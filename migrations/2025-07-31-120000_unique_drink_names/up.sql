@@ -0,0 +1,12 @@
+-- Your SQL goes here
+-- Names of drinks on the menu are unique; soft-deleted drinks don't count, so a
+-- deleted "Latte" doesn't block a new one. Existing duplicates among active
+-- drinks are renamed (not deleted, order items may reference them).
+UPDATE drinks
+SET name = name || ' (' || id || ')'
+WHERE deleted_at IS NULL
+  AND rowid NOT IN (
+    SELECT MIN(rowid) FROM drinks WHERE deleted_at IS NULL GROUP BY name
+  );
+
+CREATE UNIQUE INDEX drinks_name_unique ON drinks (name) WHERE deleted_at IS NULL;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+-- Optional contact number; cleared when the customer is anonymized
+ALTER TABLE customers ADD COLUMN phone TEXT;
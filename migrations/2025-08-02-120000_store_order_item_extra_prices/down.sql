@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE order_item_extras DROP COLUMN extra_price;
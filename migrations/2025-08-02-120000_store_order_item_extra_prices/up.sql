@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Price the extra was charged at when attached, so later catalog price changes
+-- do not alter what detaching it takes off the item
+ALTER TABLE order_item_extras ADD COLUMN extra_price TEXT NOT NULL DEFAULT '0.00'; -- Decimal
+
+-- Existing links were charged the price their extra has now
+UPDATE order_item_extras
+SET extra_price = (SELECT extras.extra_price FROM extras WHERE extras.id = order_item_extras.extra_id);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- Body of the 201 the key's first request got, so a retry receives the same
+-- response rather than the order's current state. NULL for keys recorded earlier.
+ALTER TABLE idempotency_keys ADD COLUMN response TEXT;
@@ -0,0 +1,172 @@
+//! Bearer token authentication of write endpoints
+//!
+//! Tokens are HS256 JWTs signed with `JWT_SECRET`, issued by POST /auth/token
+//! (staff) and POST /auth/customer-token (customers, at a staff member's request).
+//! `AuthenticatedUser` guards every handler that changes data and the reads of
+//! customer-owned data, where handlers check `can_access_customer`; other reads
+//! stay public. `StaffUser` additionally requires the `employee` role, for catalog
+//! changes, customer records and the order workflow.
+//! Without `JWT_SECRET` the server does not start (see `fairing`), unless
+//! `AUTH_DISABLED=true` explicitly turns both guards off for local development.
+
+use chrono::Utc;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rocket::fairing::AdHoc;
+use rocket::http::Status;
+use rocket::outcome::try_outcome;
+use rocket::request::{FromRequest, Outcome, Request};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{AppConfig, AuthSettings};
+use crate::models::auth::Role;
+
+/// Claims carried by an issued token
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// Staff username, or customer id, the token was issued to
+    sub: String,
+    /// Issue time, seconds since the epoch
+    iat: i64,
+    /// Expiry, seconds since the epoch
+    exp: i64,
+    /// What the holder may do; tokens without it count as customers
+    #[serde(default)]
+    role: Role,
+}
+
+/// Sign a token for `subject` with `role`, valid for `token_ttl_minutes`
+///
+/// Returns the token and its lifetime in seconds, or `None` when no secret is configured.
+pub fn issue_token(settings: &AuthSettings, subject: &str, role: Role) -> Option<(String, i64)> {
+    let secret = settings.jwt_secret.as_deref()?;
+    let now = Utc::now().timestamp();
+    let expires_in = settings.token_ttl_minutes * 60;
+    let claims = Claims { sub: subject.to_string(), iat: now, exp: now + expires_in, role };
+
+    match encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())) {
+        Ok(token) => Some((token, expires_in)),
+        Err(e) => {
+            error!("Cannot sign token: {}", e);
+            None
+        }
+    }
+}
+
+/// Ignite fairing refusing to start without a way to authenticate requests
+///
+/// Aborts ignition when `JWT_SECRET` is unset, since every write endpoint would
+/// then reject all requests. With `AUTH_DISABLED=true` it starts anyway and
+/// logs that writes are not authenticated.
+pub fn fairing() -> AdHoc {
+    AdHoc::try_on_ignite("Authentication", |rocket| {
+        Box::pin(async move {
+            let Some(settings) = rocket.state::<AppConfig>().map(|config| &config.auth) else {
+                return Ok(rocket);
+            };
+
+            if settings.disabled {
+                warn!("Authentication is disabled (AUTH_DISABLED=true): write endpoints accept unauthenticated requests");
+                Ok(rocket)
+            } else if settings.jwt_secret.is_none() {
+                error!(
+                    "JWT_SECRET is not set, so write endpoints cannot authenticate requests. \
+                     Set JWT_SECRET, or AUTH_DISABLED=true for local development without authentication"
+                );
+                Err(rocket)
+            } else {
+                Ok(rocket)
+            }
+        })
+    })
+}
+
+/// Why a request failed authentication or authorization, read by the 401 and
+/// 403 catchers
+///
+/// Kept in the request-local cache by `AuthenticatedUser` and `StaffUser`.
+pub struct AuthFailure(pub Option<&'static str>);
+
+/// Guard for endpoints that change data
+///
+/// Expects `Authorization: Bearer <token>` with a token signed by `JWT_SECRET`
+/// that has not expired. Fails with 401 and a message telling a missing or
+/// malformed header, an expired token and an invalid token apart.
+/// Carries the token's subject and role; every caller counts as staff when
+/// `AUTH_DISABLED` is set.
+pub struct AuthenticatedUser {
+    /// Staff username, or the customer id of a customer token
+    pub subject: String,
+    pub role: Role,
+}
+
+impl AuthenticatedUser {
+    /// Whether the caller may read or change the data of customer `customer_id`
+    /// Staff may access any customer's; a customer only their own.
+    pub fn can_access_customer(&self, customer_id: &str) -> bool {
+        self.role == Role::Employee || self.subject == customer_id
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedUser {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let settings = req.rocket().state::<AppConfig>().map(|config| &config.auth);
+        if settings.is_some_and(|settings| settings.disabled) {
+            return Outcome::Success(AuthenticatedUser { subject: "anonymous".to_string(), role: Role::Employee });
+        }
+
+        let secret = settings.and_then(|settings| settings.jwt_secret.as_deref());
+        let failure = match (secret, req.headers().get_one("Authorization")) {
+            (None, _) => "authentication is not configured",
+            (_, None) => "missing Authorization header",
+            (Some(secret), Some(value)) => match value
+                .strip_prefix("Bearer ")
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+            {
+                None => "Authorization header must be 'Bearer <token>'",
+                Some(token) => {
+                    let key = DecodingKey::from_secret(secret.as_bytes());
+                    match decode::<Claims>(token, &key, &Validation::default()) {
+                        Ok(data) => {
+                            let Claims { sub, role, .. } = data.claims;
+                            return Outcome::Success(AuthenticatedUser { subject: sub, role });
+                        }
+                        Err(e) if *e.kind() == ErrorKind::ExpiredSignature => "token has expired",
+                        Err(_) => "token is invalid",
+                    }
+                }
+            },
+        };
+
+        warn!("Authentication error: {}", failure);
+        req.local_cache(|| AuthFailure(Some(failure)));
+        Outcome::Error((Status::Unauthorized, ()))
+    }
+}
+
+/// Guard for endpoints reserved to staff (drink and extra changes)
+///
+/// Authenticates like `AuthenticatedUser` (401 on failure), then fails with 403
+/// unless the token's role is `employee`.
+pub struct StaffUser;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for StaffUser {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let user = try_outcome!(req.guard::<AuthenticatedUser>().await);
+
+        if user.role != Role::Employee {
+            warn!("Authorization error: employee role required");
+            req.local_cache(|| AuthFailure(Some("employee role required")));
+            return Outcome::Error((Status::Forbidden, ()));
+        }
+
+        Outcome::Success(StaffUser)
+    }
+}
@@ -0,0 +1,54 @@
+//! HTTP caching of catalog responses
+//!
+//! The menu changes rarely, so it is served with `Cache-Control` and an `ETag`
+//! derived from `CatalogVersion`. Every handler that changes drinks or extras
+//! bumps the version, which changes the ETag and makes clients refetch.
+//!
+//! Single drinks and customers are tagged from their `updated_at` instead
+//! (`row_etag`), so polling clients get 304 until the row changes.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::infra::sqlite_types::SqliteDateTime;
+
+/// Monotonically increasing catalog version, held in managed state
+///
+/// Starts at the server start time in epoch milliseconds and grows by one on
+/// every change, so it keeps increasing across restarts (the database may also
+/// have been edited while the server was down) without being persisted.
+pub struct CatalogVersion(AtomicU64);
+
+impl CatalogVersion {
+    pub fn new() -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        CatalogVersion(AtomicU64::new(started_at))
+    }
+
+    /// Record a change to drinks or extras, invalidating cached menus
+    pub fn bump(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Current version number
+    pub fn current(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Strong ETag (quoted) of the current catalog
+    pub fn etag(&self) -> String {
+        format!("\"catalog-{}\"", self.current())
+    }
+}
+
+/// ETag (quoted, without the weak `W/` prefix) of a row last changed at `updated_at`
+pub fn row_etag(updated_at: SqliteDateTime) -> String {
+    let mut hasher = DefaultHasher::new();
+    updated_at.into_naive_date_time().hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
@@ -0,0 +1,373 @@
+//! Application configuration
+//!
+//! Settings are read once from the environment at startup (after `.env` is
+//! loaded) and handed to Rocket as managed state, so handlers, guards and
+//! fairings can reach them through `State<AppConfig>` or `Request::rocket()`.
+//! Invalid values are reported with `eprintln!` rather than the log macros: the
+//! log subscriber is built from these settings and is not installed yet.
+
+use std::env;
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use tracing_subscriber::filter::LevelFilter;
+
+/// Runtime settings read from environment variables
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    /// Shared secret expected in the `X-Admin-Token` header for admin-only endpoints.
+    /// Admin endpoints are disabled (403) when unset.
+    pub admin_token: Option<String>,
+    /// Bearer token authentication of write endpoints (`JWT_SECRET`, `JWT_TTL_MINUTES`,
+    /// `AUTH_USERNAME`, `AUTH_PASSWORD`, `AUTH_DISABLED`)
+    pub auth: AuthSettings,
+    /// Origins allowed to call the API from a browser (`CORS_ALLOWED_ORIGINS`,
+    /// comma-separated). `*` allows any origin, without credentials.
+    pub cors_allowed_origins: Vec<String>,
+    /// Whether listed origins may send credentials (`CORS_ALLOW_CREDENTIALS`)
+    pub cors_allow_credentials: bool,
+    /// Opening hours enforced on order creation (`SHOP_OPEN_TIME`, `SHOP_CLOSE_TIME`,
+    /// `SHOP_UTC_OFFSET`). Orders are accepted at any time when unset.
+    pub shop_hours: Option<ShopHours>,
+    /// Minimum age in minutes before a pending order is listed as unpaid
+    /// (`UNPAID_ORDER_AGE_MINUTES`)
+    pub unpaid_order_age_minutes: i64,
+    /// Whether the background janitor cancels stale pending orders (`STALE_ORDER_CANCELLATION`)
+    pub stale_order_cancellation: bool,
+    /// Minutes without changes (by `updated_at`) after which a pending order is
+    /// cancelled (`STALE_ORDER_TTL_MINUTES`)
+    pub stale_order_ttl_minutes: i64,
+    /// Seconds between janitor sweeps (`STALE_ORDER_SWEEP_INTERVAL_SECONDS`)
+    pub stale_order_sweep_interval_seconds: u64,
+    /// Business printed on invoices (`SELLER_NAME`, `SELLER_ADDRESS`, `SELLER_TAX_ID`)
+    pub seller: SellerInfo,
+    /// Tax added on top of item prices on invoices, in percent (`TAX_RATE_PERCENT`)
+    pub tax_rate_percent: Decimal,
+    /// Indent JSON bodies of the read endpoints for debugging (`PRETTY_JSON`)
+    pub pretty_json: bool,
+    /// How far in the future (seconds) a client-supplied timestamp may be before
+    /// it is rejected (`MAX_CLOCK_SKEW_SECONDS`)
+    pub max_clock_skew_seconds: i64,
+    /// Smallest order subtotal accepted, 0 to accept any (`MIN_ORDER_TOTAL`)
+    pub min_order_total: Decimal,
+    /// Time budget in seconds for reporting endpoints before they answer 503
+    /// (`REPORT_TIMEOUT_SECONDS`, at least 1)
+    pub report_timeout_seconds: u64,
+    /// How long (seconds) clients and CDNs may cache GET /menu (`MENU_CACHE_MAX_AGE_SECONDS`)
+    pub menu_cache_max_age_seconds: u64,
+    /// Requests a client IP may make per window before getting 429, 0 to disable
+    /// (`RATE_LIMIT_REQUESTS`)
+    pub rate_limit_requests: usize,
+    /// Length of the sliding rate limit window, in seconds (`RATE_LIMIT_WINDOW_SECONDS`,
+    /// at least 1)
+    pub rate_limit_window_seconds: u64,
+    /// Largest JSON request body accepted, in KiB, before answering 413
+    /// (`JSON_LIMIT_KIB`, at least 1)
+    pub json_limit_kib: u64,
+    /// Pragmas applied to every pooled SQLite connection (`SQLITE_SYNCHRONOUS`,
+    /// `SQLITE_CACHE_SIZE_KIB`, `SQLITE_TEMP_STORE`)
+    pub sqlite: SqliteTuning,
+    /// Connections in the database pool (`DB_POOL_SIZE`), overriding
+    /// `databases.sqlite.pool_size`; Rocket's default when unset
+    pub db_pool_size: Option<u32>,
+    /// Seconds a request waits for a pooled connection before failing with 503
+    /// (`DB_TIMEOUT_SECONDS`), overriding `databases.sqlite.timeout`; values below
+    /// `db::MIN_CONNECTION_TIMEOUT_SECONDS` are raised to it
+    pub db_timeout_seconds: Option<u32>,
+    /// Most verbose log level emitted (`LOG_LEVEL`: trace, debug, info, warn or error)
+    pub log_level: LevelFilter,
+    /// Layout of log lines (`LOG_FORMAT`: pretty or json)
+    pub log_format: LogFormat,
+}
+
+/// Per-connection SQLite performance settings
+///
+/// Connections always run in WAL mode with foreign keys enforced. With WAL,
+/// `synchronous = NORMAL` (the default) skips the fsync on each commit: the
+/// database cannot be corrupted, but the last transactions before a power loss or
+/// OS crash may be rolled back (an application crash loses nothing). Use `FULL`
+/// when every acknowledged order must survive power loss, at some write speed.
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteTuning {
+    /// `PRAGMA synchronous`: OFF, NORMAL, FULL or EXTRA
+    pub synchronous: &'static str,
+    /// Page cache per connection, in KiB
+    pub cache_size_kib: u64,
+    /// `PRAGMA temp_store`: DEFAULT, FILE or MEMORY
+    pub temp_store: &'static str,
+}
+
+impl Default for SqliteTuning {
+    fn default() -> Self {
+        SqliteTuning {
+            synchronous: "NORMAL",
+            cache_size_kib: 16384,
+            temp_store: "MEMORY",
+        }
+    }
+}
+
+impl SqliteTuning {
+    /// Statements applying these settings to a connection
+    /// A negative `cache_size` is a size in KiB rather than in pages.
+    pub fn pragmas(&self) -> String {
+        format!(
+            "PRAGMA synchronous = {}; PRAGMA cache_size = -{}; PRAGMA temp_store = {};",
+            self.synchronous, self.cache_size_kib, self.temp_store
+        )
+    }
+}
+
+/// How log lines are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines, for local development
+    Pretty,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+/// JWT settings of the write endpoints
+///
+/// The server refuses to start without a secret unless authentication is
+/// explicitly disabled.
+#[derive(Debug, Clone)]
+pub struct AuthSettings {
+    /// HMAC secret signing and verifying tokens
+    pub jwt_secret: Option<String>,
+    /// Let every request through as staff, for local development only
+    pub disabled: bool,
+    /// Lifetime of an issued token, in minutes
+    pub token_ttl_minutes: i64,
+    /// Credential exchanged for a token at POST /auth/token
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Seller details shown on invoices
+#[derive(Debug, Clone)]
+pub struct SellerInfo {
+    pub name: String,
+    pub address: Option<String>,
+    pub tax_id: Option<String>,
+}
+
+/// Daily opening hours in the shop's local time
+#[derive(Debug, Clone, Copy)]
+pub struct ShopHours {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+    pub utc_offset: FixedOffset,
+}
+
+impl ShopHours {
+    /// Message of the 423 answered outside these hours
+    pub fn closed_message(&self) -> String {
+        format!(
+            "The shop is closed. Orders are accepted between {} and {} (UTC{}).",
+            self.open.format("%H:%M"),
+            self.close.format("%H:%M"),
+            self.utc_offset
+        )
+    }
+
+    /// Whether the shop is open at the given instant
+    /// A close time before the open time means the shop is open past midnight.
+    pub fn is_open_at(&self, now: DateTime<Utc>) -> bool {
+        let local_time = now.with_timezone(&self.utc_offset).time();
+        if self.open <= self.close {
+            self.open <= local_time && local_time < self.close
+        } else {
+            local_time >= self.open || local_time < self.close
+        }
+    }
+}
+
+impl AppConfig {
+    /// Offset of the shop's local time: `SHOP_UTC_OFFSET` when opening hours are
+    /// configured, UTC otherwise. Used to decide which calendar day an order falls on.
+    pub fn local_offset(&self) -> FixedOffset {
+        self.shop_hours
+            .map(|hours| hours.utc_offset)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is valid"))
+    }
+
+    /// Load the configuration from the process environment
+    pub fn from_env() -> Self {
+        AppConfig {
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|token| !token.trim().is_empty()),
+            auth: AuthSettings {
+                jwt_secret: env::var("JWT_SECRET").ok().filter(|secret| !secret.trim().is_empty()),
+                disabled: parse_bool("AUTH_DISABLED", false),
+                token_ttl_minutes: parse_number("JWT_TTL_MINUTES", 60).max(1),
+                username: parse_text("AUTH_USERNAME"),
+                password: env::var("AUTH_PASSWORD").ok().filter(|password| !password.is_empty()),
+            },
+            cors_allowed_origins: parse_list(&env::var("CORS_ALLOWED_ORIGINS").unwrap_or_else(|_| "*".to_string())),
+            cors_allow_credentials: parse_bool("CORS_ALLOW_CREDENTIALS", false),
+            shop_hours: parse_shop_hours(),
+            unpaid_order_age_minutes: parse_number("UNPAID_ORDER_AGE_MINUTES", 30),
+            stale_order_cancellation: parse_bool("STALE_ORDER_CANCELLATION", true),
+            stale_order_ttl_minutes: parse_number("STALE_ORDER_TTL_MINUTES", 120),
+            stale_order_sweep_interval_seconds: parse_number("STALE_ORDER_SWEEP_INTERVAL_SECONDS", 300),
+            seller: SellerInfo {
+                name: parse_text("SELLER_NAME").unwrap_or_else(|| "Coffee Shop".to_string()),
+                address: parse_text("SELLER_ADDRESS"),
+                tax_id: parse_text("SELLER_TAX_ID"),
+            },
+            tax_rate_percent: parse_number("TAX_RATE_PERCENT", Decimal::ZERO),
+            pretty_json: parse_bool("PRETTY_JSON", false),
+            max_clock_skew_seconds: parse_number("MAX_CLOCK_SKEW_SECONDS", 300),
+            min_order_total: parse_number("MIN_ORDER_TOTAL", Decimal::ZERO),
+            report_timeout_seconds: parse_number("REPORT_TIMEOUT_SECONDS", 10).max(1),
+            menu_cache_max_age_seconds: parse_number("MENU_CACHE_MAX_AGE_SECONDS", 60),
+            rate_limit_requests: parse_number("RATE_LIMIT_REQUESTS", 100),
+            rate_limit_window_seconds: parse_number("RATE_LIMIT_WINDOW_SECONDS", 60).max(1),
+            json_limit_kib: parse_number("JSON_LIMIT_KIB", 256).max(1),
+            sqlite: SqliteTuning {
+                synchronous: parse_choice("SQLITE_SYNCHRONOUS", &["OFF", "NORMAL", "FULL", "EXTRA"], "NORMAL"),
+                cache_size_kib: parse_number("SQLITE_CACHE_SIZE_KIB", SqliteTuning::default().cache_size_kib),
+                temp_store: parse_choice("SQLITE_TEMP_STORE", &["DEFAULT", "FILE", "MEMORY"], "MEMORY"),
+            },
+            db_pool_size: parse_optional_number("DB_POOL_SIZE"),
+            db_timeout_seconds: parse_optional_number("DB_TIMEOUT_SECONDS"),
+            log_level: parse_log_level(),
+            log_format: parse_log_format(),
+        }
+    }
+}
+
+/// Read a free-text setting, treating blank values as unset
+fn parse_text(name: &str) -> Option<String> {
+    env::var(name).ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Read a non-negative numeric setting, falling back to the default when unset or invalid
+fn parse_number<T>(name: &str, default: T) -> T
+where
+    T: std::str::FromStr + PartialOrd + Default + std::fmt::Display + Copy,
+{
+    match env::var(name) {
+        Ok(value) => match value.trim().parse::<T>() {
+            Ok(number) if number >= T::default() => number,
+            _ => {
+                eprintln!("Configuration warning: {} must be a non-negative number, using {}", name, default);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Read a non-negative numeric setting without a default, `None` when unset or invalid
+fn parse_optional_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse::<T>() {
+        Ok(number) => Some(number),
+        Err(_) => {
+            eprintln!("Configuration warning: {} must be a non-negative number, ignoring it", name);
+            None
+        }
+    }
+}
+
+/// Read the opening hours; both times must be set (as `HH:MM`) to enable the check
+fn parse_shop_hours() -> Option<ShopHours> {
+    let open = env::var("SHOP_OPEN_TIME").ok()?;
+    let close = env::var("SHOP_CLOSE_TIME").ok()?;
+    let offset = env::var("SHOP_UTC_OFFSET").unwrap_or_else(|_| "+00:00".to_string());
+
+    let parsed = (
+        NaiveTime::parse_from_str(open.trim(), "%H:%M"),
+        NaiveTime::parse_from_str(close.trim(), "%H:%M"),
+        offset.trim().parse::<FixedOffset>(),
+    );
+
+    match parsed {
+        (Ok(open), Ok(close), Ok(utc_offset)) => Some(ShopHours { open, close, utc_offset }),
+        _ => {
+            eprintln!(
+                "Configuration warning: invalid shop hours (SHOP_OPEN_TIME/SHOP_CLOSE_TIME must be HH:MM, \
+                 SHOP_UTC_OFFSET like +02:00), opening hours are not enforced"
+            );
+            None
+        }
+    }
+}
+
+/// Split a comma-separated setting, dropping blanks and trailing slashes
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().trim_end_matches('/'))
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Read a boolean setting (`true`/`1`/`yes`), falling back to the default when unset or invalid
+fn parse_bool(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => true,
+            "false" | "0" | "no" => false,
+            _ => {
+                eprintln!("Configuration warning: {} must be a boolean, using {}", name, default);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Read a setting restricted to a few keywords (case-insensitive), falling back
+/// to the default when unset or invalid
+fn parse_choice(name: &str, choices: &[&'static str], default: &'static str) -> &'static str {
+    let Ok(value) = env::var(name) else {
+        return default;
+    };
+
+    let value = value.trim().to_uppercase();
+    match choices.iter().find(|choice| **choice == value) {
+        Some(choice) => choice,
+        None => {
+            eprintln!("Configuration warning: {} must be one of {}, using {}", name, choices.join(", "), default);
+            default
+        }
+    }
+}
+
+/// Read `LOG_LEVEL`, falling back to `info` when unset or invalid
+fn parse_log_level() -> LevelFilter {
+    let Ok(value) = env::var("LOG_LEVEL") else {
+        return LevelFilter::INFO;
+    };
+
+    match value.trim().to_lowercase().as_str() {
+        "trace" => LevelFilter::TRACE,
+        "debug" => LevelFilter::DEBUG,
+        "info" => LevelFilter::INFO,
+        "warn" => LevelFilter::WARN,
+        "error" => LevelFilter::ERROR,
+        _ => {
+            eprintln!("Configuration warning: LOG_LEVEL must be trace, debug, info, warn or error, using info");
+            LevelFilter::INFO
+        }
+    }
+}
+
+/// Read `LOG_FORMAT`, falling back to `pretty` when unset or invalid
+fn parse_log_format() -> LogFormat {
+    let Ok(value) = env::var("LOG_FORMAT") else {
+        return LogFormat::Pretty;
+    };
+
+    match value.trim().to_lowercase().as_str() {
+        "pretty" => LogFormat::Pretty,
+        "json" => LogFormat::Json,
+        _ => {
+            eprintln!("Configuration warning: LOG_FORMAT must be pretty or json, using pretty");
+            LogFormat::Pretty
+        }
+    }
+}
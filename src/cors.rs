@@ -0,0 +1,109 @@
+//! Cross-Origin Resource Sharing
+//!
+//! Response fairing that lets browser front-ends on other origins call the API.
+//! The allowed origins come from `AppConfig::cors_allowed_origins`:
+//! - an origin listed explicitly is echoed back in `Access-Control-Allow-Origin`
+//!   (with `Vary: Origin`), and credentials are allowed if configured
+//! - `*` in the list allows any origin, but never together with credentials:
+//!   browsers reject `*` on credentialed requests, so the wildcard is answered
+//!   with a literal `*` and no `Access-Control-Allow-Credentials`
+//! - any other origin gets no CORS headers at all, so the browser blocks it
+//!
+//! Preflight `OPTIONS` requests to `/api/*` are answered by `preflight` with
+//! 204 and the allowed methods and headers, without touching the database.
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::{options, routes, Request, Response, Route};
+
+use crate::config::AppConfig;
+use crate::guards::{ADMIN_TOKEN_HEADER, IDEMPOTENCY_KEY_HEADER};
+
+/// Methods browsers may use on the API, announced to preflight requests
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+/// Response headers scripts on other origins may read
+const EXPOSED_HEADERS: &str = "Location, ETag, Link, X-Total-Count, X-Request-Id";
+
+/// How long (seconds) browsers may cache a preflight answer
+const PREFLIGHT_MAX_AGE_SECONDS: u32 = 86400;
+
+/// Answer CORS preflight requests for any API path
+///
+/// The CORS headers themselves are added by the fairing, like on every response.
+#[options("/<_..>")]
+pub fn preflight() -> Status {
+    Status::NoContent
+}
+
+/// Preflight route, to be mounted under "/api"
+pub fn routes() -> Vec<Route> {
+    routes![preflight]
+}
+
+/// CORS response fairing
+pub struct Cors {
+    allowed_origins: Vec<String>,
+    allow_any_origin: bool,
+    allow_credentials: bool,
+}
+
+impl Cors {
+    /// Build the fairing from the application configuration
+    pub fn from_config(config: &AppConfig) -> Self {
+        let allow_any_origin = config.cors_allowed_origins.iter().any(|origin| origin == "*");
+        let allowed_origins = config
+            .cors_allowed_origins
+            .iter()
+            .filter(|origin| origin.as_str() != "*")
+            .cloned()
+            .collect();
+
+        Cors {
+            allowed_origins,
+            allow_any_origin,
+            allow_credentials: config.cors_allow_credentials,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        // Same-origin and non-browser requests carry no Origin header
+        let Some(origin) = req.headers().get_one("Origin") else {
+            return;
+        };
+
+        if self.allowed_origins.iter().any(|allowed| allowed == origin) {
+            res.set_header(Header::new("Access-Control-Allow-Origin", origin.to_string()));
+            res.adjoin_header(Header::new("Vary", "Origin"));
+            if self.allow_credentials {
+                res.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            }
+        } else if self.allow_any_origin {
+            res.set_header(Header::new("Access-Control-Allow-Origin", "*"));
+        } else {
+            return;
+        }
+
+        res.set_header(Header::new("Access-Control-Expose-Headers", EXPOSED_HEADERS));
+
+        if req.method() == Method::Options {
+            let allowed_headers = format!(
+                "Authorization, Content-Type, If-None-Match, {}, {}",
+                ADMIN_TOKEN_HEADER, IDEMPOTENCY_KEY_HEADER
+            );
+            res.set_header(Header::new("Access-Control-Allow-Methods", ALLOWED_METHODS));
+            res.set_header(Header::new("Access-Control-Allow-Headers", allowed_headers));
+            res.set_header(Header::new("Access-Control-Max-Age", PREFLIGHT_MAX_AGE_SECONDS.to_string()));
+        }
+    }
+}
@@ -1,4 +1,341 @@
-use rocket_sync_db_pools::{database, diesel};
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
 
-#[database("sqlite")]
-pub struct DbConn(pub diesel::SqliteConnection);
\ No newline at end of file
+use rocket::fairing::{self, Fairing};
+use rocket::figment::Figment;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::{Build, Ignite, Phase, Rocket, Sentinel};
+use rocket_sync_db_pools::{diesel, Config, Connection, ConnectionPool, PoolResult, Poolable};
+use diesel::connection::SimpleConnection;
+use diesel::r2d2::{self, ConnectionManager, CustomizeConnection, ManageConnection, Pool};
+use diesel::result::{Error, QueryResult};
+use diesel::SqliteConnection;
+
+use crate::config::AppConfig;
+use crate::error::ApiError;
+
+/// Request guard handing out a pooled connection to the `sqlite` database
+///
+/// Equivalent to `#[database("sqlite")]`, written out so the pool is built by
+/// `PooledSqlite` (with the configured pragmas) while `run` still passes a plain
+/// Diesel `SqliteConnection` to the closure.
+pub struct DbConn(Connection<DbConn, PooledSqlite>);
+
+/// Pool backing `DbConn`
+pub type DbPool = ConnectionPool<DbConn, PooledSqlite>;
+
+impl DbConn {
+    /// Fairing initializing the connection pool from `databases.sqlite`
+    pub fn fairing() -> impl Fairing {
+        DbPool::fairing("'sqlite' Database Pool", "sqlite")
+    }
+
+    /// The pool itself, for background tasks that outlive a request
+    pub fn pool<P: Phase>(rocket: &Rocket<P>) -> Option<&DbPool> {
+        DbPool::pool(rocket)
+    }
+
+    /// Take a connection from `pool`
+    pub async fn from_pool(pool: &DbPool) -> Option<Self> {
+        pool.get().await.map(Self)
+    }
+
+    /// Run `f` on the connection in a blocking-safe thread
+    pub async fn run<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut SqliteConnection) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.0.run(move |conn| f(&mut conn.0)).await
+    }
+
+    /// Take a connection from the pool outside of a request
+    pub async fn get_one<P: Phase>(rocket: &Rocket<P>) -> Option<Self> {
+        DbPool::get_one(rocket).await.map(Self)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for DbConn {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ()> {
+        Connection::<Self, PooledSqlite>::from_request(req).await.map(Self)
+    }
+}
+
+impl Sentinel for DbConn {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        Connection::<Self, PooledSqlite>::abort(rocket)
+    }
+}
+
+/// Milliseconds a connection waits for another one's write lock before SQLite
+/// reports "database is locked"
+const BUSY_TIMEOUT_MS: u32 = 5000;
+
+/// Smallest pool accepted for SQLite
+///
+/// SQLite allows a single writer at a time, but in WAL mode readers run alongside
+/// it. With one connection every read would queue behind each write (and behind
+/// the janitor's sweeps), so smaller configured sizes are raised to this.
+pub const MIN_POOL_SIZE: u32 = 2;
+
+/// Shortest wait for a pooled connection accepted, in seconds
+///
+/// r2d2 panics on a zero connection timeout, so lower configured values are
+/// raised to this.
+pub const MIN_CONNECTION_TIMEOUT_SECONDS: u8 = 1;
+
+/// SQLite connection as held by the `DbConn` pool
+pub struct PooledSqlite(SqliteConnection);
+
+/// r2d2 manager opening `PooledSqlite` connections
+pub struct PooledSqliteManager(ConnectionManager<SqliteConnection>);
+
+impl ManageConnection for PooledSqliteManager {
+    type Connection = PooledSqlite;
+    type Error = r2d2::Error;
+
+    fn connect(&self) -> Result<PooledSqlite, r2d2::Error> {
+        self.0.connect().map(PooledSqlite)
+    }
+
+    fn is_valid(&self, conn: &mut PooledSqlite) -> Result<(), r2d2::Error> {
+        self.0.is_valid(&mut conn.0)
+    }
+
+    fn has_broken(&self, conn: &mut PooledSqlite) -> bool {
+        self.0.has_broken(&mut conn.0)
+    }
+}
+
+/// Pragmas run on every new pooled connection
+#[derive(Debug)]
+struct ConnectionPragmas(String);
+
+impl CustomizeConnection<PooledSqlite, r2d2::Error> for ConnectionPragmas {
+    fn on_acquire(&self, conn: &mut PooledSqlite) -> Result<(), r2d2::Error> {
+        conn.0.batch_execute(&self.0).map_err(r2d2::Error::QueryError)
+    }
+}
+
+impl Poolable for PooledSqlite {
+    type Manager = PooledSqliteManager;
+    type Error = std::convert::Infallible;
+
+    /// Build the pool from `databases.sqlite` (url, pool_size, timeout)
+    ///
+    /// `pool_size` is raised to `MIN_POOL_SIZE` and `timeout` to
+    /// `MIN_CONNECTION_TIMEOUT_SECONDS` when configured lower. Each
+    /// connection gets a `BUSY_TIMEOUT_MS` busy timeout, WAL mode and foreign keys,
+    /// then the `SqliteTuning` from `AppConfig`. The busy timeout is set first so
+    /// switching to WAL waits for connections opened at the same time.
+    fn pool(db_name: &str, rocket: &Rocket<Build>) -> PoolResult<Self> {
+        let config = Config::from(db_name, rocket)?;
+        if config.pool_size < MIN_POOL_SIZE {
+            warn!("Database pool size {} is too small for SQLite, using {}", config.pool_size, MIN_POOL_SIZE);
+        }
+        let pool_size = config.pool_size.max(MIN_POOL_SIZE);
+        if config.timeout < MIN_CONNECTION_TIMEOUT_SECONDS {
+            warn!(
+                "Database connection timeout {}s is too short, using {}s",
+                config.timeout, MIN_CONNECTION_TIMEOUT_SECONDS
+            );
+        }
+        let timeout = config.timeout.max(MIN_CONNECTION_TIMEOUT_SECONDS);
+        info!("SQLite pool: {} connections, {}s connection timeout", pool_size, timeout);
+        let tuning = rocket.state::<AppConfig>().map(|config| config.sqlite).unwrap_or_default();
+        info!(
+            "SQLite connections: synchronous={}, cache_size={} KiB, temp_store={}",
+            tuning.synchronous, tuning.cache_size_kib, tuning.temp_store
+        );
+
+        let pragmas = format!(
+            "PRAGMA busy_timeout = {}; PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON; {}",
+            BUSY_TIMEOUT_MS,
+            tuning.pragmas()
+        );
+        let pool = Pool::builder()
+            .connection_customizer(Box::new(ConnectionPragmas(pragmas)))
+            .max_size(pool_size)
+            .connection_timeout(Duration::from_secs(timeout as u64))
+            .build(PooledSqliteManager(ConnectionManager::new(&config.url)))?;
+
+        Ok(pool)
+    }
+}
+
+// SQL functions used in queries
+diesel::define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// Escape character of the patterns built by `like_contains`, for `.escape()`
+pub const LIKE_ESCAPE: char = '\\';
+
+/// LIKE pattern matching text that contains `term` literally
+///
+/// The `%` and `_` wildcards (and the escape character itself) in `term` are
+/// escaped, so a search for "50%" looks for those three characters. Use with
+/// `.like(like_contains(term)).escape(LIKE_ESCAPE)`.
+pub fn like_contains(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for ch in term.chars() {
+        if matches!(ch, '%' | '_' | LIKE_ESCAPE) {
+            pattern.push(LIKE_ESCAPE);
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Attempts made by `retry_on_busy` before giving up
+const MAX_BUSY_ATTEMPTS: u32 = 5;
+
+/// Run a database operation, retrying with exponential backoff while SQLite
+/// reports the database as busy or locked (another connection holds the write lock).
+/// Any other outcome, success or error, is returned immediately.
+pub fn retry_on_busy<T>(mut operation: impl FnMut() -> QueryResult<T>) -> QueryResult<T> {
+    let mut delay = Duration::from_millis(50);
+    let mut attempt = 1;
+
+    loop {
+        match operation() {
+            Err(e) if is_busy(&e) && attempt < MAX_BUSY_ATTEMPTS => {
+                warn!("Database busy (attempt {}/{}), retrying in {:?}", attempt, MAX_BUSY_ATTEMPTS, delay);
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether an error is SQLite's "database is locked/busy" condition
+fn is_busy(error: &Error) -> bool {
+    match error {
+        Error::DatabaseError(_, info) => {
+            let message = info.message();
+            message.contains("database is locked") || message.contains("database is busy")
+        }
+        _ => false,
+    }
+}
+
+/// Figment key holding the SQLite database URL of `DbConn`
+const DATABASE_URL_KEY: &str = "databases.sqlite.url";
+
+/// How to configure the database, printed when no URL is found
+pub const MISSING_DATABASE_HELP: &str = "No database configured: set the `sqlite` database URL in Rocket.toml \
+     ([default.databases.sqlite] url = \"<path>\"), ROCKET_DATABASES='{sqlite={url=\"<path>\"}}' or DATABASE_URL";
+
+/// SQLite database URL of `DbConn`, from Rocket's configuration or `DATABASE_URL`
+///
+/// `None` when neither is set (or the configured value is not a string), in which
+/// case the pool cannot start.
+pub fn database_url(figment: &Figment) -> Option<String> {
+    figment
+        .extract_inner::<String>(DATABASE_URL_KEY)
+        .ok()
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .filter(|url| !url.trim().is_empty())
+}
+
+/// Make sure the SQLite database file can be opened before the pool starts
+///
+/// Attach before `DbConn::fairing()`. The URL comes from Rocket's configuration
+/// (`databases.sqlite.url`) or, failing that, the `DATABASE_URL` environment variable.
+/// A missing file is created empty, along with its parent directories, so the
+/// migrations can run on first start. Ignition is aborted with a descriptive
+/// message if the file cannot be created. In-memory and `file:` URI databases are
+/// left to SQLite.
+pub async fn ensure_database_file(rocket: Rocket<Build>) -> fairing::Result {
+    let Some(url) = database_url(rocket.figment()) else {
+        error!("{}", MISSING_DATABASE_HELP);
+        return Err(rocket);
+    };
+    // Hand a `DATABASE_URL` fallback on to the pool, which only reads the figment
+    let rocket = if rocket.figment().extract_inner::<String>(DATABASE_URL_KEY).ok().as_ref() == Some(&url) {
+        rocket
+    } else {
+        let figment = rocket.figment().clone().merge((DATABASE_URL_KEY, url.clone()));
+        rocket.configure(figment)
+    };
+
+    if url == ":memory:" || url.starts_with("file:") {
+        return Ok(rocket);
+    }
+
+    let path = Path::new(&url);
+    if path.exists() {
+        info!("Using SQLite database {}", url);
+        return Ok(rocket);
+    }
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty())
+        && let Err(e) = fs::create_dir_all(dir)
+    {
+        error!("Cannot create database directory {}: {}", dir.display(), e);
+        return Err(rocket);
+    }
+
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => {
+            info!("SQLite database {} not found, created an empty one; migrations will initialize it", url);
+            Ok(rocket)
+        }
+        Err(e) => {
+            error!("Cannot create SQLite database {}: {} (is the directory writable?)", url, e);
+            Err(rocket)
+        }
+    }
+}
+
+/// Wall-clock budget of a report, checked between its queries
+#[derive(Debug, Clone, Copy)]
+pub struct ReportDeadline(Instant);
+
+impl ReportDeadline {
+    /// Fail with 503 once the report has run out of time
+    /// Call it between queries so an oversized report stops early and releases
+    /// its connection instead of running every remaining query.
+    pub fn check(&self) -> Result<(), Status> {
+        if Instant::now() > self.0 {
+            Err(Status::ServiceUnavailable)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Run a reporting query under `REPORT_TIMEOUT_SECONDS`
+///
+/// Answers 503 with a "narrow the range" message when the report exceeds its
+/// time budget, either through `ReportDeadline::check` or because the client-side
+/// wait times out. SQLite cannot cancel a statement through Diesel, so a single
+/// long query still finishes in the background before its connection returns to
+/// the pool; the deadline keeps any further queries from starting.
+pub async fn run_report<T, F>(conn: &DbConn, timeout: Duration, report: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut SqliteConnection, ReportDeadline) -> Result<T, Status> + Send + 'static,
+{
+    let deadline = ReportDeadline(Instant::now() + timeout);
+
+    match rocket::tokio::time::timeout(timeout, conn.run(move |c| report(c, deadline))).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(status)) if status != Status::ServiceUnavailable => Err(ApiError::from(status)),
+        _ => {
+            error!("Report exceeded its {}s time budget", timeout.as_secs());
+            Err(ApiError::new(
+                Status::ServiceUnavailable,
+                "Report too large, narrow the range and try again.",
+            ))
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! API error responses
+//!
+//! `ApiError` pairs an HTTP status with a human-readable message and renders the
+//! same JSON shape as the error catchers in `lib.rs`:
+//! `{"error": "<reason phrase>", "message": "<details>"}`.
+//! Handlers return it when the generic catcher message isn't specific enough.
+//! Diesel errors convert into it too, so database failures get a JSON body.
+
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, status, Responder};
+use rocket::serde::json::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Error returned by API handlers
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: Status,
+    pub message: String,
+}
+
+impl ApiError {
+    /// Error with a specific message
+    pub fn new(status: Status, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// 400 Bad Request
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        ApiError::new(Status::BadRequest, message)
+    }
+
+    /// 409 Conflict
+    pub fn conflict(message: impl Into<String>) -> Self {
+        ApiError::new(Status::Conflict, message)
+    }
+}
+
+/// Bare statuses fall back to the generic catcher messages
+impl From<Status> for ApiError {
+    fn from(status: Status) -> Self {
+        let message = match status.code {
+            400 => "The request was invalid or cannot be served.",
+            404 => "The requested resource could not be found.",
+            409 => "The request conflicts with the current state of the resource.",
+            422 => "The request was well-formed but contains invalid data or unknown fields.",
+            _ => "An unexpected error occurred while processing the request.",
+        };
+        ApiError::new(status, message)
+    }
+}
+
+/// Database errors: missing rows are 404, unique constraint violations 409
+/// naming the conflicting columns, anything else a generic 500. Callers log the
+/// error with their context before converting it.
+impl From<DieselError> for ApiError {
+    fn from(error: DieselError) -> Self {
+        match error {
+            DieselError::NotFound => ApiError::from(Status::NotFound),
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                // SQLite reports "UNIQUE constraint failed: customers.email[, ...]"
+                let columns: Vec<&str> = info
+                    .message()
+                    .rsplit(": ")
+                    .next()
+                    .unwrap_or_default()
+                    .split(", ")
+                    .map(|column| column.rsplit('.').next().unwrap_or(column))
+                    .collect();
+                ApiError::conflict(format!("A record with the same {} already exists", columns.join(" and ")))
+            }
+            _ => ApiError::from(Status::InternalServerError),
+        }
+    }
+}
+
+/// JSON body of an error response, as written by `ApiError` and the catchers
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorApiModel {
+    /// Reason phrase of the status, e.g. "Not Found"
+    pub error: String,
+    pub message: String,
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let body = ErrorApiModel {
+            error: self.status.reason().unwrap_or("Error").to_string(),
+            message: self.message,
+        };
+        status::Custom(self.status, Json(body)).respond_to(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_violation(message: &str) -> DieselError {
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new(message.to_string()))
+    }
+
+    #[test]
+    fn unique_violations_name_the_columns() {
+        let error = ApiError::from(unique_violation("UNIQUE constraint failed: customers.email"));
+        assert_eq!(error.status, Status::Conflict);
+        assert_eq!(error.message, "A record with the same email already exists");
+
+        let error = ApiError::from(unique_violation(
+            "UNIQUE constraint failed: order_item_extras.order_item_id, order_item_extras.extra_id",
+        ));
+        assert_eq!(error.message, "A record with the same order_item_id and extra_id already exists");
+    }
+
+    #[test]
+    fn other_database_errors_are_not_leaked() {
+        assert_eq!(ApiError::from(DieselError::NotFound).status, Status::NotFound);
+        let error = ApiError::from(DieselError::DatabaseError(
+            DatabaseErrorKind::ForeignKeyViolation,
+            Box::new("FOREIGN KEY constraint failed".to_string()),
+        ));
+        assert_eq!(error.status, Status::InternalServerError);
+        assert!(!error.message.contains("FOREIGN KEY"));
+    }
+}
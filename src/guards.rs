@@ -0,0 +1,308 @@
+//! Request guards shared by the route modules
+//!
+//! - AdminUser: restricts an endpoint to callers presenting the admin token
+//! - ShopOpen: rejects requests outside the configured opening hours
+//! - IfNoneMatch: entity tags of a conditional GET
+//! - IdempotencyKey: client key making a retried POST safe
+//! - IncludeDeleted: staff request to list soft-deleted rows too
+//! - JsonBody: JSON request body whose deserialization errors name the field
+//! - UuidParam: path segment holding a UUID id
+
+use rocket::data::{self, Data, FromData, Limits};
+use rocket::http::Status;
+use rocket::outcome::try_outcome;
+use rocket::request::FromParam;
+use rocket::request::{FromRequest, Outcome, Request};
+use serde::de::DeserializeOwned;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::auth::StaffUser;
+use crate::config::AppConfig;
+use crate::error::ApiError;
+
+/// Header carrying the admin token
+pub const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
+/// Guard for admin-only endpoints
+///
+/// Succeeds when the `X-Admin-Token` header matches the `ADMIN_TOKEN` setting
+/// (compared in constant time).
+/// Fails with 401 when the header is missing and 403 when it doesn't match or
+/// when no admin token is configured at all.
+pub struct AdminUser;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminUser {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let expected = req
+            .rocket()
+            .state::<AppConfig>()
+            .and_then(|config| config.admin_token.as_deref());
+
+        let Some(expected) = expected else {
+            warn!("Authorization error: admin endpoints are disabled (ADMIN_TOKEN not set)");
+            return Outcome::Error((Status::Forbidden, ()));
+        };
+
+        match req.headers().get_one(ADMIN_TOKEN_HEADER) {
+            None => Outcome::Error((Status::Unauthorized, ())),
+            // Constant-time, so response timing doesn't reveal how much of the token matched
+            Some(token) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Outcome::Success(AdminUser),
+            Some(_) => {
+                warn!("Authorization error: invalid admin token");
+                Outcome::Error((Status::Forbidden, ()))
+            }
+        }
+    }
+}
+
+/// Guard for endpoints only usable while the shop is open (adding items, checkout)
+///
+/// Fails with 423 Locked outside the configured `ShopHours`; the 423 catcher
+/// tells the client the opening hours. Always succeeds when no hours are configured.
+/// POST /orders checks the hours itself, after its idempotency lookup.
+pub struct ShopOpen;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ShopOpen {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let hours = req.rocket().state::<AppConfig>().and_then(|config| config.shop_hours);
+
+        match hours {
+            Some(hours) if !hours.is_open_at(chrono::Utc::now()) => {
+                warn!("Validation error: order rejected outside opening hours");
+                Outcome::Error((Status::Locked, ()))
+            }
+            _ => Outcome::Success(ShopOpen),
+        }
+    }
+}
+
+/// `If-None-Match` header of a conditional GET
+///
+/// Holds the listed entity tags, with weak `W/` prefixes removed; empty when the
+/// header is absent. Never fails.
+pub struct IfNoneMatch(Vec<String>);
+
+impl IfNoneMatch {
+    /// Whether the client already holds the representation tagged `etag`
+    pub fn matches(&self, etag: &str) -> bool {
+        self.0.iter().any(|tag| tag == "*" || tag == etag)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let tags = req
+            .headers()
+            .get("If-None-Match")
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().trim_start_matches("W/").to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        Outcome::Success(IfNoneMatch(tags))
+    }
+}
+
+/// Header carrying the idempotency key of a request
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Longest idempotency key accepted
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// `Idempotency-Key` header of a request that must not be applied twice
+///
+/// Holds the trimmed key, or `None` when the header is absent. Fails with 400
+/// when the key is blank or longer than 255 characters.
+pub struct IdempotencyKey(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKey {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(key) = req.headers().get_one(IDEMPOTENCY_KEY_HEADER).map(str::trim) else {
+            return Outcome::Success(IdempotencyKey(None));
+        };
+
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            warn!(
+                "Validation error: {} must be 1 to {} characters",
+                IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LEN
+            );
+            return Outcome::Error((Status::BadRequest, ()));
+        }
+
+        Outcome::Success(IdempotencyKey(Some(key.to_string())))
+    }
+}
+
+/// `?include_deleted=` flag of the list endpoints
+///
+/// `true` asks for soft-deleted rows alongside the active ones. Since that exposes
+/// deleted data, the flag requires a staff caller (`StaffUser`: 401 without a valid
+/// token, 403 for other roles). Fails with 400 when the value is not a boolean.
+/// Absent or `false` always succeeds.
+pub struct IncludeDeleted(pub bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IncludeDeleted {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match req.query_value::<bool>("include_deleted") {
+            None | Some(Ok(false)) => Outcome::Success(IncludeDeleted(false)),
+            Some(Ok(true)) => {
+                try_outcome!(req.guard::<StaffUser>().await);
+                Outcome::Success(IncludeDeleted(true))
+            }
+            Some(Err(_)) => {
+                warn!("Validation error: include_deleted must be true or false");
+                Outcome::Error((Status::BadRequest, ()))
+            }
+        }
+    }
+}
+
+/// Why a JSON body could not be deserialized, read by the 422 catcher
+///
+/// Kept in the request-local cache by `JsonBody`. `field` is the path of the
+/// offending value (e.g. `base_price` or `available_sizes[1]`), or `None` when
+/// the problem is with the body as a whole; `reason` is serde's explanation.
+#[derive(Default)]
+pub struct JsonFailure {
+    pub field: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// JSON request body, like `Json<T>` but with field-level errors
+///
+/// Fails like `Json<T>`: 413 over the `json` limit, 400 for malformed JSON and
+/// 422 for well-formed JSON that doesn't fit `T`. On a 422 the catcher names the
+/// field that failed and serde's reason, e.g. `unknown variant `huge``.
+pub struct JsonBody<T>(pub T);
+
+impl<T> JsonBody<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for JsonBody<T> {
+    type Error = ();
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_string().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                warn!("Validation error: JSON body larger than {}", limit);
+                return data::Outcome::Error((Status::PayloadTooLarge, ()));
+            }
+            Err(e) => {
+                warn!("Validation error: unreadable JSON body: {}", e);
+                return data::Outcome::Error((Status::BadRequest, ()));
+            }
+        };
+
+        let deserializer = &mut serde_json::Deserializer::from_str(&body);
+        match serde_path_to_error::deserialize(deserializer) {
+            Ok(value) => data::Outcome::Success(JsonBody(value)),
+            Err(e) => {
+                let path = e.path().to_string();
+                let field = Some(path).filter(|path| path != ".");
+                let error = e.into_inner();
+                warn!("Validation error: JSON body at {}: {}", field.as_deref().unwrap_or("root"), error);
+
+                if !error.is_data() {
+                    return data::Outcome::Error((Status::BadRequest, ()));
+                }
+                req.local_cache(|| JsonFailure { field, reason: Some(serde_reason(&error)) });
+                data::Outcome::Error((Status::UnprocessableEntity, ()))
+            }
+        }
+    }
+}
+
+/// serde_json's message without its " at line L column C" suffix
+fn serde_reason(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    match message.rfind(" at line ") {
+        Some(position) => message[..position].to_string(),
+        None => message,
+    }
+}
+
+/// Path segment holding a UUID, such as a customer or drink id
+///
+/// Ids are generated with `Uuid::new_v4` and stored in the hyphenated lowercase
+/// form, so the segment is parsed and kept in that form ("3F2504E0-..." finds the
+/// same row as "3f2504e0-..."). Rocket forwards a failed parameter as a 422, so
+/// take it as `Result<UuidParam, InvalidUuid>` and `?` it to answer 400 instead.
+#[derive(Debug)]
+pub struct UuidParam(String);
+
+impl UuidParam {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+/// Path segment that is not a UUID
+#[derive(Debug)]
+pub struct InvalidUuid(String);
+
+impl From<InvalidUuid> for ApiError {
+    fn from(invalid: InvalidUuid) -> Self {
+        warn!("Validation error: id {:?} is not a UUID", invalid.0);
+        ApiError::bad_request(format!("'{}' is not a valid id", invalid.0))
+    }
+}
+
+impl<'a> FromParam<'a> for UuidParam {
+    type Error = InvalidUuid;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        Uuid::parse_str(param)
+            .map(|id| UuidParam(id.hyphenated().to_string()))
+            .map_err(|_| InvalidUuid(param.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuid_param_accepts_uuids() {
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        assert_eq!(UuidParam::from_param(id).unwrap().into_inner(), id);
+    }
+
+    #[test]
+    fn uuid_param_canonicalizes_to_lowercase_hyphenated() {
+        let canonical = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        for id in ["0F8FAD5B-D9CB-469F-A165-70867728950E", "0f8fad5bd9cb469fa16570867728950e"] {
+            assert_eq!(UuidParam::from_param(id).unwrap().into_inner(), canonical, "{}", id);
+        }
+    }
+
+    #[test]
+    fn uuid_param_rejects_other_segments() {
+        for id in ["", "42", "latte", "0f8fad5b-d9cb-469f-a165-70867728950", "0f8fad5b-d9cb-469f-a165-70867728950e-00"] {
+            let error = ApiError::from(UuidParam::from_param(id).unwrap_err());
+            assert_eq!(error.status, Status::BadRequest, "{:?}", id);
+            assert_eq!(error.message, format!("'{}' is not a valid id", id));
+        }
+    }
+}
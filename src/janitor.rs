@@ -0,0 +1,62 @@
+//! Stale order janitor
+//!
+//! Background task started at liftoff that periodically cancels `pending`
+//! orders not changed for `STALE_ORDER_TTL_MINUTES` (by `updated_at`), every
+//! `STALE_ORDER_SWEEP_INTERVAL_SECONDS`. Each cancellation goes through the
+//! regular transition rules and is written to the order status history.
+//! Disable it with `STALE_ORDER_CANCELLATION=false`.
+
+use std::time::Duration;
+
+use rocket::fairing::AdHoc;
+
+use crate::config::AppConfig;
+use crate::db::{retry_on_busy, DbConn};
+use crate::logic::orders::cancel_stale_pending_orders;
+use crate::models::infra::sqlite_types::SqliteDateTime;
+
+/// Liftoff fairing spawning the janitor task
+pub fn fairing() -> AdHoc {
+    AdHoc::on_liftoff("Stale Order Janitor", |rocket| {
+        Box::pin(async move {
+            let Some(config) = rocket.state::<AppConfig>() else {
+                return;
+            };
+
+            if !config.stale_order_cancellation {
+                info!("Stale order janitor disabled");
+                return;
+            }
+
+            let Some(pool) = DbConn::pool(rocket).cloned() else {
+                error!("Stale order janitor not started: database pool unavailable");
+                return;
+            };
+
+            let ttl = chrono::Duration::minutes(config.stale_order_ttl_minutes);
+            let period = Duration::from_secs(config.stale_order_sweep_interval_seconds.max(1));
+
+            rocket::tokio::spawn(async move {
+                let mut ticker = rocket::tokio::time::interval(period);
+                loop {
+                    ticker.tick().await;
+
+                    let Some(conn) = DbConn::from_pool(&pool).await else {
+                        warn!("Stale order janitor: no database connection available, skipping sweep");
+                        continue;
+                    };
+
+                    let cutoff = SqliteDateTime::from(chrono::Utc::now().naive_utc() - ttl);
+                    match conn.run(move |c| retry_on_busy(|| cancel_stale_pending_orders(c, cutoff))).await {
+                        Ok(cancelled) => {
+                            for order_id in cancelled {
+                                info!("Stale order janitor: cancelled pending order {}", order_id);
+                            }
+                        }
+                        Err(e) => error!("Database error cancelling stale orders: {}", e),
+                    }
+                }
+            });
+        })
+    })
+}
@@ -0,0 +1,356 @@
+//! Coffee Shop API
+//!
+//! The server is assembled by `rocket()`; the binary (src/main.rs) reads the
+//! configuration and launches it. Integration tests build the same server
+//! against a temporary database.
+
+#[macro_use] extern crate rocket;
+
+mod auth;
+mod cache;
+pub mod config;
+mod cors;
+pub mod db;
+mod error;
+mod guards;
+mod janitor;
+mod logic;
+pub mod logging;
+mod metrics;
+mod models;
+mod openapi;
+mod rate_limit;
+mod responders;
+mod routes;
+mod schema;
+mod validation;
+
+use diesel::RunQueryDsl;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rocket::{Rocket, Build, Request, State, catch, catchers};
+use rocket::figment::Figment;
+use rocket::fairing::{self, AdHoc};
+use rocket::data::{Limits, ToByteUnit};
+use rocket::http::{Header, Status};
+use rocket::response::status;
+use rocket::serde::json::{Value, json, Json};
+
+use auth::AuthFailure;
+use cache::CatalogVersion;
+use config::AppConfig;
+use cors::Cors;
+use db::DbConn;
+use guards::JsonFailure;
+use metrics::{MetricsRegistry, RequestMetrics};
+use rate_limit::{RateLimiter, RetryAfter};
+
+/// Health check endpoint (liveness probe)
+/// 
+/// Returns a simple greeting to verify the API is running.
+/// This endpoint doesn't require database access; use `/health` for readiness.
+#[get("/")]
+fn hello() -> &'static str {
+    "Coffee Shop API is running!"
+}
+
+/// Moment the server was built, used to report uptime
+struct StartedAt(Instant);
+
+/// Health report endpoint (readiness probe)
+/// 
+/// Takes a pooled connection and runs `SELECT 1`. Returns 200 with status `ok`
+/// when that works, or 503 with status `degraded` when no connection can be had
+/// or the query fails. Uptime in seconds and the crate version are included either way.
+#[get("/health")]
+async fn health(started_at: &State<StartedAt>, conn: Option<DbConn>) -> status::Custom<Json<Value>> {
+    let database_ok = match conn {
+        Some(conn) => conn
+            .run(|c| diesel::sql_query("SELECT 1").execute(c))
+            .await
+            .inspect_err(|e| error!("Health check query failed: {}", e))
+            .is_ok(),
+        None => {
+            error!("Health check could not get a database connection");
+            false
+        }
+    };
+
+    let (code, health_status) = if database_ok {
+        (Status::Ok, "ok")
+    } else {
+        (Status::ServiceUnavailable, "degraded")
+    };
+
+    status::Custom(code, Json(json!({
+        "status": health_status,
+        "uptime_seconds": started_at.0.elapsed().as_secs(),
+        "version": env!("CARGO_PKG_VERSION")
+    })))
+}
+
+/// Build information endpoint
+/// 
+/// Tells which build is deployed: crate name and version, and the git commit it was
+/// built from (`GIT_HASH`, captured by build.rs; "unknown" outside a git checkout).
+#[get("/version")]
+fn version() -> Json<Value> {
+    Json(json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "git_sha": env!("GIT_HASH")
+    }))
+}
+
+/// JSON error catcher for 400 Bad Request
+#[catch(400)]
+fn bad_request(_req: &Request) -> Json<Value> {
+    Json(json!({
+        "error": "Bad Request",
+        "message": "The request was invalid or cannot be served."
+    }))
+}
+
+/// JSON error catcher for 401 Unauthorized
+///
+/// Tells why `AuthenticatedUser` rejected the request when it did.
+#[catch(401)]
+fn unauthorized(req: &Request) -> Json<Value> {
+    let message = req
+        .local_cache(|| AuthFailure(None))
+        .0
+        .map(|reason| format!("Authentication required: {}.", reason))
+        .unwrap_or_else(|| "Authentication is required to access this resource.".to_string());
+
+    Json(json!({
+        "error": "Unauthorized",
+        "message": message
+    }))
+}
+
+/// JSON error catcher for 403 Forbidden
+///
+/// Tells which role was missing when `StaffUser` rejected the request.
+#[catch(403)]
+fn forbidden(req: &Request) -> Json<Value> {
+    let message = req
+        .local_cache(|| AuthFailure(None))
+        .0
+        .map(|reason| format!("Access denied: {}.", reason))
+        .unwrap_or_else(|| "You are not allowed to access this resource.".to_string());
+
+    Json(json!({
+        "error": "Forbidden",
+        "message": message
+    }))
+}
+
+/// JSON error catcher for 404 Not Found
+#[catch(404)]
+fn not_found(_req: &Request) -> Json<Value> {
+    Json(json!({
+        "error": "Not Found",
+        "message": "The requested resource could not be found."
+    }))
+}
+
+/// JSON error catcher for 409 Conflict
+#[catch(409)]
+fn conflict(_req: &Request) -> Json<Value> {
+    Json(json!({
+        "error": "Conflict",
+        "message": "The request conflicts with the current state of the resource."
+    }))
+}
+
+/// JSON error catcher for 413 Payload Too Large
+///
+/// Bodies over the JSON limit (`JSON_LIMIT_KIB`) are rejected whole, never truncated.
+#[catch(413)]
+fn payload_too_large(req: &Request) -> Json<Value> {
+    let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+    Json(json!({
+        "error": "Payload Too Large",
+        "message": format!("The request body exceeds the {} limit.", limit)
+    }))
+}
+
+/// JSON error catcher for 422 Unprocessable Entity
+///
+/// When `JsonBody` rejected the body, names the offending field (if any) and
+/// serde's reason instead of the generic message.
+#[catch(422)]
+fn unprocessable_entity(req: &Request) -> Json<Value> {
+    let failure = req.local_cache(JsonFailure::default);
+    let Some(reason) = &failure.reason else {
+        return Json(json!({
+            "error": "Unprocessable Entity",
+            "message": "The request was well-formed but contains invalid data or unknown fields."
+        }));
+    };
+
+    let mut body = json!({
+        "error": "Unprocessable Entity",
+        "message": reason
+    });
+    if let Some(field) = &failure.field {
+        body["field"] = json!(field);
+    }
+    Json(body)
+}
+
+/// JSON error catcher for 423 Locked (shop closed)
+#[catch(423)]
+fn locked(req: &Request) -> Json<Value> {
+    let hours = req.rocket().state::<AppConfig>().and_then(|config| config.shop_hours);
+    let message = match hours {
+        Some(hours) => hours.closed_message(),
+        None => "The resource is locked.".to_string(),
+    };
+
+    Json(json!({
+        "error": "Locked",
+        "message": message
+    }))
+}
+
+/// Response of the 429 catcher, with the `Retry-After` header
+#[derive(Responder)]
+#[response(status = 429)]
+struct TooManyRequests {
+    body: Json<Value>,
+    retry_after: Header<'static>,
+}
+
+/// JSON error catcher for 429 Too Many Requests
+///
+/// `Retry-After` tells the client how many seconds to wait before its next request.
+#[catch(429)]
+fn too_many_requests(req: &Request) -> TooManyRequests {
+    let retry_after = req.local_cache(|| RetryAfter(None)).0.unwrap_or(1);
+
+    TooManyRequests {
+        body: Json(json!({
+            "error": "Too Many Requests",
+            "message": format!("Rate limit exceeded. Retry in {} second(s).", retry_after)
+        })),
+        retry_after: Header::new("Retry-After", retry_after.to_string()),
+    }
+}
+
+/// JSON error catcher for 500 Internal Server Error
+#[catch(500)]
+fn internal_error(_req: &Request) -> Json<Value> {
+    Json(json!({
+        "error": "Internal Server Error", 
+        "message": "An unexpected error occurred while processing the request."
+    }))
+}
+
+/// Apply pending migrations, aborting ignition with a clear message on failure
+async fn run_db_migrations(rocket: Rocket<Build>) -> fairing::Result {
+    use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+    const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
+    let Some(conn) = DbConn::get_one(&rocket).await else {
+        error!("Unable to retrieve a database connection to run migrations");
+        return Err(rocket);
+    };
+
+    let result = conn
+        .run(|c| {
+            c.run_pending_migrations(MIGRATIONS)
+                .map(|applied| applied.len())
+                .map_err(|e| e.to_string())
+        })
+        .await;
+
+    match result {
+        Ok(0) => Ok(rocket),
+        Ok(applied) => {
+            info!("Applied {} database migration(s)", applied);
+            Ok(rocket)
+        }
+        Err(e) => {
+            error!("Database migrations failed: {}", e);
+            Err(rocket)
+        }
+    }
+}
+
+/// Rocket configuration for `config`
+///
+/// Rocket's own sources (Rocket.toml, `ROCKET_*` variables) with the JSON body
+/// limit and the database pool settings of `config` merged in.
+pub fn figment(config: &AppConfig) -> Figment {
+    // Explicit JSON body limit so oversized payloads fail with 413
+    let mut figment = rocket::Config::figment().merge(("limits.json", config.json_limit_kib.kibibytes()));
+    if let Some(pool_size) = config.db_pool_size {
+        figment = figment.merge(("databases.sqlite.pool_size", pool_size));
+    }
+    if let Some(timeout) = config.db_timeout_seconds {
+        figment = figment.merge(("databases.sqlite.timeout", timeout));
+    }
+    figment
+}
+
+/// The API server: routes, fairings, managed state and catchers
+pub fn rocket(config: AppConfig, figment: Figment) -> Rocket<Build> {
+    let metrics = Arc::new(MetricsRegistry::default());
+
+    rocket::build()
+        .configure(figment)
+
+        // Health check endpoints
+        .mount("/", routes![hello, health, version])
+        .mount("/", rate_limit::routes())            // target of rate-limited requests
+        .mount("/", openapi::routes())               // /openapi.json and /docs
+        .mount("/", metrics::routes())               // /metrics (Prometheus)
+        
+        // API endpoints - all mounted under /api prefix
+        .mount("/api", routes::customers::routes())  // /api/customers/*
+        .mount("/api", routes::drinks::routes())     // /api/drinks/*
+        .mount("/api", routes::employees::routes())  // /api/employees/*
+        .mount("/api", routes::extras::routes())     // /api/extras/*
+        .mount("/api", routes::orders::routes())     // /api/orders/*
+        .mount("/api", routes::menu::routes())       // /api/menu/*
+        .mount("/api", routes::checkout::routes())   // /api/checkout
+        .mount("/api", routes::auth::routes())       // /api/auth/token, /api/auth/customer-token
+        .mount("/api", routes::reports::routes())    // /api/reports/*
+        .mount("/api", cors::routes())               // OPTIONS /api/* (CORS preflight)
+        
+        // Request ids and one log line per request
+        .attach(logging::RequestLogger)
+
+        // Request counters and latencies for /metrics
+        .attach(RequestMetrics(Arc::clone(&metrics)))
+
+        // Reject clients sending too many requests before any handler runs
+        .attach(RateLimiter::from_config(&config))
+
+        // CORS headers for browser front-ends on other origins
+        .attach(Cors::from_config(&config))
+        
+        // Runtime configuration read from the environment
+        .manage(config)
+        .manage(StartedAt(Instant::now()))
+        .manage(CatalogVersion::new())
+        .manage(metrics)
+        
+        // Refuse to start without JWT_SECRET unless AUTH_DISABLED is set
+        .attach(auth::fairing())
+
+        // Create the SQLite file on first run, then open the connection pool
+        .attach(AdHoc::try_on_ignite("Database File", db::ensure_database_file))
+        .attach(DbConn::fairing())
+        
+        // Run database migrations on startup
+        .attach(AdHoc::try_on_ignite("Database Initialization", run_db_migrations))
+        
+        // Periodically cancel stale pending orders
+        .attach(janitor::fairing())
+        
+        // Register JSON error catchers
+        .register("/", catchers![bad_request, unauthorized, forbidden, not_found, conflict, payload_too_large, unprocessable_entity, locked, too_many_requests, internal_error])
+}
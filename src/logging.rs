@@ -0,0 +1,136 @@
+//! Log output
+//!
+//! Installs the global log subscriber from `LOG_LEVEL` and `LOG_FORMAT` before
+//! Rocket starts. Rocket and the handlers log through the `log` macros, which
+//! are forwarded to the subscriber; Rocket keeps its own logger only when none
+//! has been installed. Server-side failures are logged with `error!`, rejected
+//! requests (validation, conflicts, authentication) with `warn!`.
+//!
+//! `RequestLogger` tags every request with a `RequestId` (also returned in
+//! `X-Request-Id`) and logs one line per answered request. Handlers take the
+//! `RequestId` guard to prefix their own error logs with it.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Data, Request, Response};
+use uuid::Uuid;
+
+use crate::config::{AppConfig, LogFormat};
+
+/// Install the subscriber; call once, before building Rocket
+pub fn init(config: &AppConfig) {
+    let builder = tracing_subscriber::fmt().with_max_level(config.log_level);
+
+    let result = match config.log_format {
+        LogFormat::Pretty => builder.try_init(),
+        LogFormat::Json => builder.json().flatten_event(true).try_init(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Unable to install the log subscriber: {}", e);
+    }
+}
+
+/// Response header carrying the request id
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Longest client-supplied request id kept as is
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Identifier of the current request, for correlating log lines
+///
+/// Taken from the client's `X-Request-Id` when it sends a reasonable one (up to
+/// 128 visible ASCII characters), otherwise a fresh UUID. Kept in the
+/// request-local cache; as a guard it never fails. Displays as the bare id.
+#[derive(Debug, Clone)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Id of `req`, assigned on first use
+    fn of(req: &Request<'_>) -> RequestId {
+        req.local_cache(|| {
+            let id = req
+                .headers()
+                .get_one(REQUEST_ID_HEADER)
+                .map(str::trim)
+                .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()))
+                .map(String::from)
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            RequestId(id)
+        })
+        .clone()
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId::of(req))
+    }
+}
+
+/// Moment the request reached the server
+///
+/// Kept in the request-local cache by the first fairing that marks it, so
+/// `RequestLogger` and the metrics fairing measure the same span.
+pub struct ReceivedAt(Instant);
+
+impl ReceivedAt {
+    /// Record the arrival of `req`, unless already recorded
+    pub fn mark(req: &Request<'_>) {
+        req.local_cache(|| ReceivedAt(Instant::now()));
+    }
+
+    /// Time elapsed since `req` was marked (zero if it never was)
+    pub fn elapsed(req: &Request<'_>) -> Duration {
+        req.local_cache(|| ReceivedAt(Instant::now())).0.elapsed()
+    }
+}
+
+/// Fairing giving every request an id and logging it once answered
+///
+/// Sets `X-Request-Id` on every response and logs one line per request with
+/// its id, method, path, status and elapsed time.
+pub struct RequestLogger;
+
+#[rocket::async_trait]
+impl Fairing for RequestLogger {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request Logger",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        ReceivedAt::mark(req);
+        RequestId::of(req);
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let request_id = RequestId::of(req);
+        let elapsed = ReceivedAt::elapsed(req);
+
+        info!(
+            "[{}] {} {} -> {} in {:.1}ms",
+            request_id,
+            req.method(),
+            req.uri().path(),
+            res.status().code,
+            elapsed.as_secs_f64() * 1000.0
+        );
+        res.set_header(Header::new(REQUEST_ID_HEADER, request_id.0));
+    }
+}
@@ -0,0 +1,64 @@
+//! Customer rules shared by the customer and checkout routes
+
+use diesel::prelude::*;
+
+use crate::db::lower;
+use crate::error::ApiError;
+use crate::models::customer::NewCustomer;
+use crate::schema::customers;
+use crate::validation::{validate_email, validate_phone};
+
+/// Check a new customer's fields
+///
+/// Returns 400 if name or email is empty, or the email or phone is malformed.
+pub fn validate_new_customer(customer: &NewCustomer) -> Result<(), ApiError> {
+    if customer.name.trim().is_empty() {
+        warn!("Validation error: customer name cannot be empty");
+        return Err(ApiError::bad_request("customer name cannot be empty"));
+    }
+
+    if customer.email.trim().is_empty() {
+        warn!("Validation error: customer email cannot be empty");
+        return Err(ApiError::bad_request("customer email cannot be empty"));
+    }
+
+    validate_email(&customer.email)?;
+
+    match &customer.phone {
+        Some(phone) => validate_phone(phone),
+        None => Ok(()),
+    }
+}
+
+/// Whether an active customer other than `except_id` already uses `email`
+/// Emails are compared case-insensitively.
+pub fn email_in_use(c: &mut SqliteConnection, email: &str, except_id: Option<&str>) -> QueryResult<bool> {
+    let mut query = customers::table
+        .filter(lower(customers::email).eq(email.trim().to_lowercase()))
+        .filter(customers::deleted_at.is_null())
+        .into_boxed();
+
+    if let Some(id) = except_id {
+        query = query.filter(customers::id.ne(id.to_string()));
+    }
+
+    query.count().get_result::<i64>(c).map(|count| count > 0)
+}
+
+/// Fail with 409 when an active customer other than `except_id` already uses `email`
+///
+/// The message names the email, e.g. "A customer with email 'a@b.co' already exists".
+/// The UNIQUE constraint on the column remains as a backstop for races.
+pub fn ensure_email_available(c: &mut SqliteConnection, email: &str, except_id: Option<&str>) -> Result<(), ApiError> {
+    let taken = email_in_use(c, email, except_id).map_err(|e| {
+        error!("Database error checking customer email {}: {}", email, e);
+        ApiError::from(e)
+    })?;
+
+    if taken {
+        warn!("Conflict: email {} is already used by another customer", email);
+        return Err(ApiError::conflict(format!("A customer with email '{}' already exists", email)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Drink pricing and naming rules
+
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::drink_price_history::DrinkPriceChange;
+use crate::models::infra::sqlite_types::SqliteDateTime;
+use crate::schema::{drink_price_history, drinks};
+
+/// Apply a percentage change to a price, rounded to 2 decimal places
+pub fn adjust_price(price: Decimal, percent: Decimal) -> Decimal {
+    (price * (Decimal::ONE_HUNDRED + percent) / Decimal::ONE_HUNDRED).round_dp(2)
+}
+
+/// Record a drink price change in `drink_price_history`
+/// Nothing is recorded when the price did not actually change.
+pub fn record_price_change(
+    c: &mut SqliteConnection,
+    drink_id: &str,
+    old_price: Decimal,
+    new_price: Decimal,
+    changed_at: SqliteDateTime,
+) -> QueryResult<()> {
+    if old_price == new_price {
+        return Ok(());
+    }
+
+    let change = DrinkPriceChange::new(
+        Uuid::new_v4().to_string(),
+        drink_id.to_string(),
+        old_price,
+        new_price,
+        changed_at,
+    );
+    diesel::insert_into(drink_price_history::table)
+        .values(&change)
+        .execute(c)?;
+
+    Ok(())
+}
+
+/// Fail with 409 when an active drink already uses one of `names`
+///
+/// The message names the taken name, e.g. "A drink named 'Latte' already exists".
+/// Soft-deleted drinks don't count. The partial UNIQUE index on the column remains
+/// as a backstop for races.
+pub fn ensure_drink_names_available(c: &mut SqliteConnection, names: &[&str]) -> Result<(), ApiError> {
+    let taken = drinks::table
+        .filter(drinks::name.eq_any(names.to_vec()))
+        .filter(drinks::deleted_at.is_null())
+        .select(drinks::name)
+        .first::<String>(c)
+        .optional()
+        .map_err(|e| {
+            error!("Database error checking drink names: {}", e);
+            ApiError::from(e)
+        })?;
+
+    if let Some(name) = taken {
+        warn!("Conflict: drink name {} is already in use", name);
+        return Err(ApiError::conflict(format!("A drink named '{}' already exists", name)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+//! Business logic shared by route handlers and background tasks
+//!
+//! Functions here take a plain `SqliteConnection` so they can run inside
+//! `DbConn::run` closures and transactions alike.
+
+pub mod customers;
+pub mod drinks;
+pub mod orders;
@@ -0,0 +1,660 @@
+//! Order workflow
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use rocket::http::Status;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteDecimal, SqliteDrinkSizes, SqliteOrderStatus};
+use crate::models::extra::Extra;
+use crate::models::idempotency_key::IdempotencyRecord;
+use crate::models::modifier::ModifierOption;
+use crate::models::order::{
+    IncomingOrder, IncomingOrderItem, NewOrder, Order, OrderApiModel, OrderDetailApiModel, OrderProblem,
+};
+use crate::models::order_item_extras::{NewOrderItemExtra, OrderItemExtra};
+use crate::models::order_item_modifiers::{OrderItemModifier, SelectedModifierApiModel};
+use crate::models::order_items::{NewOrderItem, OrderItem, OrderItemDetailApiModel};
+use crate::models::order_status::OrderStatus;
+use crate::models::order_status_history::OrderStatusChange;
+use crate::schema::{
+    customers, drink_extras, drink_modifiers, drinks, employees, extras, idempotency_keys, modifier_options,
+    modifiers, order_item_extras, order_item_modifiers, order_items, order_status_history, orders,
+};
+
+/// Stop at the first problem, as the order-creating handlers do
+///
+/// Logs the problem and returns it as an error with its status (400 or 422).
+pub fn reject_first(problems: Vec<OrderProblem>) -> Result<(), ApiError> {
+    match problems.into_iter().next() {
+        Some(problem) => {
+            let message = format!("{}: {}", problem.field, problem.message);
+            warn!("Validation error: {}", message);
+            Err(ApiError::new(problem.status, message))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Check an order payload without touching the database
+///
+/// Covers the customer/employee ids, the items and `placed_at`. The subtotal needs
+/// the drink and extra prices, see `check_minimum_total`.
+pub fn order_problems(order: &IncomingOrder, max_skew_seconds: i64) -> Vec<OrderProblem> {
+    let mut problems = Vec::new();
+
+    if order.customer_id.trim().is_empty() {
+        problems.push(OrderProblem::invalid("customer_id", "customer_id cannot be empty"));
+    }
+
+    if order.employee_id.trim().is_empty() {
+        problems.push(OrderProblem::invalid("employee_id", "employee_id cannot be empty"));
+    }
+
+    problems.extend(item_problems(&order.items));
+    problems.extend(placed_at_problem(order.placed_at, max_skew_seconds));
+    problems
+}
+
+/// Check order items without touching the database
+///
+/// 400 if there are no items or a drink_id is empty.
+/// 422 if an item lists the same extra or modifier option more than once.
+pub fn item_problems(items: &[IncomingOrderItem]) -> Vec<OrderProblem> {
+    let mut problems = Vec::new();
+
+    if items.is_empty() {
+        problems.push(OrderProblem::invalid("items", "an order needs at least one item"));
+    }
+
+    for (index, item) in items.iter().enumerate() {
+        if item.drink_id.trim().is_empty() {
+            problems.push(OrderProblem::invalid(
+                format!("items[{}].drink_id", index),
+                "drink_id cannot be empty",
+            ));
+        }
+
+        let mut seen_extras = HashSet::new();
+        if let Some(duplicate) = item.extras.iter().find(|extra_id| !seen_extras.insert(*extra_id)) {
+            problems.push(OrderProblem::unprocessable(
+                format!("items[{}].extras", index),
+                format!("extra {} is listed more than once", duplicate),
+            ));
+        }
+
+        let mut seen_options = HashSet::new();
+        if let Some(duplicate) = item.modifiers.iter().find(|option_id| !seen_options.insert(*option_id)) {
+            problems.push(OrderProblem::unprocessable(
+                format!("items[{}].modifiers", index),
+                format!("modifier option {} is listed more than once", duplicate),
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Reject orders whose subtotal (sum of item prices) is below `MIN_ORDER_TOTAL`
+///
+/// 422 with the shortfall in the message. A zero minimum disables the check.
+pub fn minimum_total_problem(subtotal: Decimal, min_order_total: Decimal) -> Option<OrderProblem> {
+    (min_order_total > Decimal::ZERO && subtotal < min_order_total).then(|| {
+        OrderProblem::unprocessable(
+            "items",
+            format!(
+                "order subtotal {:.2} is below the minimum order total of {:.2} ({:.2} short)",
+                subtotal,
+                min_order_total,
+                min_order_total - subtotal
+            ),
+        )
+    })
+}
+
+/// Price order items from the menu
+///
+/// Each item costs its drink's `base_price` in the item's size (`DrinkSize::price_of`),
+/// plus the `extra_price` of every extra it lists. Drinks and extras are expected
+/// to have passed `reference_problems`; unknown ones count as zero.
+pub fn price_items(c: &mut SqliteConnection, items: &[IncomingOrderItem]) -> QueryResult<Vec<Decimal>> {
+    let drink_ids: Vec<&String> = items.iter().map(|item| &item.drink_id).collect();
+    let base_prices: HashMap<String, SqliteDecimal> = drinks::table
+        .filter(drinks::id.eq_any(&drink_ids))
+        .select((drinks::id, drinks::base_price))
+        .load(c)?
+        .into_iter()
+        .collect();
+
+    let extra_prices = extra_prices(c, items)?;
+
+    let prices = items
+        .iter()
+        .map(|item| {
+            let base_price = base_prices.get(&item.drink_id).map_or(Decimal::ZERO, |price| price.into_decimal());
+            let extras: Decimal = item
+                .extras
+                .iter()
+                .filter_map(|extra_id| extra_prices.get(extra_id))
+                .map(|price| price.into_decimal())
+                .sum();
+            item.size.price_of(base_price) + extras
+        })
+        .collect();
+
+    Ok(prices)
+}
+
+/// Current price of every extra listed on `items`, by extra id
+fn extra_prices(c: &mut SqliteConnection, items: &[IncomingOrderItem]) -> QueryResult<HashMap<String, SqliteDecimal>> {
+    let extra_ids: Vec<&String> = items.iter().flat_map(|item| &item.extras).collect();
+    Ok(extras::table
+        .filter(extras::id.eq_any(&extra_ids))
+        .select((extras::id, extras::extra_price))
+        .load(c)?
+        .into_iter()
+        .collect())
+}
+
+/// `minimum_total_problem` on the server-computed item prices, for the order-creating handlers
+/// Returns the problem (422), or 500 if the prices cannot be loaded.
+pub fn check_minimum_total(
+    c: &mut SqliteConnection,
+    items: &[IncomingOrderItem],
+    min_order_total: Decimal,
+) -> Result<(), ApiError> {
+    let prices = price_items(c, items).map_err(|e| {
+        error!("Database error pricing order items: {}", e);
+        Status::InternalServerError
+    })?;
+
+    reject_first(minimum_total_problem(prices.into_iter().sum(), min_order_total).into_iter().collect())
+}
+
+/// Reject client-supplied order times too far in the future
+///
+/// A small lead over the server clock (`max_skew_seconds`) is tolerated for clock
+/// drift between devices. 400 beyond that.
+pub fn placed_at_problem(placed_at: Option<DateTime<Utc>>, max_skew_seconds: i64) -> Option<OrderProblem> {
+    let placed_at = placed_at?;
+    let latest_allowed = Utc::now() + chrono::Duration::seconds(max_skew_seconds);
+
+    (placed_at > latest_allowed).then(|| {
+        OrderProblem::invalid(
+            "placed_at",
+            format!("{} is more than {}s in the future", placed_at.to_rfc3339(), max_skew_seconds),
+        )
+    })
+}
+
+/// The extras each of `drink_ids` restricts itself to in `drink_extras`
+///
+/// Drinks without rows there are missing from the map and take any extra.
+pub fn allowed_extras(c: &mut SqliteConnection, drink_ids: &[&String]) -> QueryResult<HashMap<String, HashSet<String>>> {
+    let mut allowed: HashMap<String, HashSet<String>> = HashMap::new();
+    for (drink_id, extra_id) in drink_extras::table
+        .filter(drink_extras::drink_id.eq_any(drink_ids))
+        .select((drink_extras::drink_id, drink_extras::extra_id))
+        .load::<(String, String)>(c)?
+    {
+        allowed.entry(drink_id).or_default().insert(extra_id);
+    }
+    Ok(allowed)
+}
+
+/// Whether `extra_id` may go on `drink_id`, given `allowed_extras`
+pub fn extra_allowed(allowed: &HashMap<String, HashSet<String>>, drink_id: &str, extra_id: &str) -> bool {
+    allowed.get(drink_id).is_none_or(|extras| extras.contains(extra_id))
+}
+
+/// Check what an order refers to against the database
+///
+/// All problems are 422:
+/// - the customer (when given) and the employee must exist and not be deleted
+/// - every drink must be active, available and offered in the chosen size
+/// - every extra must exist, be available and, when the drink restricts its
+///   extras in `drink_extras`, be one of them
+/// - every modifier option must exist and be available, its modifier must be
+///   offered by the drink (`drink_modifiers`), and an item picks at most one
+///   option per modifier
+///
+/// Empty ids are skipped; `order_problems`/`item_problems` report those.
+pub fn reference_problems(
+    c: &mut SqliteConnection,
+    customer_id: Option<&str>,
+    employee_id: &str,
+    items: &[IncomingOrderItem],
+) -> QueryResult<Vec<OrderProblem>> {
+    let mut problems = Vec::new();
+
+    if let Some(customer_id) = customer_id.filter(|id| !id.trim().is_empty()) {
+        let customer_exists = customers::table
+            .filter(customers::id.eq(customer_id))
+            .filter(customers::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(c)?
+            > 0;
+        if !customer_exists {
+            problems.push(OrderProblem::unprocessable(
+                "customer_id",
+                format!("customer {} does not exist", customer_id),
+            ));
+        }
+    }
+
+    if !employee_id.trim().is_empty() {
+        let employee_exists = employees::table
+            .filter(employees::id.eq(employee_id))
+            .filter(employees::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(c)?
+            > 0;
+        if !employee_exists {
+            problems.push(OrderProblem::unprocessable(
+                "employee_id",
+                format!("employee {} does not exist", employee_id),
+            ));
+        }
+    }
+
+    let drink_ids: Vec<&String> = items.iter().map(|item| &item.drink_id).collect();
+    let drink_sizes: HashMap<String, (SqliteDrinkSizes, bool)> = drinks::table
+        .filter(drinks::id.eq_any(&drink_ids))
+        .filter(drinks::deleted_at.is_null())
+        .select((drinks::id, drinks::available_sizes, drinks::is_available))
+        .load::<(String, SqliteDrinkSizes, bool)>(c)?
+        .into_iter()
+        .map(|(id, sizes, is_available)| (id, (sizes, is_available)))
+        .collect();
+
+    let extra_ids: Vec<&String> = items.iter().flat_map(|item| &item.extras).collect();
+    let extra_availability: HashMap<String, bool> = extras::table
+        .filter(extras::id.eq_any(&extra_ids))
+        .select((extras::id, extras::is_available))
+        .load(c)?
+        .into_iter()
+        .collect();
+
+    let allowed_extras = allowed_extras(c, &drink_ids)?;
+
+    let option_ids: Vec<&String> = items.iter().flat_map(|item| &item.modifiers).collect();
+    let options: HashMap<String, (ModifierOption, String)> = modifier_options::table
+        .inner_join(modifiers::table)
+        .filter(modifier_options::id.eq_any(&option_ids))
+        .select((ModifierOption::as_select(), modifiers::name))
+        .load::<(ModifierOption, String)>(c)?
+        .into_iter()
+        .map(|(option, modifier_name)| (option.id.clone(), (option, modifier_name)))
+        .collect();
+
+    let offered_modifiers: HashSet<(String, String)> = drink_modifiers::table
+        .filter(drink_modifiers::drink_id.eq_any(&drink_ids))
+        .select((drink_modifiers::drink_id, drink_modifiers::modifier_id))
+        .load(c)?
+        .into_iter()
+        .collect();
+
+    for (index, item) in items.iter().enumerate() {
+        if item.drink_id.trim().is_empty() {
+            continue;
+        }
+
+        match drink_sizes.get(&item.drink_id) {
+            None => problems.push(OrderProblem::unprocessable(
+                format!("items[{}].drink_id", index),
+                format!("drink {} does not exist", item.drink_id),
+            )),
+            Some((_, false)) => problems.push(OrderProblem::unprocessable(
+                format!("items[{}].drink_id", index),
+                format!("drink {} is unavailable", item.drink_id),
+            )),
+            Some((sizes, _)) if !sizes.contains(item.size) => problems.push(OrderProblem::unprocessable(
+                format!("items[{}].size", index),
+                format!("drink {} is not offered in size {}", item.drink_id, item.size),
+            )),
+            Some(_) => {}
+        }
+
+        for extra_id in &item.extras {
+            let field = format!("items[{}].extras", index);
+            match extra_availability.get(extra_id) {
+                None => problems.push(OrderProblem::unprocessable(field, format!("extra {} does not exist", extra_id))),
+                Some(false) => problems.push(OrderProblem::unprocessable(field, format!("extra {} is unavailable", extra_id))),
+                Some(true) => {
+                    if !extra_allowed(&allowed_extras, &item.drink_id, extra_id) {
+                        problems.push(OrderProblem::unprocessable(
+                            field,
+                            format!("extra {} cannot be added to drink {}", extra_id, item.drink_id),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut chosen_modifiers = HashSet::new();
+        for option_id in &item.modifiers {
+            let field = format!("items[{}].modifiers", index);
+            match options.get(option_id) {
+                None => problems.push(OrderProblem::unprocessable(
+                    field,
+                    format!("modifier option {} does not exist", option_id),
+                )),
+                Some((option, _)) if !option.is_available => problems.push(OrderProblem::unprocessable(
+                    field,
+                    format!("modifier option {} is unavailable", option_id),
+                )),
+                Some((option, modifier_name)) => {
+                    if !offered_modifiers.contains(&(item.drink_id.clone(), option.modifier_id.clone())) {
+                        problems.push(OrderProblem::unprocessable(
+                            field,
+                            format!("drink {} does not offer modifier {}", item.drink_id, modifier_name),
+                        ));
+                    } else if !chosen_modifiers.insert(&option.modifier_id) {
+                        problems.push(OrderProblem::unprocessable(
+                            field,
+                            format!("only one option of modifier {} can be chosen", modifier_name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// `reference_problems` for the order-creating handlers
+/// Returns the first problem (422), or 500 if the lookups fail.
+pub fn check_references(
+    c: &mut SqliteConnection,
+    customer_id: Option<&str>,
+    employee_id: &str,
+    items: &[IncomingOrderItem],
+) -> Result<(), ApiError> {
+    let problems = reference_problems(c, customer_id, employee_id, items).map_err(|e| {
+        error!("Database error validating order references: {}", e);
+        Status::InternalServerError
+    })?;
+
+    reject_first(problems)
+}
+
+/// Insert an order with its items, their extras and modifier options
+///
+/// Item prices are computed with `price_items`; the client never sets them.
+/// Generates UUIDs for every row. Modifier options are expected to have passed
+/// `reference_problems`; unknown ones are skipped. Run it inside a transaction so a failure on
+/// any row rolls the whole order back.
+pub fn insert_order(c: &mut SqliteConnection, new_order: NewOrder, items: Vec<IncomingOrderItem>) -> QueryResult<Order> {
+    let order_id = Uuid::new_v4().to_string();
+    let db_order = Order::from_new(new_order, order_id.clone());
+
+    diesel::insert_into(orders::table)
+        .values(&db_order)
+        .execute(c)?;
+
+    insert_order_items(c, &order_id, items, 0)?;
+
+    Ok(db_order)
+}
+
+/// Insert items into an order, with their extras and modifier options
+///
+/// Items are priced with `price_items` and numbered from `first_position` on, in
+/// the given sequence; each extra keeps the price it was charged at. The same expectations as `insert_order` apply: references
+/// checked beforehand, and a surrounding transaction.
+pub fn insert_order_items(
+    c: &mut SqliteConnection,
+    order_id: &str,
+    items: Vec<IncomingOrderItem>,
+    first_position: i32,
+) -> QueryResult<()> {
+    let prices = price_items(c, &items)?;
+    let extra_prices = extra_prices(c, &items)?;
+
+    let option_ids: Vec<&String> = items.iter().flat_map(|item| &item.modifiers).collect();
+    let option_modifiers: HashMap<String, String> = modifier_options::table
+        .filter(modifier_options::id.eq_any(&option_ids))
+        .select((modifier_options::id, modifier_options::modifier_id))
+        .load(c)?
+        .into_iter()
+        .collect();
+
+    for (position, (item, total_price)) in items.into_iter().zip(prices).enumerate() {
+        let item_id = Uuid::new_v4().to_string();
+        let db_item = OrderItem::from_new(
+            NewOrderItem {
+                order_id: order_id.to_string(),
+                drink_id: item.drink_id,
+                size: item.size,
+                total_price,
+                position: first_position + position as i32,
+            },
+            item_id.clone(),
+        );
+
+        diesel::insert_into(order_items::table)
+            .values(&db_item)
+            .execute(c)?;
+
+        for extra_id in item.extras {
+            let db_extra = OrderItemExtra::from_new(
+                NewOrderItemExtra {
+                    order_item_id: item_id.clone(),
+                    extra_price: extra_prices.get(&extra_id).map_or(Decimal::ZERO, |price| price.into_decimal()),
+                    extra_id,
+                },
+                Uuid::new_v4().to_string(),
+            );
+
+            diesel::insert_into(order_item_extras::table)
+                .values(&db_extra)
+                .execute(c)?;
+        }
+
+        for option_id in item.modifiers {
+            let Some(modifier_id) = option_modifiers.get(&option_id) else {
+                continue;
+            };
+            let db_modifier = OrderItemModifier {
+                id: Uuid::new_v4().to_string(),
+                order_item_id: item_id.clone(),
+                modifier_id: modifier_id.clone(),
+                modifier_option_id: option_id,
+            };
+
+            diesel::insert_into(order_item_modifiers::table)
+                .values(&db_modifier)
+                .execute(c)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// How long an `Idempotency-Key` keeps pointing at the order it created
+pub const IDEMPOTENCY_WINDOW_HOURS: i64 = 24;
+
+/// Response recorded for an idempotency key within `IDEMPOTENCY_WINDOW_HOURS`
+///
+/// This is the order as the first request returned it; keys recorded without a
+/// response fall back to the order's current state. A record older than the
+/// window is removed so the key can be used again.
+pub fn find_idempotent_response(c: &mut SqliteConnection, key: &str) -> QueryResult<Option<OrderApiModel>> {
+    let cutoff = SqliteDateTime::from(Utc::now().naive_utc() - chrono::Duration::hours(IDEMPOTENCY_WINDOW_HOURS));
+
+    diesel::delete(
+        idempotency_keys::table
+            .filter(idempotency_keys::key.eq(key))
+            .filter(idempotency_keys::created_at.lt(cutoff)),
+    )
+    .execute(c)?;
+
+    let recorded = idempotency_keys::table
+        .inner_join(orders::table)
+        .filter(idempotency_keys::key.eq(key))
+        .select((Order::as_select(), idempotency_keys::response))
+        .first::<(Order, Option<String>)>(c)
+        .optional()?;
+
+    Ok(recorded.map(|(order, response)| {
+        response
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| order.to_api_model())
+    }))
+}
+
+/// Remember that `key` created `order`, and the response it got
+///
+/// The key is the primary key, so a concurrent request that slipped past
+/// `find_idempotent_response` with the same key fails with a unique violation.
+pub fn record_idempotency_key(c: &mut SqliteConnection, key: String, order: &OrderApiModel) -> QueryResult<()> {
+    let record = IdempotencyRecord {
+        key,
+        order_id: order.id.clone(),
+        created_at: SqliteDateTime::from(Utc::now().naive_utc()),
+        response: serde_json::to_string(order).ok(),
+    };
+
+    diesel::insert_into(idempotency_keys::table)
+        .values(&record)
+        .execute(c)?;
+
+    Ok(())
+}
+
+/// Attach items, their extras and modifier options to orders
+///
+/// Loads everything with three queries regardless of the number of orders and
+/// keeps the orders in the given sequence. Each item carries the name of its
+/// drink, soft-deleted or not. Items are sorted by `position` (the
+/// sequence they were placed in), then by id, so an order always serializes the
+/// same way; extras and modifiers are sorted by name.
+pub fn load_order_details(c: &mut SqliteConnection, orders: Vec<Order>) -> QueryResult<Vec<OrderDetailApiModel>> {
+    let order_ids: Vec<&String> = orders.iter().map(|order| &order.id).collect();
+    let items = order_items::table
+        .inner_join(drinks::table)
+        .filter(order_items::order_id.eq_any(order_ids))
+        .order((order_items::position.asc(), order_items::id.asc()))
+        .select((OrderItem::as_select(), drinks::name))
+        .load::<(OrderItem, String)>(c)?;
+
+    let item_ids: Vec<&String> = items.iter().map(|(item, _)| &item.id).collect();
+    let mut extras_by_item: HashMap<String, Vec<Extra>> = HashMap::new();
+    for (item_id, extra) in order_item_extras::table
+        .inner_join(extras::table)
+        .filter(order_item_extras::order_item_id.eq_any(&item_ids))
+        .order((extras::name.asc(), extras::id.asc()))
+        .select((order_item_extras::order_item_id, Extra::as_select()))
+        .load::<(String, Extra)>(c)?
+    {
+        extras_by_item.entry(item_id).or_default().push(extra);
+    }
+
+    let mut modifiers_by_item: HashMap<String, Vec<SelectedModifierApiModel>> = HashMap::new();
+    for (item_id, modifier_id, modifier, option_id, option) in order_item_modifiers::table
+        .inner_join(modifiers::table)
+        .inner_join(modifier_options::table)
+        .filter(order_item_modifiers::order_item_id.eq_any(&item_ids))
+        .order((modifiers::name.asc(), modifiers::id.asc()))
+        .select((
+            order_item_modifiers::order_item_id,
+            modifiers::id,
+            modifiers::name,
+            modifier_options::id,
+            modifier_options::name,
+        ))
+        .load::<(String, String, String, String, String)>(c)?
+    {
+        modifiers_by_item
+            .entry(item_id)
+            .or_default()
+            .push(SelectedModifierApiModel { modifier_id, modifier, option_id, option });
+    }
+
+    let mut items_by_order: HashMap<String, Vec<OrderItemDetailApiModel>> = HashMap::new();
+    for (item, drink_name) in items {
+        let extras = extras_by_item
+            .remove(&item.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|extra| extra.to_api_model())
+            .collect();
+        let modifiers = modifiers_by_item.remove(&item.id).unwrap_or_default();
+        items_by_order
+            .entry(item.order_id.clone())
+            .or_default()
+            .push(OrderItemDetailApiModel { item: item.to_api_model(), drink_name, extras, modifiers });
+    }
+
+    Ok(orders
+        .into_iter()
+        .map(|order| OrderDetailApiModel {
+            items: items_by_order.remove(&order.id).unwrap_or_default(),
+            order: order.to_api_model(),
+        })
+        .collect())
+}
+
+/// Move an order to `next` and persist it
+///
+/// Applies the `OrderStatus::can_transition_to` rules, saves the new status and
+/// updated_at, and records the change in `order_status_history`.
+/// Returns `Ok(false)` without touching the database if the transition is illegal.
+/// Run it inside a transaction so the order row and its history stay consistent.
+pub fn transition_order(c: &mut SqliteConnection, order: &mut Order, next: OrderStatus) -> QueryResult<bool> {
+    let previous = order.status.into_order_status();
+    if !order.transition_to(next) {
+        return Ok(false);
+    }
+
+    diesel::update(orders::table.filter(orders::id.eq(&order.id)))
+        .set((
+            orders::status.eq(&order.status),
+            orders::updated_at.eq(&order.updated_at),
+        ))
+        .execute(c)?;
+
+    let change = OrderStatusChange::new(
+        Uuid::new_v4().to_string(),
+        order.id.clone(),
+        previous,
+        next,
+        order.updated_at,
+    );
+    diesel::insert_into(order_status_history::table)
+        .values(&change)
+        .execute(c)?;
+
+    Ok(true)
+}
+
+/// Cancel every pending order last changed before `cutoff`
+///
+/// Age is measured from `updated_at`, the server time of the insert or latest
+/// change: `created_at` may be a client's backdated `placed_at`, and an order
+/// synced hours after it was taken is not stale yet.
+/// Runs in one transaction and returns the ids of the cancelled orders, oldest first.
+pub fn cancel_stale_pending_orders(c: &mut SqliteConnection, cutoff: SqliteDateTime) -> QueryResult<Vec<String>> {
+    c.transaction(|tx| {
+        let stale_orders = orders::table
+            .filter(orders::status.eq(SqliteOrderStatus::from(OrderStatus::Pending)))
+            .filter(orders::updated_at.lt(cutoff))
+            .order((orders::updated_at.asc(), orders::id.asc()))
+            .select(Order::as_select())
+            .load(tx)?;
+
+        let mut cancelled = Vec::new();
+        for mut order in stale_orders {
+            if transition_order(tx, &mut order, OrderStatus::Cancelled)? {
+                cancelled.push(order.id);
+            }
+        }
+
+        Ok(cancelled)
+    })
+}
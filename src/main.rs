@@ -1,100 +1,26 @@
-#[macro_use] extern crate diesel;
-#[macro_use] extern crate rocket;
-
-mod db;
-mod models;
-mod routes;
-mod schema;
+//! Server binary: reads the configuration from the environment and launches the API
 
 use dotenvy::dotenv;
-use rocket::{Rocket, Build, Request, catch, catchers};
-use rocket::fairing::AdHoc;
-use rocket::http::Status;
-use rocket::serde::json::{Value, json, Json};
-use rocket_sync_db_pools::database;
-
-#[database("sqlite")]
-struct DbConn(diesel::SqliteConnection);
-
-/// Health check endpoint
-/// 
-/// Returns a simple greeting to verify the API is running.
-/// This endpoint doesn't require database access.
-#[get("/")]
-fn hello() -> &'static str {
-    "Coffee Shop API is running!"
-}
-
-/// JSON error catcher for 400 Bad Request
-#[catch(400)]
-fn bad_request(_req: &Request) -> Json<Value> {
-    Json(json!({
-        "error": "Bad Request",
-        "message": "The request was invalid or cannot be served."
-    }))
-}
-
-/// JSON error catcher for 404 Not Found
-#[catch(404)]
-fn not_found(_req: &Request) -> Json<Value> {
-    Json(json!({
-        "error": "Not Found",
-        "message": "The requested resource could not be found."
-    }))
-}
-
-/// JSON error catcher for 422 Unprocessable Entity
-#[catch(422)]
-fn unprocessable_entity(_req: &Request) -> Json<Value> {
-    Json(json!({
-        "error": "Unprocessable Entity",
-        "message": "The request was well-formed but contains invalid data or unknown fields."
-    }))
-}
-
-/// JSON error catcher for 500 Internal Server Error
-#[catch(500)]
-fn internal_error(_req: &Request) -> Json<Value> {
-    Json(json!({
-        "error": "Internal Server Error", 
-        "message": "An unexpected error occurred while processing the request."
-    }))
-}
 
-async fn run_db_migrations(rocket: Rocket<Build>) -> Rocket<Build> {
-    use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-    const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
-
-    DbConn::get_one(&rocket)
-        .await
-        .expect("unable to retrieve connection").run(|c| {
-        c.run_pending_migrations(MIGRATIONS).expect("migrations failed");
-    }).await;
-
-    rocket
-}
+use coffeeshop_rocket_api::config::AppConfig;
+use coffeeshop_rocket_api::{db, logging};
 
 #[rocket::main]
 async fn main() {
     dotenv().ok(); // loads .env into process environment
+    let config = AppConfig::from_env();
+    logging::init(&config);
+
+    let figment = coffeeshop_rocket_api::figment(&config);
 
-    let _ = rocket::build()
-        // Health check endpoint
-        .mount("/", routes![hello])
-        
-        // API endpoints - all mounted under /api prefix
-        .mount("/api", routes::customers::routes())  // /api/customers/*
-        .mount("/api", routes::drinks::routes())     // /api/drinks/*
-        
-        // Database connection pool
-        .attach(DbConn::fairing())
-        
-        // Run database migrations on startup
-        .attach(AdHoc::on_ignite("Database Initialization", run_db_migrations))
-        
-        // Register JSON error catchers
-        .register("/", catchers![bad_request, not_found, unprocessable_entity, internal_error])
-        
-        .launch()
-        .await;
-}
\ No newline at end of file
+    // Fail fast with a clear message instead of a cascade of fairing errors
+    if db::database_url(&figment).is_none() {
+        rocket::error!("{}", db::MISSING_DATABASE_HELP);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = coffeeshop_rocket_api::rocket(config, figment).launch().await {
+        rocket::error!("Startup failed: {}", e.pretty_print());
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,155 @@
+//! Prometheus metrics
+//!
+//! `RequestMetrics` records every answered request in a shared `MetricsRegistry`,
+//! which GET /metrics renders in the Prometheus text exposition format:
+//! - `http_requests_total{method,path,status}`: counter of answered requests
+//! - `http_request_duration_seconds{method,path}`: histogram of their latency
+//!
+//! `path` is the matched route (e.g. `/api/customers/<customer_id>`), not the raw
+//! URI, so ids don't create a series each; requests matching no route count as
+//! `unmatched`. Scrapes of /metrics are not recorded. The registry lives in memory
+//! only, so counters reset on restart as Prometheus expects.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::ContentType;
+use rocket::{get, routes, Data, Request, Response, Route, State};
+
+use crate::logging::ReceivedAt;
+
+/// Path of the scrape endpoint, excluded from the recorded requests
+const METRICS_PATH: &str = "/metrics";
+
+/// Upper bounds of the latency histogram buckets, in seconds (Prometheus defaults)
+const DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Latency histogram of one method and route
+#[derive(Default)]
+struct Histogram {
+    /// Observations at or below each of `DURATION_BUCKETS`, cumulative
+    buckets: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Default)]
+struct Series {
+    /// Requests by (method, path, status)
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// Latency by (method, path)
+    durations: BTreeMap<(String, String), Histogram>,
+}
+
+/// Request counters and latencies, shared by the fairing and GET /metrics
+#[derive(Default)]
+pub struct MetricsRegistry {
+    series: Mutex<Series>,
+}
+
+impl MetricsRegistry {
+    /// Record one answered request
+    fn record(&self, method: &str, path: &str, status: u16, elapsed: Duration) {
+        let mut series = self.series.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *series.requests.entry((method.to_string(), path.to_string(), status)).or_default() += 1;
+        series
+            .durations
+            .entry((method.to_string(), path.to_string()))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Render every series in the Prometheus text format
+    fn render(&self) -> String {
+        let series = self.series.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Requests answered, by method, route and status.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for ((method, path, status), count) in &series.requests {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",path=\"{}\",status=\"{}\"}} {}",
+                method,
+                escape_label(path),
+                status,
+                count
+            );
+        }
+
+        out.push_str("# HELP http_request_duration_seconds Time to answer a request, by method and route.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for ((method, path), histogram) in &series.durations {
+            let labels = format!("method=\"{}\",path=\"{}\"", method, escape_label(path));
+            for (count, bound) in histogram.buckets.iter().zip(DURATION_BUCKETS) {
+                let _ = writeln!(out, "http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}", labels, bound, count);
+            }
+            let _ = writeln!(out, "http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}", labels, histogram.count);
+            let _ = writeln!(out, "http_request_duration_seconds_sum{{{}}} {}", labels, histogram.sum);
+            let _ = writeln!(out, "http_request_duration_seconds_count{{{}}} {}", labels, histogram.count);
+        }
+
+        out
+    }
+}
+
+/// Escape a label value: backslash, double quote and line feed
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Fairing recording each answered request in the registry
+pub struct RequestMetrics(pub Arc<MetricsRegistry>);
+
+#[rocket::async_trait]
+impl Fairing for RequestMetrics {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request Metrics",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        ReceivedAt::mark(req);
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let path = match req.route() {
+            Some(route) => route.uri.path(),
+            None => "unmatched",
+        };
+        if path == METRICS_PATH {
+            return;
+        }
+
+        self.0.record(req.method().as_str(), path, res.status().code, ReceivedAt::elapsed(req));
+    }
+}
+
+/// Current metrics in the Prometheus text format (version 0.0.4)
+#[get("/metrics")]
+pub fn metrics(registry: &State<Arc<MetricsRegistry>>) -> (ContentType, String) {
+    let content_type = ContentType::new("text", "plain").with_params([("version", "0.0.4"), ("charset", "utf-8")]);
+    (content_type, registry.render())
+}
+
+/// Scrape endpoint, to be mounted under "/"
+pub fn routes() -> Vec<Route> {
+    routes![metrics]
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+// Role carried in a token's `role` claim
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Employee,
+    // Tokens without a role claim get the least privileged one
+    #[default]
+    Customer,
+}
+
+// input model (for POST /auth/token); the role is not client-supplied
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenRequest {
+    pub username: String,
+    pub password: String,
+}
+
+// input model (for POST /auth/customer-token)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomerTokenRequest {
+    pub customer_id: String,
+}
+
+// Issued bearer token; expires_in is in seconds
+#[derive(Debug, Serialize)]
+pub struct TokenApiModel {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    pub role: Role,
+}
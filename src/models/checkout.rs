@@ -0,0 +1,27 @@
+use crate::models::customer::{CustomerApiModel, NewCustomer};
+use crate::models::order::{IncomingOrderItem, OrderApiModel};
+use crate::models::order_status::InitialOrderStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// Input model for walk-in checkout: a new customer and their first order
+// status is optional (defaults to pending) and may only be pending or paid
+#[derive(Debug, Deserialize)]
+pub struct IncomingCheckout {
+    pub customer: NewCustomer,
+    pub employee_id: String,
+    #[serde(default)]
+    pub status: InitialOrderStatus,
+    /// RFC 3339 time the order was taken; defaults to now
+    #[serde(default)]
+    pub placed_at: Option<DateTime<Utc>>,
+    pub items: Vec<IncomingOrderItem>,
+}
+
+// API representation: the created order with its (new) customer embedded
+#[derive(Debug, Serialize)]
+pub struct CheckoutApiModel {
+    #[serde(flatten)]
+    pub order: OrderApiModel,
+    pub customer: CustomerApiModel,
+}
@@ -1,7 +1,9 @@
 use crate::schema::customers;
+use crate::models::infra::patch::Patch;
 use crate::models::infra::sqlite_types::SqliteDateTime;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use diesel::prelude::*;
 
 // Database model (used for querying and inserting)
@@ -15,10 +17,11 @@ pub struct Customer {
     pub created_at: SqliteDateTime,
     pub updated_at: SqliteDateTime,
     pub deleted_at: Option<SqliteDateTime>,
+    pub phone: Option<String>,
 }
 
 // API representation (for serialization/deserialization)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CustomerApiModel {
     pub id: String,
     pub name: String,
@@ -30,23 +33,40 @@ pub struct CustomerApiModel {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "crate::models::infra::sqlite_types::datetime_format_option")]
     pub deleted_at: Option<NaiveDateTime>,
+    pub phone: Option<String>,
 }
 
 // Input model (for creating customers)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct NewCustomer {
     pub name: String,
     pub email: String,
+    #[serde(default)]
+    pub phone: Option<String>,
 }
 
 // Input model (for updating customers)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct UpdateCustomer {
     pub name: String,
     pub email: String,
 }
 
+// Input model (for partially updating customers); omitted fields are left unchanged,
+// a null phone clears it
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatchCustomer {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub phone: Patch<String>,
+}
+
 impl Customer {
     /// Convert to API-friendly model
     pub fn to_api_model(&self) -> CustomerApiModel {
@@ -57,6 +77,7 @@ impl Customer {
             created_at: self.created_at.into_naive_date_time(),
             updated_at: self.updated_at.into_naive_date_time(),
             deleted_at: self.deleted_at.map(|dt| dt.into_naive_date_time()),
+            phone: self.phone.clone(),
         }
     }
 
@@ -70,6 +91,7 @@ impl Customer {
             created_at: SqliteDateTime::from(now),
             updated_at: SqliteDateTime::from(now),
             deleted_at: None,
+            phone: new.phone.map(|phone| phone.trim().to_string()),
         }
     }
     
@@ -78,7 +100,43 @@ impl Customer {
     pub fn update_from_input(&mut self, update_data: UpdateCustomer) {
         self.name = update_data.name;
         self.email = update_data.email;
-        self.updated_at = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Apply the fields present in a patch
+    pub fn apply_patch(&mut self, patch: PatchCustomer) {
+        if let Some(name) = patch.name {
+            self.name = name;
+        }
+        if let Some(email) = patch.email {
+            self.email = email;
+        }
+        let phone = match patch.phone {
+            Patch::Value(phone) => Patch::Value(phone.trim().to_string()),
+            other => other,
+        };
+        phone.apply_to(&mut self.phone);
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Replace personal data with placeholders (GDPR erasure)
+    /// The id is kept so order history still resolves. The customer is soft-deleted
+    /// if it wasn't already; an existing deleted_at is preserved.
+    pub fn anonymize(&mut self) {
+        let now = SqliteDateTime::updated_now(self.created_at);
+        let short_id: String = self.id.chars().take(8).collect();
+        self.name = format!("anonymized-{}", short_id);
+        // The full id keeps the placeholder unique under the email UNIQUE constraint
+        self.email = format!("anonymized-{}@anonymized.invalid", self.id);
+        self.phone = None;
+        self.updated_at = now;
+        self.deleted_at.get_or_insert(now);
+    }
+
+    /// Undo a soft delete, bumping updated_at
+    pub fn restore(&mut self) {
+        self.deleted_at = None;
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
     }
 }
 
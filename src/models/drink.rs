@@ -1,8 +1,11 @@
 use crate::schema::drinks;
-use crate::models::infra::sqlite_types::{SqliteDecimal, SqliteDateTime};
+use crate::models::drink_size::DrinkSize;
+use crate::models::infra::patch::Patch;
+use crate::models::infra::sqlite_types::{SqliteDecimal, SqliteDateTime, SqliteDrinkSizes};
 use chrono::NaiveDateTime;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use diesel::prelude::*;
 
 // database model (used for querying and inserting)
@@ -16,14 +19,17 @@ pub struct Drink {
     pub created_at: SqliteDateTime,
     pub updated_at: SqliteDateTime,
     pub deleted_at: Option<SqliteDateTime>,
+    pub available_sizes: SqliteDrinkSizes,
+    pub category: Option<String>,
+    pub is_available: bool,
 }
 
 // API representation (for serialization/deserialization)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DrinkApiModel {
     pub id: String,
     pub name: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub base_price: Decimal,
     #[serde(with = "crate::models::infra::sqlite_types::datetime_format")]
     pub created_at: NaiveDateTime,
@@ -32,25 +38,103 @@ pub struct DrinkApiModel {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default, with = "crate::models::infra::sqlite_types::datetime_format_option")]
     pub deleted_at: Option<NaiveDateTime>,
+    pub available_sizes: Vec<DrinkSize>,
+    pub category: Option<String>,
+    pub is_available: bool,
 }
 
 // input model (for creating drinks)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct NewDrink {
     pub name: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub base_price: Decimal,
+    /// Sizes the drink can be ordered in; every size when omitted
+    #[serde(default = "all_sizes")]
+    pub available_sizes: Vec<DrinkSize>,
+    /// Optional menu grouping, case-insensitive (stored lowercase)
+    #[serde(default)]
+    pub category: Option<String>,
 }
 
 // input model (for updating drinks)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(deny_unknown_fields)]
 pub struct UpdateDrink {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub base_price: Decimal,
+    /// New set of sizes; the current sizes are kept when omitted
+    #[serde(default)]
+    pub available_sizes: Option<Vec<DrinkSize>>,
+}
+
+// input model (for partially updating drinks); omitted fields are left unchanged
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatchDrink {
+    #[serde(default, with = "crate::models::infra::sqlite_types::decimal_format_option")]
+    pub base_price: Option<Decimal>,
+    #[serde(default)]
+    pub available_sizes: Option<Vec<DrinkSize>>,
+    /// `null` (or a blank string) removes the drink from its category
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub category: Patch<String>,
+}
+
+// input model (for switching a drink's availability)
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateDrinkAvailability {
+    pub is_available: bool,
+}
+
+// input model (for adjusting every drink's price at once)
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BulkPriceAdjustment {
+    /// Percentage to apply, e.g. 5 for +5% or -10 for a 10% cut
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
+    pub percent: Decimal,
+    /// Only adjust drinks in this category (case-insensitive); all drinks when omitted
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+// result of a bulk price adjustment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkPriceAdjustmentApiModel {
+    pub updated: usize,
+}
+
+// price of a drink in one size (GET /drinks/<id>/sizes)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DrinkSizePriceApiModel {
+    pub size: DrinkSize,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub price: Decimal,
+}
+
+/// Normalize a category: trimmed, lowercase, `None` when blank
+pub fn normalize_category(category: Option<String>) -> Option<String> {
+    category.map(|category| category.trim().to_lowercase()).filter(|category| !category.is_empty())
+}
+
+fn all_sizes() -> Vec<DrinkSize> {
+    DrinkSize::ALL.to_vec()
 }
 
 impl Drink {
+    /// Price in every size the drink is offered in, in menu order
+    pub fn size_prices(&self) -> Vec<DrinkSizePriceApiModel> {
+        let base_price = self.base_price.into_decimal();
+        DrinkSize::ALL
+            .into_iter()
+            .filter(|size| self.available_sizes.contains(*size))
+            .map(|size| DrinkSizePriceApiModel { size, price: size.price_of(base_price) })
+            .collect()
+    }
+
     /// convert to API-friendly model
     pub fn to_api_model(&self) -> DrinkApiModel {
         DrinkApiModel {
@@ -60,6 +144,9 @@ impl Drink {
             created_at: self.created_at.into_naive_date_time(),
             updated_at: self.updated_at.into_naive_date_time(),
             deleted_at: self.deleted_at.map(|dt| dt.into_naive_date_time()),
+            available_sizes: self.available_sizes.clone().into_drink_sizes(),
+            category: self.category.clone(),
+            is_available: self.is_available,
         }
     }
 
@@ -73,14 +160,51 @@ impl Drink {
             created_at: SqliteDateTime::from(now),
             updated_at: SqliteDateTime::from(now),
             deleted_at: None,
+            available_sizes: SqliteDrinkSizes::from(new.available_sizes),
+            category: normalize_category(new.category),
+            is_available: true,
         }
     }
     
     /// Update an existing drink with new data
-    /// Only updates base_price and updated_at. Name is immutable, created_at remains unchanged.
+    /// Only updates base_price, available_sizes (when given) and updated_at.
+    /// Name is immutable, created_at remains unchanged.
     pub fn update_from_input(&mut self, update_data: UpdateDrink) {
         self.base_price = SqliteDecimal::from(update_data.base_price);
-        self.updated_at = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+        if let Some(sizes) = update_data.available_sizes {
+            self.available_sizes = SqliteDrinkSizes::from(sizes);
+        }
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Apply a partial update
+    /// Only the fields present in the patch change; updated_at is refreshed.
+    pub fn apply_patch(&mut self, patch: PatchDrink) {
+        if let Some(base_price) = patch.base_price {
+            self.base_price = SqliteDecimal::from(base_price);
+        }
+        if let Some(sizes) = patch.available_sizes {
+            self.available_sizes = SqliteDrinkSizes::from(sizes);
+        }
+        // A blank category normalizes to nothing and clears it, like null
+        let category = match patch.category {
+            Patch::Value(category) => normalize_category(Some(category)).map_or(Patch::Null, Patch::Value),
+            other => other,
+        };
+        category.apply_to(&mut self.category);
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Mark the drink as orderable or not; updated_at is refreshed
+    pub fn set_availability(&mut self, is_available: bool) {
+        self.is_available = is_available;
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Undo a soft delete, bumping updated_at
+    pub fn restore(&mut self) {
+        self.deleted_at = None;
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
     }
 }
 
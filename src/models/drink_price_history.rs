@@ -0,0 +1,29 @@
+use crate::schema::drink_price_history;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteDecimal};
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+
+// Database model: one row per drink price change
+#[derive(Queryable, Insertable, Selectable, Debug)]
+#[diesel(table_name = drink_price_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct DrinkPriceChange {
+    pub id: String,
+    pub drink_id: String,
+    pub old_price: SqliteDecimal,
+    pub new_price: SqliteDecimal,
+    pub changed_at: SqliteDateTime,
+}
+
+impl DrinkPriceChange {
+    /// Record a price change of `drink_id`
+    pub fn new(id: String, drink_id: String, old_price: Decimal, new_price: Decimal, changed_at: SqliteDateTime) -> Self {
+        DrinkPriceChange {
+            id,
+            drink_id,
+            old_price: SqliteDecimal::from(old_price),
+            new_price: SqliteDecimal::from(new_price),
+            changed_at,
+        }
+    }
+}
@@ -1,8 +1,10 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DrinkSize {
     Small,
@@ -11,6 +13,26 @@ pub enum DrinkSize {
     Standard,
 }
 
+impl DrinkSize {
+    /// Every size, in menu order
+    pub const ALL: [DrinkSize; 4] = [DrinkSize::Small, DrinkSize::Medium, DrinkSize::Large, DrinkSize::Standard];
+
+    /// Factor applied to a drink's base price for this size
+    pub fn price_multiplier(&self) -> Decimal {
+        match self {
+            DrinkSize::Small => Decimal::new(8, 1),
+            DrinkSize::Medium => Decimal::ONE,
+            DrinkSize::Large => Decimal::new(13, 1),
+            DrinkSize::Standard => Decimal::ONE,
+        }
+    }
+
+    /// Price of a drink costing `base_price` in this size, rounded to cents
+    pub fn price_of(&self, base_price: Decimal) -> Decimal {
+        (base_price * self.price_multiplier()).round_dp(2)
+    }
+}
+
 // Implement FromStr for conversion from string
 impl FromStr for DrinkSize {
     type Err = String;
@@ -36,4 +58,29 @@ impl fmt::Display for DrinkSize {
             DrinkSize::Standard => write!(f, "standard"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_of_applies_the_size_multiplier() {
+        let base = Decimal::new(300, 2);
+        let expected = [
+            (DrinkSize::Small, Decimal::new(240, 2)),
+            (DrinkSize::Medium, Decimal::new(300, 2)),
+            (DrinkSize::Large, Decimal::new(390, 2)),
+            (DrinkSize::Standard, Decimal::new(300, 2)),
+        ];
+        for (size, price) in expected {
+            assert_eq!(size.price_of(base), price, "{} of a 3.00 drink", size);
+        }
+    }
+
+    #[test]
+    fn price_of_rounds_to_cents() {
+        // 2.55 × 1.3 = 3.315 and 1.99 × 0.8 = 1.592
+        assert_eq!(DrinkSize::Large.price_of(Decimal::new(255, 2)), Decimal::new(332, 2));
+        assert_eq!(DrinkSize::Small.price_of(Decimal::new(199, 2)), Decimal::new(159, 2));
+    }
+}
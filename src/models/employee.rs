@@ -88,7 +88,7 @@ impl Employee {
     pub fn update_from_input(&mut self, update_data: UpdateEmployee) {
         self.name = update_data.name;
         self.email = update_data.email;
-        self.updated_at = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
     }
 }
 
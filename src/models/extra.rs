@@ -2,11 +2,13 @@ use crate::schema::extras;
 use crate::models::infra::sqlite_types::SqliteDecimal;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use diesel::prelude::*;
 
 // Database model (used for querying and inserting)
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Selectable, Debug)]
 #[diesel(table_name = extras)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct Extra {
     pub id: String,
     pub name: String,
@@ -15,11 +17,11 @@ pub struct Extra {
 }
 
 // API representation (for serialization/deserialization)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExtraApiModel {
     pub id: String,
     pub name: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub extra_price: Decimal,
     pub is_available: bool,
 }
@@ -28,11 +30,22 @@ pub struct ExtraApiModel {
 #[derive(Debug, Deserialize)]
 pub struct NewExtra {
     pub name: String,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub extra_price: Decimal,
     pub is_available: Option<bool>, // Optional with default
 }
 
+// Input model (for updating extras); names are immutable
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateExtra {
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
+    pub extra_price: Decimal,
+    /// New availability; the current one is kept when omitted
+    #[serde(default)]
+    pub is_available: Option<bool>,
+}
+
 impl Extra {
     /// Convert to API-friendly model
     pub fn to_api_model(&self) -> ExtraApiModel {
@@ -53,6 +66,14 @@ impl Extra {
             is_available: new.is_available.unwrap_or(true), // Default to available
         }
     }
+
+    /// Apply an update, keeping the availability when it is not given
+    pub fn update_from_input(&mut self, update: UpdateExtra) {
+        self.extra_price = SqliteDecimal::from(update.extra_price);
+        if let Some(is_available) = update.is_available {
+            self.is_available = is_available;
+        }
+    }
 }
 
 // Conversion for query results
@@ -61,25 +82,3 @@ impl From<Extra> for ExtraApiModel {
         extra.to_api_model()
     }
 }
-
-/*
-// Example usage in route handler
-#[post("/extras", data = "<new_extra>")]
-async fn create_extra(
-    conn: DbConn,
-    new_extra: Json<NewExtra>,
-) -> Result<Json<ExtraApiModel>, Status> {
-    let id = Uuid::new_v4().to_string();
-    let db_extra = Extra::from_new(new_extra.into_inner(), id);
-    
-    conn.run(|c| {
-        diesel::insert_into(extras::table)
-            .values(&db_extra)
-            .execute(c)
-            .map_err(|_| Status::InternalServerError)?;
-            
-        Ok(Json(db_extra.to_api_model()))
-    })
-    .await
-}
- */
\ No newline at end of file
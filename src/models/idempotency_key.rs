@@ -0,0 +1,16 @@
+use crate::schema::idempotency_keys;
+use crate::models::infra::sqlite_types::SqliteDateTime;
+use diesel::prelude::*;
+
+// Database model: the order created by a request carrying an Idempotency-Key
+#[derive(Queryable, Insertable, Selectable, Debug)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub order_id: String,
+    pub created_at: SqliteDateTime,
+    /// JSON body of the 201 the first request got; `None` on keys recorded
+    /// before responses were kept
+    pub response: Option<String>,
+}
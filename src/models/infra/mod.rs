@@ -1 +1 @@
-pub mod sqlite_types;
\ No newline at end of file
+pub mod sqlite_types;pub mod patch;
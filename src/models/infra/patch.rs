@@ -0,0 +1,80 @@
+//! Field of a partial update (PATCH body)
+//!
+//! JSON distinguishes a missing field from an explicit `null`, but `Option<T>`
+//! does not. `Patch<T>` keeps the three cases apart for nullable columns:
+//! - absent: leave the stored value unchanged
+//! - `null`: clear the stored value
+//! - a value: replace the stored value
+//!
+//! Declare the field with `#[serde(default)]` so a missing key becomes `Absent`.
+
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    /// Apply the change to the stored value
+    pub fn apply_to(self, current: &mut Option<T>) {
+        match self {
+            Patch::Absent => {}
+            Patch::Null => *current = None,
+            Patch::Value(value) => *current = Some(value),
+        }
+    }
+}
+
+// Only called when the key is present: `null` becomes Null, anything else Value
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(|value| match value {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Body {
+        #[serde(default)]
+        phone: Patch<String>,
+    }
+
+    fn parse(json: &str) -> Patch<String> {
+        serde_json::from_str::<Body>(json).unwrap().phone
+    }
+
+    #[test]
+    fn absent_null_and_value_are_told_apart() {
+        assert_eq!(parse("{}"), Patch::Absent);
+        assert_eq!(parse(r#"{"phone": null}"#), Patch::Null);
+        assert_eq!(parse(r#"{"phone": "555-0100"}"#), Patch::Value("555-0100".to_string()));
+    }
+
+    #[test]
+    fn apply_to_keeps_clears_or_replaces() {
+        let stored = || Some("555-0100".to_string());
+
+        let mut phone = stored();
+        Patch::Absent.apply_to(&mut phone);
+        assert_eq!(phone, stored());
+
+        Patch::Null.apply_to(&mut phone);
+        assert_eq!(phone, None);
+
+        Patch::Value("555-0199".to_string()).apply_to(&mut phone);
+        assert_eq!(phone, Some("555-0199".to_string()));
+    }
+}
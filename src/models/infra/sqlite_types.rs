@@ -1,5 +1,5 @@
 use rust_decimal::Decimal;
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use diesel::{
     sql_types::Text,
     deserialize::{self, FromSql},
@@ -47,16 +47,15 @@ impl SqliteDecimal {
 }
 
 // custom type for NaiveDateTime
-#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, AsExpression, FromSqlRow)]
 #[diesel(sql_type = Text)]
 pub struct SqliteDateTime(pub NaiveDateTime);
 
 impl FromSql<Text, Sqlite> for SqliteDateTime {
     fn from_sql(value: diesel::sqlite::SqliteValue) -> deserialize::Result<Self> {
         let s = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
-        NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S%.f")
-            .map(SqliteDateTime)
-            .map_err(Into::into)
+        SqliteDateTime::parse_stored(&s)
+            .ok_or_else(|| format!("'{}' is not a valid timestamp", s).into())
     }
 }
 
@@ -77,6 +76,66 @@ impl SqliteDateTime {
     pub fn into_naive_date_time(self) -> NaiveDateTime {
         self.0
     }
+
+    /// Build from milliseconds since the Unix epoch (UTC)
+    pub fn from_epoch_millis(millis: i64) -> Option<SqliteDateTime> {
+        chrono::DateTime::from_timestamp_millis(millis).map(|dt| SqliteDateTime(dt.naive_utc()))
+    }
+
+    /// Parse a timestamp read from the database
+    /// Values written by this API use `%Y-%m-%dT%H:%M:%S%.f` in UTC. Rows written
+    /// by other tools may end in `Z` or carry an offset; those are read as RFC 3339
+    /// and converted to UTC.
+    fn parse_stored(s: &str) -> Option<SqliteDateTime> {
+        const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+        NaiveDateTime::parse_from_str(s, FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(s.strip_suffix('Z').unwrap_or(s), FORMAT))
+            .or_else(|_| chrono::DateTime::parse_from_rfc3339(s).map(|dt| dt.naive_utc()))
+            .ok()
+            .map(SqliteDateTime)
+    }
+
+    /// Parse a timestamp given by a client (e.g. a query parameter)
+    /// Accepts RFC 3339 with an offset (converted to UTC), a naive UTC
+    /// `YYYY-MM-DDTHH:MM:SS[.fff]` value or epoch milliseconds.
+    pub fn parse(s: &str) -> Option<SqliteDateTime> {
+        let s = s.trim();
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Some(SqliteDateTime(dt.naive_utc()));
+        }
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+            return Some(SqliteDateTime(dt));
+        }
+        s.parse().ok().and_then(SqliteDateTime::from_epoch_millis)
+    }
+
+    /// UTC bounds `[start, end)` of a calendar day in the given local offset
+    pub fn day_bounds(date: NaiveDate, offset: FixedOffset) -> (SqliteDateTime, SqliteDateTime) {
+        let local_midnight = date.and_time(NaiveTime::MIN);
+        let start = local_midnight - offset;
+        (SqliteDateTime(start), SqliteDateTime(start + chrono::Duration::days(1)))
+    }
+
+    /// Current time as the `updated_at` of a row created at `created_at`
+    pub fn updated_now(created_at: SqliteDateTime) -> SqliteDateTime {
+        SqliteDateTime::from(chrono::Utc::now().naive_utc()).not_before(created_at)
+    }
+
+    /// Enforce the `updated_at >= created_at` invariant
+    /// A clock running behind the stored creation time would otherwise produce an
+    /// update that predates the row; the value is clamped to `floor` and logged.
+    pub fn not_before(self, floor: SqliteDateTime) -> SqliteDateTime {
+        if self < floor {
+            error!(
+                "Invariant violation: updated_at {} precedes created_at {}, clamping",
+                self.0, floor.0
+            );
+            floor
+        } else {
+            self
+        }
+    }
 }
 
 // custom type for NaiveDate
@@ -144,6 +203,49 @@ impl SqliteDrinkSize {
     }
 }
 
+// custom type for a set of drink sizes, stored comma-separated (e.g. "small,medium,large")
+#[derive(Debug, Clone, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub struct SqliteDrinkSizes(pub Vec<DrinkSize>);
+
+impl FromSql<Text, Sqlite> for SqliteDrinkSizes {
+    fn from_sql(value: diesel::sqlite::SqliteValue) -> deserialize::Result<Self> {
+        let s = <String as FromSql<Text, Sqlite>>::from_sql(value)?;
+        s.split(',')
+            .map(str::trim)
+            .filter(|size| !size.is_empty())
+            .map(DrinkSize::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(SqliteDrinkSizes)
+            .map_err(|e| e.into())
+    }
+}
+
+impl ToSql<Text, Sqlite> for SqliteDrinkSizes {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let s = self.0.iter().map(|size| size.to_string()).collect::<Vec<_>>().join(",");
+        out.set_value(s);
+        Ok(serialize::IsNull::No)
+    }
+}
+
+impl From<Vec<DrinkSize>> for SqliteDrinkSizes {
+    /// Duplicates are dropped and sizes kept in `DrinkSize::ALL` order
+    fn from(value: Vec<DrinkSize>) -> Self {
+        SqliteDrinkSizes(DrinkSize::ALL.into_iter().filter(|size| value.contains(size)).collect())
+    }
+}
+
+impl SqliteDrinkSizes {
+    pub fn into_drink_sizes(self) -> Vec<DrinkSize> {
+        self.0
+    }
+
+    pub fn contains(&self, size: DrinkSize) -> bool {
+        self.0.contains(&size)
+    }
+}
+
 #[derive(Debug, Clone, Copy, AsExpression, FromSqlRow)]
 #[diesel(sql_type = Text)]
 pub struct SqliteOrderStatus(pub OrderStatus);
@@ -254,4 +356,211 @@ pub mod datetime_format_option {
             None => Ok(None),
         }
     }
-}
\ No newline at end of file
+}
+// Decimal input accepting either a JSON string ("4.00") or a JSON number (4.00).
+// Values are always serialized back as strings to keep full precision.
+pub mod decimal_format {
+    use rust_decimal::Decimal;
+    use serde::{self, de, Deserializer, Serializer};
+    use std::fmt;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+
+    struct DecimalVisitor;
+
+    impl de::Visitor<'_> for DecimalVisitor {
+        type Value = Decimal;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a decimal as a string or a number")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Decimal, E> {
+            Decimal::from_str(value.trim()).map_err(de::Error::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Decimal, E> {
+            Ok(Decimal::from(value))
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<Decimal, E> {
+            // Display gives the shortest round-trip form, so 4.1 stays 4.1 instead of 4.0999...
+            Decimal::from_str(&value.to_string()).map_err(de::Error::custom)
+        }
+    }
+}
+
+// Optional variant of decimal_format (null when absent)
+pub mod decimal_format_option {
+    use rust_decimal::Decimal;
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(d) => serializer.serialize_some(&d.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::decimal_format")] Decimal);
+
+        Option::<Wrapper>::deserialize(deserializer).map(|opt| opt.map(|Wrapper(d)| d))
+    }
+}
+
+// Money serialized as a string with exactly 2 decimal places (e.g. "4.50")
+pub mod money_format {
+    use rust_decimal::Decimal;
+    use serde::Serializer;
+
+    pub fn serialize<S>(amount: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("{:.2}", amount.round_dp(2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::{IntoSql, RunQueryDsl};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Price {
+        #[serde(with = "decimal_format")]
+        amount: Decimal,
+    }
+
+    fn parse_price(json: &str) -> Result<Decimal, serde_json::Error> {
+        serde_json::from_str::<Price>(json).map(|price| price.amount)
+    }
+
+    #[test]
+    fn decimal_format_accepts_strings_and_numbers() {
+        let cases = [
+            (r#"{"amount": "4.00"}"#, Decimal::new(400, 2)),
+            (r#"{"amount": " 4.5 "}"#, Decimal::new(45, 1)),
+            (r#"{"amount": 4.00}"#, Decimal::new(4, 0)),
+            (r#"{"amount": 4.1}"#, Decimal::new(41, 1)),
+            (r#"{"amount": 4}"#, Decimal::new(4, 0)),
+        ];
+        for (json, expected) in cases {
+            assert_eq!(parse_price(json).unwrap(), expected, "{}", json);
+        }
+    }
+
+    #[test]
+    fn decimal_format_rejects_other_values() {
+        for json in [r#"{"amount": "four"}"#, r#"{"amount": true}"#, r#"{"amount": null}"#, r#"{"amount": [4]}"#] {
+            assert!(parse_price(json).is_err(), "{} should be rejected", json);
+        }
+    }
+
+    #[test]
+    fn decimal_format_serializes_as_a_string() {
+        let json = serde_json::to_string(&Price { amount: Decimal::new(400, 2) }).unwrap();
+        assert_eq!(json, r#"{"amount":"4.00"}"#);
+    }
+
+    fn at(s: &str) -> SqliteDateTime {
+        SqliteDateTime(NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap())
+    }
+
+    #[test]
+    fn not_before_clamps_an_earlier_update_to_the_creation_time() {
+        let created_at = at("2025-03-01T12:00:00");
+        assert_eq!(at("2025-03-01T11:59:59").not_before(created_at), created_at);
+        assert_eq!(at("2025-03-01T12:00:00").not_before(created_at), created_at);
+        assert_eq!(at("2025-03-01T12:00:01").not_before(created_at), at("2025-03-01T12:00:01"));
+    }
+
+    #[test]
+    fn updated_now_never_precedes_a_future_creation_time() {
+        // A row written by a clock running ahead of this one
+        let created_at = SqliteDateTime(chrono::Utc::now().naive_utc() + chrono::Duration::hours(1));
+        assert_eq!(SqliteDateTime::updated_now(created_at), created_at);
+
+        let created_at = at("2025-03-01T12:00:00");
+        assert!(SqliteDateTime::updated_now(created_at) > created_at);
+    }
+
+    fn stored(s: &str) -> NaiveDateTime {
+        SqliteDateTime::parse_stored(s).unwrap_or_else(|| panic!("{} should parse", s)).into_naive_date_time()
+    }
+
+    #[test]
+    fn stored_timestamps_parse_with_or_without_an_offset() {
+        let whole_second = at("2024-01-02T03:04:05").into_naive_date_time();
+        assert_eq!(stored("2024-01-02T03:04:05"), whole_second);
+        assert_eq!(stored("2024-01-02T03:04:05Z"), whole_second);
+        assert_eq!(stored("2024-01-02T03:04:05+00:00"), whole_second);
+        assert_eq!(stored("2024-01-02T03:04:05.123"), whole_second + chrono::Duration::milliseconds(123));
+        // Rows seeded with an explicit zero fraction
+        assert_eq!(stored("2024-01-02T03:04:05.000"), whole_second);
+        assert_eq!(stored("2024-01-02T03:04:05.000000"), whole_second);
+        // Offsets are converted to UTC
+        assert_eq!(stored("2024-01-02T05:04:05+02:00"), whole_second);
+    }
+
+    #[test]
+    fn stored_timestamps_reject_garbage() {
+        for s in ["", "yesterday", "2024-01-02", "2024-13-02T03:04:05", "1704164645000"] {
+            assert!(SqliteDateTime::parse_stored(s).is_none(), "{:?} should be rejected", s);
+        }
+    }
+
+    fn memory_db() -> diesel::SqliteConnection {
+        use diesel::Connection;
+        diesel::SqliteConnection::establish(":memory:").unwrap()
+    }
+
+    #[test]
+    fn decimals_round_trip_through_sqlite() {
+        for value in ["0", "4.50", "3.9", "1234567.89", "-0.01"] {
+            let decimal = Decimal::from_str(value).unwrap();
+            let read = diesel::select(SqliteDecimal(decimal).into_sql::<Text>())
+                .get_result::<SqliteDecimal>(&mut memory_db())
+                .unwrap()
+                .into_decimal();
+            assert_eq!(read, decimal);
+            assert_eq!(read.to_string(), value, "scale is kept");
+        }
+    }
+
+    #[test]
+    fn timestamps_round_trip_through_sqlite() {
+        let whole_second = at("2024-01-02T03:04:05");
+        let fractional = SqliteDateTime(whole_second.0 + chrono::Duration::microseconds(123_456));
+        for value in [whole_second, fractional] {
+            let read = diesel::select(value.into_sql::<Text>()).get_result::<SqliteDateTime>(&mut memory_db()).unwrap();
+            assert_eq!(read, value);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::SellerInfo;
+use crate::models::customer::Customer;
+use crate::models::drink_size::DrinkSize;
+use crate::models::order::Order;
+use crate::models::order_items::OrderItem;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+// Business invoice for one order (GET /orders/<id>/invoice)
+// Money is rendered with exactly 2 decimals. Item prices are tax-exclusive.
+#[derive(Debug, Serialize)]
+pub struct InvoiceApiModel {
+    pub invoice_number: String,
+    pub order_id: String,
+    #[serde(with = "crate::models::infra::sqlite_types::date_format")]
+    pub issue_date: NaiveDate,
+    pub seller: InvoiceSellerApiModel,
+    pub customer: InvoiceCustomerApiModel,
+    pub lines: Vec<InvoiceLineApiModel>,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub subtotal: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
+    pub tax_rate_percent: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub tax: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub discount: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub total: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceSellerApiModel {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceCustomerApiModel {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+}
+
+// One order item; its price already includes the listed extras
+#[derive(Debug, Serialize)]
+pub struct InvoiceLineApiModel {
+    pub description: String,
+    pub drink_id: String,
+    pub size: DrinkSize,
+    pub extras: Vec<String>,
+    pub quantity: i64,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub unit_price: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub line_total: Decimal,
+}
+
+impl InvoiceLineApiModel {
+    /// Build a line from an order item, its drink name and the names of its extras
+    pub fn new(item: &OrderItem, drink_name: &str, extras: Vec<String>) -> Self {
+        let size = item.size.into_drink_size();
+        let mut description = format!("{} ({})", drink_name, size);
+        if !extras.is_empty() {
+            description.push_str(&format!(" + {}", extras.join(", ")));
+        }
+
+        // Order items are single units
+        let quantity = 1;
+        let unit_price = item.total_price.into_decimal();
+
+        InvoiceLineApiModel {
+            description,
+            drink_id: item.drink_id.clone(),
+            size,
+            extras,
+            quantity,
+            unit_price,
+            line_total: unit_price * Decimal::from(quantity),
+        }
+    }
+}
+
+impl InvoiceApiModel {
+    /// Assemble the invoice and compute its totals
+    /// Tax is `tax_rate_percent` of the subtotal, rounded to 2 decimals. Orders
+    /// carry no discounts yet, so the discount is always zero.
+    pub fn build(
+        order: &Order,
+        customer: &Customer,
+        seller: &SellerInfo,
+        tax_rate_percent: Decimal,
+        lines: Vec<InvoiceLineApiModel>,
+    ) -> Self {
+        let subtotal: Decimal = lines.iter().map(|line| line.line_total).sum();
+        let tax = (subtotal * tax_rate_percent / Decimal::ONE_HUNDRED).round_dp(2);
+        let discount = Decimal::ZERO;
+
+        InvoiceApiModel {
+            invoice_number: format!("INV-{}", order.short_code()),
+            order_id: order.id.clone(),
+            issue_date: order.created_at.into_naive_date_time().date(),
+            seller: InvoiceSellerApiModel {
+                name: seller.name.clone(),
+                address: seller.address.clone(),
+                tax_id: seller.tax_id.clone(),
+            },
+            customer: InvoiceCustomerApiModel {
+                id: customer.id.clone(),
+                name: customer.name.clone(),
+                email: customer.email.clone(),
+            },
+            lines,
+            subtotal,
+            tax_rate_percent,
+            tax,
+            discount,
+            total: subtotal - discount + tax,
+        }
+    }
+}
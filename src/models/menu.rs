@@ -0,0 +1,63 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::models::drink::DrinkApiModel;
+use crate::models::extra::ExtraApiModel;
+use crate::models::modifier::ModifierApiModel;
+
+// Orderable catalog (GET /menu): active drinks with their sizes, available extras
+// and modifiers with their available options
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MenuApiModel {
+    pub drinks: Vec<DrinkApiModel>,
+    pub extras: Vec<ExtraApiModel>,
+    pub modifiers: Vec<ModifierApiModel>,
+}
+
+// Catalog version clients poll to know when to refetch the menu (GET /menu/version)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MenuVersionApiModel {
+    pub version: u64,
+}
+
+// Catalog summary for dashboards (GET /menu/stats)
+// Price aggregates cover active drinks and are null when there are none.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MenuStatsApiModel {
+    pub drink_count: i64,
+    pub available_drink_count: i64,
+    pub extra_count: i64,
+    pub available_extra_count: i64,
+    #[serde(default, with = "crate::models::infra::sqlite_types::decimal_format_option")]
+    pub avg_drink_price: Option<Decimal>,
+    #[serde(default, with = "crate::models::infra::sqlite_types::decimal_format_option")]
+    pub min_price: Option<Decimal>,
+    #[serde(default, with = "crate::models::infra::sqlite_types::decimal_format_option")]
+    pub max_price: Option<Decimal>,
+}
+
+impl MenuStatsApiModel {
+    /// Aggregate drink prices and extra availability
+    /// Prices are stored as Text, so the aggregates are computed here with Decimal
+    /// arithmetic instead of in SQL. The average is rounded to 2 decimal places.
+    pub fn from_catalog(drink_prices: &[Decimal], extra_availability: &[bool]) -> Self {
+        let drink_count = drink_prices.len() as i64;
+        let avg_drink_price = if drink_prices.is_empty() {
+            None
+        } else {
+            let sum: Decimal = drink_prices.iter().sum();
+            Some((sum / Decimal::from(drink_count)).round_dp(2))
+        };
+
+        MenuStatsApiModel {
+            drink_count,
+            // Drinks have no availability flag: every active drink is orderable
+            available_drink_count: drink_count,
+            extra_count: extra_availability.len() as i64,
+            available_extra_count: extra_availability.iter().filter(|available| **available).count() as i64,
+            avg_drink_price,
+            min_price: drink_prices.iter().min().copied(),
+            max_price: drink_prices.iter().max().copied(),
+        }
+    }
+}
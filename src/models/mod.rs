@@ -1,10 +1,21 @@
+pub mod auth;
 pub mod employee;
 pub mod customer;
+pub mod checkout;
 pub mod drink;
+pub mod drink_price_history;
 pub mod order;
 pub mod extra;
+pub mod idempotency_key;
+pub mod modifier;
+pub mod invoice;
+pub mod menu;
 pub mod order_status;
+pub mod order_status_history;
+pub mod report;
+pub mod pagination;
 pub mod order_items;
 pub mod order_item_extras;
+pub mod order_item_modifiers;
 pub mod drink_size;
-mod infra;
\ No newline at end of file
+pub mod infra;
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::schema::{modifier_options, modifiers};
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+
+// Database model of a single-select customization (e.g. milk type)
+#[derive(Queryable, Insertable, Selectable, Debug)]
+#[diesel(table_name = modifiers)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Modifier {
+    pub id: String,
+    pub name: String,
+}
+
+// Database model of one choice of a modifier (e.g. oat)
+#[derive(Queryable, Insertable, Selectable, Debug)]
+#[diesel(table_name = modifier_options)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ModifierOption {
+    pub id: String,
+    pub modifier_id: String,
+    pub name: String,
+    pub is_available: bool,
+}
+
+// API representation of a modifier, as listed on the menu
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifierApiModel {
+    pub id: String,
+    pub name: String,
+    pub options: Vec<ModifierOptionApiModel>,
+    /// Drinks offering this modifier
+    pub drink_ids: Vec<String>,
+}
+
+// API representation of a modifier option
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModifierOptionApiModel {
+    pub id: String,
+    pub name: String,
+    pub is_available: bool,
+}
+
+impl ModifierOption {
+    /// Convert to API-friendly model
+    pub fn to_api_model(&self) -> ModifierOptionApiModel {
+        ModifierOptionApiModel {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            is_available: self.is_available,
+        }
+    }
+}
@@ -1,17 +1,21 @@
-#[allow(dead_code)]  // suppress warnings for structs used in routes
+#![allow(dead_code)]  // suppress warnings for structs used in routes
 
 use crate::schema::orders;
 use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteOrderStatus};
-use crate::models::order_status::OrderStatus;
-use chrono::NaiveDateTime;
+use crate::models::order_status::{InitialOrderStatus, OrderStatus};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use diesel::prelude::*;
+use rocket::http::Status;
 use crate::models::drink_size::DrinkSize;
+use crate::models::order_items::OrderItemDetailApiModel;
 
 // Database model
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Selectable, Debug)]
 #[diesel(table_name = orders)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct Order {
     pub id: String,
     pub customer_id: String,
@@ -22,21 +26,102 @@ pub struct Order {
 }
 
 // API representation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct OrderApiModel {
     pub id: String,
     pub customer_id: String,
     pub employee_id: String,
     pub status: OrderStatus,
+    #[serde(with = "crate::models::infra::sqlite_types::datetime_format")]
     pub created_at: NaiveDateTime,
+    #[serde(with = "crate::models::infra::sqlite_types::datetime_format")]
     pub updated_at: NaiveDateTime,
 }
 
+// Order with its items (GET /orders/<id>)
+// Items come in the sequence they were placed: by position, then id.
+#[derive(Debug, Serialize)]
+pub struct OrderDetailApiModel {
+    #[serde(flatten)]
+    pub order: OrderApiModel,
+    pub items: Vec<OrderItemDetailApiModel>,
+}
+
+// Computed totals of an order (GET /orders/<id>/total), money with 2 decimals
+// Item prices already include their extras: drinks + extras = subtotal = total.
+#[derive(Debug, Serialize)]
+pub struct OrderTotalApiModel {
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub drinks: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub extras: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub subtotal: Decimal,
+    #[serde(with = "crate::models::infra::sqlite_types::money_format")]
+    pub total: Decimal,
+}
+
 // Input model for creating orders
+// status is optional (defaults to pending) and may only be pending or paid
 #[derive(Debug, Deserialize)]
 pub struct NewOrder {
     pub customer_id: String,
     pub employee_id: String,
+    #[serde(default)]
+    pub status: InitialOrderStatus,
+    /// When the order was taken, if earlier than its arrival here (e.g. offline POS sync)
+    #[serde(default)]
+    pub placed_at: Option<DateTime<Utc>>,
+}
+
+// One reason an order payload cannot be placed
+// `field` points into the payload, e.g. "items[0].size"; `status` is what the
+// order-creating endpoints answer for it (400 malformed, 422 invalid reference).
+#[derive(Debug, Serialize)]
+pub struct OrderProblem {
+    pub field: String,
+    pub message: String,
+    #[serde(skip)]
+    pub status: Status,
+}
+
+impl OrderProblem {
+    /// Malformed value (400)
+    pub fn invalid(field: impl Into<String>, message: impl Into<String>) -> Self {
+        OrderProblem { field: field.into(), message: message.into(), status: Status::BadRequest }
+    }
+
+    /// Value that doesn't match the current data (422)
+    pub fn unprocessable(field: impl Into<String>, message: impl Into<String>) -> Self {
+        OrderProblem { field: field.into(), message: message.into(), status: Status::UnprocessableEntity }
+    }
+}
+
+// Result of a dry-run validation (POST /orders/validate)
+#[derive(Debug, Serialize)]
+pub struct OrderValidationApiModel {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<OrderProblem>,
+}
+
+impl From<Vec<OrderProblem>> for OrderValidationApiModel {
+    fn from(problems: Vec<OrderProblem>) -> Self {
+        OrderValidationApiModel { valid: problems.is_empty(), problems }
+    }
+}
+
+// Input model for moving an order to another customer
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateOrderCustomer {
+    pub customer_id: String,
+}
+
+// Input model for moving an order along its lifecycle
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateOrderStatus {
     pub status: OrderStatus,
 }
 
@@ -52,17 +137,63 @@ impl Order {
         }
     }
 
+    /// Short human-friendly reference: the first 8 characters of the id, uppercased
+    pub fn short_code(&self) -> String {
+        self.id.chars().take(8).collect::<String>().to_uppercase()
+    }
+
+    /// created_at is the client's placed_at when given, otherwise now.
+    /// updated_at is now, but never earlier than created_at.
     pub fn from_new(new: NewOrder, id: String) -> Self {
-        let now = chrono::Utc::now().naive_utc();
+        let now = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+        let created_at = new.placed_at.map(|placed_at| SqliteDateTime::from(placed_at.naive_utc())).unwrap_or(now);
         Order {
             id,
             customer_id: new.customer_id,
             employee_id: new.employee_id,
-            status: SqliteOrderStatus::from(new.status),
-            created_at: SqliteDateTime::from(now),
-            updated_at: SqliteDateTime::from(now),
+            status: SqliteOrderStatus::from(OrderStatus::from(new.status)),
+            created_at,
+            updated_at: now.max(created_at),
+        }
+    }
+
+    /// Move the order to a new status
+    /// Returns false (leaving the order untouched) if the transition is illegal.
+    pub fn transition_to(&mut self, next: OrderStatus) -> bool {
+        if !self.status.into_order_status().can_transition_to(next) {
+            return false;
         }
+        self.status = SqliteOrderStatus::from(next);
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+        true
     }
+
+    /// Record a change to the order's items
+    /// Only updates updated_at.
+    pub fn touch(&mut self) {
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+
+    /// Attach the order to another customer
+    /// Only updates customer_id and updated_at.
+    pub fn reassign_customer(&mut self, customer_id: String) {
+        self.customer_id = customer_id;
+        self.updated_at = SqliteDateTime::updated_now(self.created_at);
+    }
+}
+
+// Result of a batch status change: orders moved and orders left alone
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchTransitionApiModel {
+    pub updated: Vec<String>,
+    pub skipped: Vec<SkippedOrderApiModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkippedOrderApiModel {
+    pub id: String,
+    pub status: OrderStatus,
+    pub reason: String,
 }
 
 // Input models for complex order creation
@@ -70,17 +201,22 @@ impl Order {
 pub struct IncomingOrder {
     pub customer_id: String,
     pub employee_id: String,
-    pub status: OrderStatus,
+    #[serde(default)]
+    pub status: InitialOrderStatus,
+    /// RFC 3339 time the order was taken; defaults to now
+    #[serde(default)]
+    pub placed_at: Option<DateTime<Utc>>,
     pub items: Vec<IncomingOrderItem>,
 }
 
+// The item price is computed server-side; a client-sent total_price is ignored
 #[derive(Debug, Deserialize)]
 pub struct IncomingOrderItem {
     pub drink_id: String,
     pub size: DrinkSize,
-    #[serde(with = "rust_decimal::serde::str")]
-    pub total_price: Decimal,
     pub extras: Vec<String>, // List of extra IDs
+    #[serde(default)]
+    pub modifiers: Vec<String>, // List of modifier option IDs, at most one per modifier
 }
 
 // Conversion for query results
@@ -89,85 +225,3 @@ impl From<Order> for OrderApiModel {
         order.to_api_model()
     }
 }
-
-/*
-Creating a New Order - Full Example
-
-use rocket::{post, State};
-use rocket::serde::json::Json;
-use crate::DbConn;
-use crate::models::order::{IncomingOrder, Order, NewOrder, OrderApiModel};
-use crate::models::order_item::{OrderItem, NewOrderItem};
-use crate::models::order_item_extra::{OrderItemExtra, NewOrderItemExtra};
-
-#[post("/orders", data = "<incoming_order>")]
-async fn create_order(
-    conn: DbConn,
-    incoming_order: Json<IncomingOrder>,
-) -> Result<Json<OrderApiModel>, Status> {
-    // Start transaction
-    conn.run(|c| {
-        let transaction_result: Result<_, diesel::result::Error> = c.transaction(|tx| {
-            // Create the main order
-            let order_id = Uuid::new_v4().to_string();
-            let db_order = Order::from_new(
-                NewOrder {
-                    customer_id: incoming_order.customer_id.clone(),
-                    employee_id: incoming_order.employee_id.clone(),
-                    status: incoming_order.status,
-                },
-                order_id.clone()
-            );
-
-            diesel::insert_into(orders::table)
-                .values(&db_order)
-                .execute(tx)?;
-
-            // Create order items
-            for (item_index, item) in incoming_order.items.iter().enumerate() {
-                let item_id = Uuid::new_v4().to_string();
-                let db_item = OrderItem::from_new(
-                    NewOrderItem {
-                        order_id: order_id.clone(),
-                        drink_id: item.drink_id.clone(),
-                        size: item.size,
-                        total_price: item.total_price,
-                    },
-                    item_id.clone()
-                );
-
-                diesel::insert_into(order_items::table)
-                    .values(&db_item)
-                    .execute(tx)?;
-
-                // Create item extras
-                for extra_id in &item.extras {
-                    let extra_item_id = Uuid::new_v4().to_string();
-                    let db_extra = OrderItemExtra::from_new(
-                        NewOrderItemExtra {
-                            order_item_id: item_id.clone(),
-                            extra_id: extra_id.clone(),
-                        },
-                        extra_item_id
-                    );
-
-                    diesel::insert_into(order_item_extras::table)
-                        .values(&db_extra)
-                        .execute(tx)?;
-                }
-            }
-
-            Ok(db_order)
-        });
-
-        match transaction_result {
-            Ok(order) => Ok(Json(order.to_api_model())),
-            Err(e) => {
-                error!("Order creation failed: {}", e);
-                Err(Status::InternalServerError)
-            }
-        }
-    })
-    .await
-}
- */
\ No newline at end of file
@@ -1,4 +1,6 @@
 use crate::schema::order_item_extras;
+use crate::models::infra::sqlite_types::SqliteDecimal;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use diesel::prelude::*;
 
@@ -9,6 +11,8 @@ pub struct OrderItemExtra {
     pub id: String,
     pub order_item_id: String,
     pub extra_id: String,
+    /// Price the extra was charged at when attached
+    pub extra_price: SqliteDecimal,
 }
 
 // API representation (for serialization/deserialization)
@@ -17,6 +21,14 @@ pub struct OrderItemExtraApiModel {
     pub id: String,
     pub order_item_id: String,
     pub extra_id: String,
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
+    pub extra_price: Decimal,
+}
+
+// Input model (for attaching an extra to an existing order item)
+#[derive(Debug, Deserialize)]
+pub struct AttachOrderItemExtra {
+    pub extra_id: String,
 }
 
 // Input model (for creating order item extras)
@@ -24,6 +36,8 @@ pub struct OrderItemExtraApiModel {
 pub struct NewOrderItemExtra {
     pub order_item_id: String,
     pub extra_id: String,
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
+    pub extra_price: Decimal,
 }
 
 impl OrderItemExtra {
@@ -33,6 +47,7 @@ impl OrderItemExtra {
             id: self.id.clone(),
             order_item_id: self.order_item_id.clone(),
             extra_id: self.extra_id.clone(),
+            extra_price: self.extra_price.into_decimal(),
         }
     }
 
@@ -42,6 +57,7 @@ impl OrderItemExtra {
             id,
             order_item_id: new.order_item_id,
             extra_id: new.extra_id,
+            extra_price: SqliteDecimal::from(new.extra_price),
         }
     }
 }
@@ -52,32 +68,3 @@ impl From<OrderItemExtra> for OrderItemExtraApiModel {
         extra.to_api_model()
     }
 }
-
-/*
-// In your route handler
-#[post("/order-items/<order_item_id>/extras", data = "<new_extra>")]
-async fn add_extra_to_order_item(
-    conn: DbConn,
-    order_item_id: String,
-    new_extra: Json<NewOrderItemExtra>,
-) -> Result<Json<OrderItemExtraApiModel>, Status> {
-    let id = Uuid::new_v4().to_string();
-    let db_extra = OrderItemExtra::from_new(
-        NewOrderItemExtra {
-            order_item_id: order_item_id.clone(),
-            extra_id: new_extra.extra_id.clone(),
-        },
-        id
-    );
-    
-    conn.run(|c| {
-        diesel::insert_into(order_item_extras::table)
-            .values(&db_extra)
-            .execute(c)
-            .map_err(|_| Status::InternalServerError)?;
-            
-        Ok(Json(db_extra.to_api_model()))
-    })
-    .await
-}
- */
\ No newline at end of file
@@ -0,0 +1,22 @@
+use crate::schema::order_item_modifiers;
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+
+// Database model: the option chosen for one modifier of an order item
+#[derive(Queryable, Insertable, Debug)]
+#[diesel(table_name = order_item_modifiers)]
+pub struct OrderItemModifier {
+    pub id: String,
+    pub order_item_id: String,
+    pub modifier_id: String,
+    pub modifier_option_id: String,
+}
+
+// Selection echoed in order details
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectedModifierApiModel {
+    pub modifier_id: String,
+    pub modifier: String,
+    pub option_id: String,
+    pub option: String,
+}
@@ -1,19 +1,23 @@
 use crate::schema::order_items;
 use crate::models::infra::sqlite_types::{SqliteDecimal, SqliteDrinkSize};
 use crate::models::drink_size::DrinkSize;
+use crate::models::extra::ExtraApiModel;
+use crate::models::order_item_modifiers::SelectedModifierApiModel;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use diesel::prelude::*;
 
 // Database model
-#[derive(Queryable, Insertable, Debug)]
+#[derive(Queryable, Insertable, Selectable, Debug)]
 #[diesel(table_name = order_items)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct OrderItem {
     pub id: String,
     pub order_id: String,
     pub drink_id: String,
     pub size: SqliteDrinkSize,
     pub total_price: SqliteDecimal,
+    pub position: i32,
 }
 
 // API representation
@@ -23,18 +27,30 @@ pub struct OrderItemApiModel {
     pub order_id: String,
     pub drink_id: String,
     pub size: DrinkSize,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub total_price: Decimal,
 }
 
+// Item with its drink name and the extras and modifier options attached to it, as nested in order details
+#[derive(Debug, Serialize)]
+pub struct OrderItemDetailApiModel {
+    #[serde(flatten)]
+    pub item: OrderItemApiModel,
+    pub drink_name: String,
+    pub extras: Vec<ExtraApiModel>,
+    pub modifiers: Vec<SelectedModifierApiModel>,
+}
+
 // Input model
 #[derive(Debug, Deserialize)]
 pub struct NewOrderItem {
     pub order_id: String,
     pub drink_id: String,
     pub size: DrinkSize,
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::models::infra::sqlite_types::decimal_format")]
     pub total_price: Decimal,
+    /// Index of the item within its order
+    pub position: i32,
 }
 
 impl OrderItem {
@@ -55,6 +71,7 @@ impl OrderItem {
             drink_id: new.drink_id,
             size: SqliteDrinkSize::from(new.size),
             total_price: SqliteDecimal::from(new.total_price),
+            position: new.position,
         }
     }
 }
@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderStatus {
+    #[default]
     Pending,
     Paid,
     Preparing,
@@ -13,6 +15,83 @@ pub enum OrderStatus {
     Cancelled,
 }
 
+impl OrderStatus {
+    /// Every status, in lifecycle order
+    const ALL: [OrderStatus; 6] = [
+        OrderStatus::Pending,
+        OrderStatus::Paid,
+        OrderStatus::Preparing,
+        OrderStatus::Ready,
+        OrderStatus::Completed,
+        OrderStatus::Cancelled,
+    ];
+
+    /// Whether an order in this status may move to `next`
+    ///
+    /// Orders flow Pending → Paid → Preparing → Ready → Completed.
+    /// Cancelling is only possible before preparation starts (Pending or Paid).
+    /// Completed and Cancelled are terminal.
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        matches!(
+            (self, next),
+            (OrderStatus::Pending, OrderStatus::Paid)
+                | (OrderStatus::Paid, OrderStatus::Preparing)
+                | (OrderStatus::Preparing, OrderStatus::Ready)
+                | (OrderStatus::Ready, OrderStatus::Completed)
+                | (OrderStatus::Pending, OrderStatus::Cancelled)
+                | (OrderStatus::Paid, OrderStatus::Cancelled)
+        )
+    }
+
+    /// Whether the order is finished (Completed or Cancelled) and can no longer move
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderStatus::Completed | OrderStatus::Cancelled)
+    }
+
+    /// Statuses of orders still in progress
+    pub fn active() -> Vec<OrderStatus> {
+        Self::ALL.into_iter().filter(|status| !status.is_terminal()).collect()
+    }
+
+    /// Whether the kitchen has work on the order: paid and not yet handed over
+    ///
+    /// Pending orders wait for payment and Completed or Cancelled ones are done.
+    pub fn is_in_kitchen(&self) -> bool {
+        matches!(self, OrderStatus::Paid | OrderStatus::Preparing | OrderStatus::Ready)
+    }
+
+    /// Statuses of orders shown on the kitchen display
+    pub fn in_kitchen() -> Vec<OrderStatus> {
+        Self::ALL.into_iter().filter(OrderStatus::is_in_kitchen).collect()
+    }
+}
+
+/// Status a new order may be created in
+///
+/// Orders start as `pending`, or as `paid` when payment is taken at creation.
+/// Deserializing any other status fails, so input models using this type reject
+/// e.g. `"completed"` with 422 before reaching the handler. Defaults to `pending`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(try_from = "OrderStatus", into = "OrderStatus")]
+pub struct InitialOrderStatus(OrderStatus);
+
+impl TryFrom<OrderStatus> for InitialOrderStatus {
+    type Error = String;
+
+    fn try_from(status: OrderStatus) -> Result<Self, Self::Error> {
+        match status {
+            OrderStatus::Pending | OrderStatus::Paid => Ok(InitialOrderStatus(status)),
+            _ => Err(format!("new orders must start as 'pending' or 'paid', not '{}'", status)),
+        }
+    }
+}
+
+impl From<InitialOrderStatus> for OrderStatus {
+    fn from(status: InitialOrderStatus) -> Self {
+        status.0
+    }
+}
+
 impl FromStr for OrderStatus {
     type Err = String;
 
@@ -40,4 +119,27 @@ impl fmt::Display for OrderStatus {
             OrderStatus::Cancelled => write!(f, "cancelled"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_orders_start_pending_or_paid() {
+        for status in OrderStatus::ALL {
+            let initial = InitialOrderStatus::try_from(status);
+            let allowed = matches!(status, OrderStatus::Pending | OrderStatus::Paid);
+            assert_eq!(initial.is_ok(), allowed, "initial status {}", status);
+        }
+        assert_eq!(OrderStatus::from(InitialOrderStatus::default()), OrderStatus::Pending);
+    }
+
+    #[test]
+    fn initial_status_deserialization_rejects_later_statuses() {
+        let paid: InitialOrderStatus = serde_json::from_str(r#""paid""#).unwrap();
+        assert_eq!(OrderStatus::from(paid), OrderStatus::Paid);
+
+        let error = serde_json::from_str::<InitialOrderStatus>(r#""completed""#).unwrap_err();
+        assert!(error.to_string().contains("new orders must start as 'pending' or 'paid'"), "{}", error);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::schema::order_status_history;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteOrderStatus};
+use crate::models::order_status::OrderStatus;
+use diesel::prelude::*;
+
+// Database model: one row per order status change
+#[derive(Queryable, Insertable, Selectable, Debug)]
+#[diesel(table_name = order_status_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct OrderStatusChange {
+    pub id: String,
+    pub order_id: String,
+    pub from_status: SqliteOrderStatus,
+    pub to_status: SqliteOrderStatus,
+    pub changed_at: SqliteDateTime,
+}
+
+impl OrderStatusChange {
+    /// Record a transition of `order_id` from one status to another
+    pub fn new(id: String, order_id: String, from: OrderStatus, to: OrderStatus, changed_at: SqliteDateTime) -> Self {
+        OrderStatusChange {
+            id,
+            order_id,
+            from_status: SqliteOrderStatus::from(from),
+            to_status: SqliteOrderStatus::from(to),
+            changed_at,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+
+// Envelope of one page of a list endpoint, returned instead of the bare array
+// when asked for with `?paginated=true` or the page media type in `Accept`
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
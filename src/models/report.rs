@@ -0,0 +1,18 @@
+use chrono::NaiveDate;
+use diesel::Queryable;
+use serde::{Deserialize, Serialize};
+
+// One point of the daily order count chart (GET /reports/daily-order-counts)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DailyOrderCountApiModel {
+    #[serde(with = "crate::models::infra::sqlite_types::date_format")]
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
+// Orders in an active status held by one employee (GET /reports/employee-load)
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct EmployeeLoadApiModel {
+    pub employee_id: String,
+    pub active_orders: i64,
+}
@@ -0,0 +1,106 @@
+//! OpenAPI contract of the API
+//!
+//! - GET /openapi.json - OpenAPI 3.1 document, importable into Postman or code generators
+//! - GET /docs - Swagger UI rendering that document
+//!
+//! The document is generated with `utoipa` from the `#[utoipa::path]` annotations
+//! on the handlers and the `ToSchema` derives on the API and input models. It
+//! covers the customer and drink endpoints.
+
+use rocket::response::content::RawHtml;
+use rocket::serde::json::Json;
+use rocket::{get, routes, Route};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::error::ErrorApiModel;
+use crate::guards::ADMIN_TOKEN_HEADER;
+use crate::routes::{customers, drinks};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Coffee Shop API"),
+    paths(
+        customers::get_customers,
+        customers::get_customer,
+        customers::get_customer_orders,
+        customers::create_customer,
+        customers::update_customer,
+        customers::patch_customer,
+        customers::delete_customer,
+        customers::restore_customer,
+        customers::anonymize_customer,
+        drinks::get_drinks,
+        drinks::get_recent_drinks,
+        drinks::get_drink,
+        drinks::get_drink_sizes,
+        drinks::get_drink_extras,
+        drinks::create_drink,
+        drinks::create_drinks_batch,
+        drinks::update_drink,
+        drinks::patch_drink,
+        drinks::update_drink_availability,
+        drinks::delete_drink,
+        drinks::restore_drink,
+        drinks::bulk_adjust_drink_prices,
+    ),
+    components(schemas(ErrorApiModel)),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "customers", description = "Customer management"),
+        (name = "drinks", description = "Drink catalog"),
+    )
+)]
+struct ApiDoc;
+
+/// Declares the `bearer` (JWT from /api/auth/token) and `admin_token` schemes
+/// referenced by the write endpoints
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(ADMIN_TOKEN_HEADER))),
+        );
+    }
+}
+
+/// The OpenAPI document
+#[get("/openapi.json")]
+pub fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI page, loading its assets from a CDN
+#[get("/docs")]
+pub fn docs() -> RawHtml<&'static str> {
+    RawHtml(SWAGGER_UI_PAGE)
+}
+
+const SWAGGER_UI_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Coffee Shop API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+/// Documentation routes, to be mounted under "/"
+pub fn routes() -> Vec<Route> {
+    routes![openapi_json, docs]
+}
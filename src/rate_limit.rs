@@ -0,0 +1,115 @@
+//! Per-client request rate limiting
+//!
+//! Request fairing counting the requests of every client IP over a sliding
+//! window (`RATE_LIMIT_WINDOW_SECONDS`). Once a client has made
+//! `RATE_LIMIT_REQUESTS` requests within the window, further requests are
+//! rerouted to `rate_limited`, which answers 429 without reaching any handler or
+//! the database; the 429 catcher adds a `Retry-After` header. The counts live in
+//! memory only, so they reset on restart and are not shared between instances.
+//! `RATE_LIMIT_REQUESTS=0` disables the limiter.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::uri::Origin;
+use rocket::http::{Method, Status};
+use rocket::{get, routes, Data, Request, Route};
+
+use crate::config::AppConfig;
+
+/// Path requests over the limit are rerouted to
+const RATE_LIMITED_PATH: &str = "/rate-limited";
+
+/// Number of tracked clients above which idle ones are swept out
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// Seconds a rate-limited client should wait, read by the 429 catcher
+pub struct RetryAfter(pub Option<u64>);
+
+/// Target of rate-limited requests
+#[get("/rate-limited")]
+pub fn rate_limited() -> Status {
+    Status::TooManyRequests
+}
+
+/// Rate-limited route, to be mounted under "/"
+pub fn routes() -> Vec<Route> {
+    routes![rate_limited]
+}
+
+/// Sliding-window rate limiting fairing
+pub struct RateLimiter {
+    max_requests: usize,
+    window: Duration,
+    hits: Mutex<HashMap<IpAddr, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Build the fairing from the application configuration
+    pub fn from_config(config: &AppConfig) -> Self {
+        RateLimiter {
+            max_requests: config.rate_limit_requests,
+            window: Duration::from_secs(config.rate_limit_window_seconds),
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a request from `ip` at `now`
+    ///
+    /// Returns `None` when the request is allowed, or the time until the oldest
+    /// request in the window expires when the client is over the limit. Rejected
+    /// requests are not recorded, so a client waiting out `Retry-After` gets through.
+    fn check(&self, ip: IpAddr, now: Instant) -> Option<Duration> {
+        let mut hits = self.hits.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if hits.len() > SWEEP_THRESHOLD {
+            hits.retain(|_, times| times.back().is_some_and(|last| now.duration_since(*last) < self.window));
+        }
+
+        let times = hits.entry(ip).or_default();
+        while times.front().is_some_and(|first| now.duration_since(*first) >= self.window) {
+            times.pop_front();
+        }
+
+        if times.len() >= self.max_requests {
+            let oldest = *times.front()?;
+            return Some(self.window.saturating_sub(now.duration_since(oldest)));
+        }
+
+        times.push_back(now);
+        None
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for RateLimiter {
+    fn info(&self) -> Info {
+        Info {
+            name: "Rate Limiter",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _: &mut Data<'_>) {
+        if self.max_requests == 0 {
+            return;
+        }
+
+        let Some(ip) = req.client_ip() else {
+            return;
+        };
+
+        if let Some(wait) = self.check(ip, Instant::now()) {
+            // Whole seconds, rounded up so the client never retries too early
+            let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            warn!("Rate limit exceeded by {} on {} {}", ip, req.method(), req.uri());
+
+            req.local_cache(|| RetryAfter(Some(retry_after.max(1))));
+            req.set_method(Method::Get);
+            req.set_uri(Origin::parse(RATE_LIMITED_PATH).expect("valid rate-limited path"));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Custom responders shared by the route modules
+//!
+//! - ApiJson: JSON body, indented when `PRETTY_JSON` is enabled
+//! - Paginated: JSON list response carrying pagination links in its headers
+//! - Csv: CSV file download
+//! - Cached: cacheable response with an ETag, or 304 Not Modified
+//! - Tagged: private response with a weak ETag, or 304 Not Modified
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder};
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::models::pagination::Page;
+
+/// Number of rows returned by list endpoints when no `limit` is given
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+
+/// Largest `limit` a list endpoint accepts
+pub const MAX_PAGE_LIMIT: i64 = 200;
+
+/// Resolve the `?limit=` and `?offset=` of a list endpoint
+///
+/// `limit` defaults to `DEFAULT_PAGE_LIMIT` and `offset` to 0. Fails with 400
+/// when limit is outside 1..=`MAX_PAGE_LIMIT` (SQLite would treat a negative
+/// limit as no limit at all) or offset is negative.
+pub fn page_window(limit: Option<i64>, offset: Option<i64>) -> Result<(i64, i64), Status> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+        warn!("Validation error: limit must be between 1 and {}", MAX_PAGE_LIMIT);
+        return Err(Status::BadRequest);
+    }
+
+    if offset < 0 {
+        warn!("Validation error: offset cannot be negative");
+        return Err(Status::BadRequest);
+    }
+
+    Ok((limit, offset))
+}
+
+/// JSON response body used by the read (list/detail) endpoints
+///
+/// Serializes compactly by default, or with indentation when `PRETTY_JSON` is
+/// enabled, which is easier to read while debugging. Only the body changes:
+/// headers set around it (e.g. by `Paginated`) are kept.
+pub struct ApiJson<T>(pub T);
+
+impl<'r, T: Serialize> Responder<'r, 'static> for ApiJson<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let pretty = req.rocket().state::<AppConfig>().is_some_and(|config| config.pretty_json);
+        let body = if pretty {
+            serde_json::to_string_pretty(&self.0)
+        } else {
+            serde_json::to_string(&self.0)
+        }
+        .map_err(|e| {
+            error!("JSON serialization error: {}", e);
+            Status::InternalServerError
+        })?;
+
+        (ContentType::JSON, body).respond_to(req)
+    }
+}
+
+/// CSV document served as a file download
+///
+/// Rows are written with RFC 4180 quoting: fields containing a comma, quote or
+/// line break are wrapped in double quotes, with inner quotes doubled. Lines end
+/// with CRLF. The header row is always present, even without data rows.
+pub struct Csv {
+    pub filename: String,
+    pub header: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Csv {
+    fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&csv_line(self.header.iter().copied()));
+        for row in &self.rows {
+            out.push_str(&csv_line(row.iter().map(String::as_str)));
+        }
+        out
+    }
+}
+
+/// Join fields into one CSV line, quoting them where needed
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+impl<'r> Responder<'r, 'static> for Csv {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let disposition = format!("attachment; filename=\"{}\"", self.filename);
+        let mut response = (ContentType::CSV, self.render()).respond_to(req)?;
+        response.set_header(Header::new("Content-Disposition", disposition));
+        Ok(response)
+    }
+}
+
+/// Response clients and CDNs may cache
+///
+/// Sets `Cache-Control: public, max-age=<max_age>` and `ETag`. Without a body it
+/// answers 304 Not Modified with the same headers, for a matching `If-None-Match`.
+pub struct Cached<T> {
+    pub etag: String,
+    pub max_age: u64,
+    pub body: Option<T>,
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Cached<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = match self.body {
+            Some(body) => ApiJson(body).respond_to(req)?,
+            None => rocket::Response::build().status(Status::NotModified).finalize(),
+        };
+        response.set_header(Header::new("Cache-Control", format!("public, max-age={}", self.max_age)));
+        response.set_header(Header::new("ETag", self.etag));
+        Ok(response)
+    }
+}
+
+/// Response clients must revalidate before reuse
+///
+/// Sets a weak `ETag` (`W/` + `etag`) and `Cache-Control: private, no-cache`, so
+/// shared caches keep out of it and clients ask again with `If-None-Match`.
+/// Without a body it answers 304 Not Modified with the same headers.
+pub struct Tagged<T> {
+    pub etag: String,
+    pub body: Option<T>,
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Tagged<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = match self.body {
+            Some(body) => ApiJson(body).respond_to(req)?,
+            None => rocket::Response::build().status(Status::NotModified).finalize(),
+        };
+        response.set_header(Header::new("Cache-Control", "private, no-cache"));
+        response.set_header(Header::new("ETag", format!("W/{}", self.etag)));
+        Ok(response)
+    }
+}
+
+/// `Accept` media type asking list endpoints for the `Page` envelope
+pub const PAGE_MEDIA_TYPE: &str = "application/vnd.coffeeshop.page+json";
+
+/// One page of a list endpoint
+///
+/// The body is a bare JSON array by default. Pagination metadata travels in headers:
+/// - `X-Total-Count`: number of rows matching the query across all pages
+/// - `Link`: RFC 5988 links with `rel="next"` and `rel="prev"`, built from the
+///   request path and query with only `limit` and `offset` replaced.
+///   `prev` is omitted on the first page and `next` on the last one.
+///
+/// Clients sending `?paginated=true` or `Accept: PAGE_MEDIA_TYPE` get a `Page`
+/// envelope (`items`, `total`, `limit`, `offset`) instead; the headers are set
+/// either way.
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl<T> Paginated<T> {
+    /// Build the `Link` header value, or `None` when there is a single page
+    fn link_header(&self, req: &Request<'_>) -> Option<String> {
+        let mut links = Vec::new();
+
+        if self.offset + self.limit < self.total {
+            let next = page_url(req, self.limit, self.offset + self.limit);
+            links.push(format!("<{}>; rel=\"next\"", next));
+        }
+
+        if self.offset > 0 {
+            let prev = page_url(req, self.limit, (self.offset - self.limit).max(0));
+            links.push(format!("<{}>; rel=\"prev\"", prev));
+        }
+
+        if links.is_empty() {
+            None
+        } else {
+            Some(links.join(", "))
+        }
+    }
+}
+
+/// Whether the client asked for the `Page` envelope instead of a bare array
+fn wants_envelope(req: &Request<'_>) -> bool {
+    let flag = req.query_value::<bool>("paginated").and_then(Result::ok).unwrap_or(false);
+    let accepts_page = req
+        .headers()
+        .get("Accept")
+        .flat_map(|accept| accept.split(','))
+        .any(|media_type| media_type.split(';').next().unwrap_or_default().trim() == PAGE_MEDIA_TYPE);
+
+    flag || accepts_page
+}
+
+/// Rebuild the current request URL pointing at another page
+///
+/// Every query parameter except `limit` and `offset` is kept verbatim (still
+/// percent-encoded) so filters carry over to the next/prev pages.
+fn page_url(req: &Request<'_>, limit: i64, offset: i64) -> String {
+    let mut params: Vec<String> = req
+        .uri()
+        .query()
+        .map(|query| {
+            query
+                .as_str()
+                .split('&')
+                .filter(|segment| !segment.is_empty())
+                .filter(|segment| {
+                    let key = segment.split('=').next().unwrap_or_default();
+                    key != "limit" && key != "offset"
+                })
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    params.push(format!("limit={}", limit));
+    params.push(format!("offset={}", offset));
+
+    format!("{}?{}", req.uri().path(), params.join("&"))
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Paginated<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let links = self.link_header(req);
+        let total = self.total;
+
+        let mut response = if wants_envelope(req) {
+            let page = Page { items: self.items, total, limit: self.limit, offset: self.offset };
+            ApiJson(page).respond_to(req)?
+        } else {
+            ApiJson(self.items).respond_to(req)?
+        };
+        response.set_header(Header::new("X-Total-Count", total.to_string()));
+        if let Some(links) = links {
+            response.set_header(Header::new("Link", links));
+        }
+
+        Ok(response)
+    }
+}
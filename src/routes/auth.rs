@@ -0,0 +1,107 @@
+//! Authentication Routes
+//!
+//! This module provides REST API endpoints for authentication:
+//! - POST /auth/token - Exchange the configured staff credential for a bearer token
+//! - POST /auth/customer-token - Issue a customer's token (staff only)
+
+use rocket::serde::json::Json;
+use rocket::{post, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+use subtle::ConstantTimeEq;
+
+use crate::DbConn;
+use crate::auth::{issue_token, StaffUser};
+use crate::config::AppConfig;
+use crate::error::ApiError;
+use crate::guards::JsonBody;
+use crate::models::auth::{CustomerTokenRequest, Role, TokenApiModel, TokenRequest};
+use crate::schema::customers;
+
+/// Issue a bearer token
+///
+/// Exchanges `AUTH_USERNAME`/`AUTH_PASSWORD` for a JWT accepted by the write
+/// endpoints for `JWT_TTL_MINUTES`. The credential is the staff one, so the token
+/// always carries the `employee` role; a `role` in the body is rejected (422).
+/// Meant for tooling and tests; there is no user store behind it.
+/// Returns 401 if the credential does not match.
+/// Returns 403 if `JWT_SECRET` or the credential is not configured.
+/// Returns 200 with `{"access_token", "token_type": "Bearer", "expires_in", "role"}`.
+#[post("/auth/token", data = "<request>")]
+pub async fn issue_auth_token(
+    config: &State<AppConfig>,
+    request: JsonBody<TokenRequest>,
+) -> Result<Json<TokenApiModel>, ApiError> {
+    let request = request.into_inner();
+    let settings = &config.auth;
+    let (Some(_), Some(username), Some(password)) =
+        (&settings.jwt_secret, &settings.username, &settings.password)
+    else {
+        warn!("Authorization error: token issuing is disabled (JWT_SECRET/AUTH_USERNAME/AUTH_PASSWORD not set)");
+        return Err(ApiError::new(Status::Forbidden, "Token issuing is disabled."));
+    };
+
+    // Both parts are always compared, in constant time, like the admin token
+    let username_matches = request.username.as_bytes().ct_eq(username.as_bytes());
+    let password_matches = request.password.as_bytes().ct_eq(password.as_bytes());
+    if !bool::from(username_matches & password_matches) {
+        warn!("Authentication error: invalid credential for {}", request.username);
+        return Err(ApiError::new(Status::Unauthorized, "Invalid username or password."));
+    }
+
+    let role = Role::Employee;
+    let (access_token, expires_in) = issue_token(settings, username, role).ok_or(Status::InternalServerError)?;
+
+    Ok(Json(TokenApiModel { access_token, token_type: "Bearer", expires_in, role }))
+}
+
+/// Issue a customer's bearer token
+///
+/// Staff (e.g. at the counter or a kiosk) exchange `{"customer_id"}` for a token
+/// with the `customer` role whose subject is that customer's id. It lets the
+/// customer read their own orders and nothing of other customers.
+/// Returns 401/403 unless called with a staff token.
+/// Returns 403 if `JWT_SECRET` is not configured.
+/// Returns 404 if the customer is unknown or soft-deleted.
+/// Returns 200 with the token, like POST /auth/token.
+#[post("/auth/customer-token", data = "<request>")]
+pub async fn issue_customer_token(
+    _staff: StaffUser,
+    config: &State<AppConfig>,
+    conn: DbConn,
+    request: JsonBody<CustomerTokenRequest>,
+) -> Result<Json<TokenApiModel>, ApiError> {
+    let customer_id = request.into_inner().customer_id;
+    let settings = config.auth.clone();
+    if settings.jwt_secret.is_none() {
+        warn!("Authorization error: token issuing is disabled (JWT_SECRET not set)");
+        return Err(ApiError::new(Status::Forbidden, "Token issuing is disabled."));
+    }
+
+    conn.run(move |c| {
+        let customer_exists = customers::table
+            .filter(customers::id.eq(&customer_id))
+            .filter(customers::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("Database error loading customer {}: {}", customer_id, e);
+                ApiError::from(e)
+            })?
+            > 0;
+        if !customer_exists {
+            return Err(Status::NotFound.into());
+        }
+
+        let role = Role::Customer;
+        let (access_token, expires_in) =
+            issue_token(&settings, &customer_id, role).ok_or(Status::InternalServerError)?;
+
+        Ok(Json(TokenApiModel { access_token, token_type: "Bearer", expires_in, role }))
+    })
+    .await
+}
+
+pub fn routes() -> Vec<Route> {
+    routes![issue_auth_token, issue_customer_token]
+}
@@ -0,0 +1,115 @@
+//! Checkout Routes
+//! 
+//! This module provides REST API endpoints for walk-in flows:
+//! - POST /checkout - Create a customer and their first order in one call
+
+use rocket::serde::json::Json;
+use rocket::response::status;
+use rocket::{post, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::DbConn;
+use crate::auth::StaffUser;
+use crate::config::AppConfig;
+use crate::error::ApiError;
+use crate::guards::ShopOpen;
+use crate::logic::customers::{ensure_email_available, validate_new_customer};
+use crate::logic::orders::{
+    check_minimum_total, check_references, insert_order, item_problems, placed_at_problem, reject_first,
+};
+use crate::validation::normalize_email;
+use crate::models::checkout::{CheckoutApiModel, IncomingCheckout};
+use crate::models::customer::Customer;
+use crate::models::order::NewOrder;
+use crate::schema::customers;
+
+/// Check out a walk-in customer
+/// 
+/// Creates a new customer and an order for them (items and extras included) in a
+/// single transaction: if any row fails to insert, neither the customer nor the
+/// order is kept. Everything is validated before the transaction starts, with the
+/// same rules as POST /customers and POST /orders.
+/// Requires a staff token, like POST /customers; returns 403 for a customer token.
+/// Returns 423 outside the configured opening hours.
+/// Returns 400 if the customer name/email or employee_id is invalid, the order has
+/// no items, an item is invalid, or placed_at is more than `MAX_CLOCK_SKEW_SECONDS`
+/// in the future.
+/// Returns 409 if an active customer already uses the email.
+/// Returns 422 if the initial status is not `pending` or `paid`, the subtotal is below
+/// `MIN_ORDER_TOTAL`, the employee doesn't exist, or an item's drink, size, extras or
+/// modifiers are invalid (see POST /orders).
+/// Returns 201 with the created order, its customer embedded, and its Location.
+/// Returns 500 if the transaction fails.
+#[post("/checkout", data = "<incoming_checkout>")]
+pub async fn checkout(
+    _staff: StaffUser,
+    _open: ShopOpen,
+    conn: DbConn,
+    config: &State<AppConfig>,
+    incoming_checkout: Json<IncomingCheckout>,
+) -> Result<status::Created<Json<CheckoutApiModel>>, ApiError> {
+    // Validate input fields
+    let mut checkout_data = incoming_checkout.into_inner();
+    checkout_data.customer.email = normalize_email(&checkout_data.customer.email);
+
+    validate_new_customer(&checkout_data.customer)?;
+
+    if checkout_data.employee_id.trim().is_empty() {
+        warn!("Validation error: employee_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    reject_first(item_problems(&checkout_data.items))?;
+    reject_first(placed_at_problem(checkout_data.placed_at, config.max_clock_skew_seconds).into_iter().collect())?;
+
+    let min_order_total = config.min_order_total;
+    let created = conn.run(move |c| {
+        ensure_email_available(c, &checkout_data.customer.email, None)?;
+
+        check_references(c, None, &checkout_data.employee_id, &checkout_data.items)?;
+        check_minimum_total(c, &checkout_data.items, min_order_total)?;
+
+        c.transaction(|tx| {
+            let db_customer = Customer::from_new(checkout_data.customer, Uuid::new_v4().to_string());
+
+            diesel::insert_into(customers::table)
+                .values(&db_customer)
+                .execute(tx)?;
+
+            let db_order = insert_order(
+                tx,
+                NewOrder {
+                    customer_id: db_customer.id.clone(),
+                    employee_id: checkout_data.employee_id,
+                    status: checkout_data.status,
+                    placed_at: checkout_data.placed_at,
+                },
+                checkout_data.items,
+            )?;
+
+            Ok(CheckoutApiModel {
+                order: db_order.to_api_model(),
+                customer: db_customer.to_api_model(),
+            })
+        })
+        .map_err(|e: diesel::result::Error| {
+            error!("Database error during checkout: {}", e);
+            // The email pre-check can race with another insert; the UNIQUE constraint catches it
+            ApiError::from(e)
+        })
+    })
+    .await?;
+
+    let location = format!("/api/orders/{}", created.order.id);
+    Ok(status::Created::new(location).body(Json(created)))
+}
+
+/// Export all checkout routes
+/// 
+/// This function returns all checkout-related routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![checkout]
+}
@@ -1,37 +1,140 @@
-/// Customer Management Routes
-/// 
-/// This module provides REST API endpoints for customer operations:
-/// - GET /customers - List all customers
-/// - GET /customers/{id} - Get customer by ID
-/// - POST /customers - Create new customer
-/// - PUT /customers/{id} - Update customer
-/// - DELETE /customers/{id} - Soft delete customer (future implementation)
+//! Customer Management Routes
+//! 
+//! This module provides REST API endpoints for customer operations:
+//! - GET /customers - List all customers, optionally by creation window or with deleted ones
+//! - GET /customers?ids=a,b,c - Bulk-fetch customers by id
+//! - GET /customers/{id} - Get customer by ID
+//! - GET /customers/{id}/orders - Order history of a customer, newest first
+//! - POST /customers - Create new customer
+//! - PUT /customers/{id} - Update customer
+//! - PATCH /customers/{id} - Change only the given fields (name, email, phone)
+//! - DELETE /customers/{id} - Soft delete customer
+//! - POST /customers/{id}/restore - Undo a soft delete
+//! - POST /customers/{id}/anonymize - Erase customer PII (admin only)
 
 use rocket::serde::json::Json;
-use rocket::{get, post, put, routes, Route};
+use rocket::response::status;
+use rocket::{delete, get, patch, post, put, routes, Route};
 use rocket::http::Status;
 use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::DbConn;
-use crate::models::customer::{Customer, CustomerApiModel, NewCustomer, UpdateCustomer};
-use crate::schema::customers;
+use crate::auth::{AuthenticatedUser, StaffUser};
+use crate::cache::row_etag;
+use crate::error::{ApiError, ErrorApiModel};
+use crate::logging::RequestId;
+use crate::guards::{AdminUser, IfNoneMatch, IncludeDeleted, InvalidUuid, UuidParam};
+use crate::logic::customers::{ensure_email_available, validate_new_customer};
+use crate::responders::{ApiJson, Paginated, Tagged, page_window};
+use crate::models::infra::patch::Patch;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteOrderStatus};
+use crate::models::customer::{Customer, CustomerApiModel, NewCustomer, PatchCustomer, UpdateCustomer};
+use crate::models::order::{Order, OrderApiModel};
+use crate::models::order_status::OrderStatus;
+use crate::schema::{customers, orders};
+use crate::validation::{normalize_email, validate_email, validate_phone};
+
+/// Maximum number of ids accepted by the bulk lookup (`?ids=`)
+const MAX_BULK_IDS: usize = 100;
 
 /// Get all customers
 /// 
-/// Returns a list of all active customers (not soft-deleted).
-/// This endpoint supports pagination in future iterations.
-#[get("/customers")]
-pub async fn get_customers(conn: DbConn) -> Result<Json<Vec<CustomerApiModel>>, Status> {
-    conn.run(|c| {
-        // Query all customers where deleted_at is NULL (active customers only)
-        let results = customers::table
-            .filter(customers::deleted_at.is_null())
+/// Returns a page of active customers (not soft-deleted), oldest first.
+/// Pagination is driven by `?limit=` (default `DEFAULT_PAGE_LIMIT`, 50) and `?offset=`
+/// (default 0); the body stays a bare array while `X-Total-Count` and `Link`
+/// headers describe the other pages.
+/// `?created_after=` and `?created_before=` keep customers created within that
+/// window; both bounds are inclusive and accept RFC 3339, a naive UTC
+/// `YYYY-MM-DDTHH:MM:SS` timestamp or epoch milliseconds.
+/// `?include_deleted=true` lists soft-deleted customers too (their `deleted_at` is
+/// set); it requires a staff caller, 401/403 otherwise.
+/// Returns 400 if limit is above `MAX_PAGE_LIMIT` (200) or below 1, offset is negative,
+/// a bound cannot be parsed or created_after is later than created_before.
+///
+/// When `?ids=a,b,c` is given, only active customers whose id is in the list
+/// are returned. Unknown or soft-deleted ids are skipped silently, blank entries
+/// are ignored and duplicates are collapsed. The bulk lookup takes precedence
+/// over any other list parameter: when `ids` is present the remaining filters
+/// and pagination are ignored.
+/// Returns 400 if more than `MAX_BULK_IDS` ids are requested.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(
+        ("ids" = Option<String>, Query, description = "Comma-separated ids to bulk-fetch (at most 100)"),
+        ("limit" = Option<i64>, Query, description = "Page size, 1 to 200 (default 50)"),
+        ("offset" = Option<i64>, Query, description = "Rows to skip (default 0)"),
+        ("created_after" = Option<String>, Query, description = "Inclusive lower bound on created_at"),
+        ("created_before" = Option<String>, Query, description = "Inclusive upper bound on created_at"),
+        ("include_deleted" = Option<bool>, Query, description = "List soft-deleted customers too (staff only)"),
+    ),
+    responses(
+        (status = 200, description = "One page of customers", body = [CustomerApiModel], headers(
+            ("X-Total-Count" = i64, description = "Customers matching across all pages"),
+            ("Link" = String, description = "next/prev page links"),
+        )),
+        (status = 400, description = "Invalid pagination, bound or ids", body = ErrorApiModel),
+        (status = 401, description = "include_deleted without a valid token", body = ErrorApiModel),
+        (status = 403, description = "include_deleted with a non-staff token", body = ErrorApiModel),
+    )
+)]
+#[get("/customers?<ids>&<limit>&<offset>&<created_after>&<created_before>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_customers(
+    conn: DbConn,
+    ids: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    created_after: Option<String>,
+    created_before: Option<String>,
+    include_deleted: IncludeDeleted,
+    request_id: RequestId,
+) -> Result<Paginated<CustomerApiModel>, ApiError> {
+    if let Some(ids) = ids {
+        return get_customers_by_ids(conn, ids, request_id).await;
+    }
+
+    let (limit, offset) = page_window(limit, offset)?;
+    let IncludeDeleted(include_deleted) = include_deleted;
+    let created_after = created_after
+        .as_deref()
+        .map(|raw| created_bound("created_after", raw, &request_id))
+        .transpose()?;
+    let created_before = created_before
+        .as_deref()
+        .map(|raw| created_bound("created_before", raw, &request_id))
+        .transpose()?;
+
+    if let (Some(after), Some(before)) = (created_after, created_before)
+        && after > before
+    {
+        warn!("[{}] Validation error: created_after is later than created_before", request_id);
+        return Err(ApiError::bad_request("created_after cannot be later than created_before"));
+    }
+
+    conn.run(move |c| {
+        // Count all matching customers so clients know how many pages exist
+        let total = listed_customers(include_deleted, created_after, created_before)
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("[{}] Database error counting customers: {}", request_id, e);
+                ApiError::from(e)
+            })?;
+
+        // Query one page of customers, active ones only unless include_deleted is set
+        let results = listed_customers(include_deleted, created_after, created_before)
+            .order((customers::created_at.asc(), customers::id.asc()))
+            .limit(limit)
+            .offset(offset)
             .select(Customer::as_select())
             .load(c)
             .map_err(|e| {
-                eprintln!("Database error loading customers: {}", e);
-                Status::InternalServerError
+                error!("[{}] Database error loading customers: {}", request_id, e);
+                ApiError::from(e)
             })?;
 
         // Convert database models to API models
@@ -40,23 +143,106 @@ pub async fn get_customers(conn: DbConn) -> Result<Json<Vec<CustomerApiModel>>,
             .map(|customer| customer.to_api_model())
             .collect();
 
-        Ok(Json(api_customers))
+        Ok(Paginated { items: api_customers, total, limit, offset })
+    })
+    .await
+}
+
+/// Active customers (all of them with `include_deleted`), narrowed to those
+/// created within `[after, before]`
+fn listed_customers(
+    include_deleted: bool,
+    after: Option<SqliteDateTime>,
+    before: Option<SqliteDateTime>,
+) -> customers::BoxedQuery<'static, Sqlite> {
+    let mut query = customers::table.into_boxed();
+
+    if !include_deleted {
+        query = query.filter(customers::deleted_at.is_null());
+    }
+
+    // created_at is ISO 8601 text, so the bounds compare lexically in time order
+    if let Some(after) = after {
+        query = query.filter(customers::created_at.ge(after));
+    }
+    if let Some(before) = before {
+        query = query.filter(customers::created_at.le(before));
+    }
+
+    query
+}
+
+/// Parse a `created_after`/`created_before` bound, 400 if it is not a timestamp
+fn created_bound(name: &str, raw: &str, request_id: &RequestId) -> Result<SqliteDateTime, ApiError> {
+    SqliteDateTime::parse(raw).ok_or_else(|| {
+        warn!("[{}] Validation error: {} '{}' is not a valid timestamp", request_id, name, raw);
+        ApiError::bad_request(format!("{} '{}' is not a valid timestamp", name, raw))
+    })
+}
+
+/// Bulk lookup backing `GET /customers?ids=...`
+async fn get_customers_by_ids(
+    conn: DbConn,
+    ids: String,
+    request_id: RequestId,
+) -> Result<Paginated<CustomerApiModel>, ApiError> {
+    let mut id_list: Vec<String> = ids
+        .split(',')
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect();
+    id_list.sort();
+    id_list.dedup();
+
+    if id_list.len() > MAX_BULK_IDS {
+        warn!("[{}] Validation error: at most {} ids can be requested at once", request_id, MAX_BULK_IDS);
+        return Err(Status::BadRequest.into());
+    }
+
+    conn.run(move |c| {
+        let results = customers::table
+            .filter(customers::id.eq_any(&id_list))
+            .filter(customers::deleted_at.is_null())
+            .select(Customer::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("[{}] Database error loading customers by ids: {}", request_id, e);
+                ApiError::from(e)
+            })?;
+
+        // A bulk lookup is always a single page
+        let items: Vec<CustomerApiModel> = results.into_iter().map(|customer| customer.to_api_model()).collect();
+        let total = items.len() as i64;
+        Ok(Paginated { items, total, limit: total, offset: 0 })
     })
     .await
 }
 
 /// Get customer by ID
 /// 
-/// Returns a single customer by their unique ID.
-/// Returns 400 if customer_id is empty.
+/// Returns a single customer by their unique ID, with a weak ETag derived from
+/// their `updated_at`. Answers 304 Not Modified when `If-None-Match` carries that ETag.
+/// Returns 400 if customer_id is not a UUID.
 /// Returns 404 if customer not found or is soft-deleted.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    responses(
+        (status = 200, description = "The customer", body = CustomerApiModel, headers(("ETag" = String, description = "Weak entity tag"))),
+        (status = 304, description = "Unchanged since the If-None-Match tag"),
+        (status = 404, description = "Customer not found or soft-deleted", body = ErrorApiModel),
+    )
+)]
 #[get("/customers/<customer_id>")]
-pub async fn get_customer(conn: DbConn, customer_id: String) -> Result<Json<CustomerApiModel>, Status> {
-    // Validate customer_id is not empty
-    if customer_id.trim().is_empty() {
-        eprintln!("Validation error: customer_id cannot be empty");
-        return Err(Status::BadRequest);
-    }
+pub async fn get_customer(
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    if_none_match: IfNoneMatch,
+    request_id: RequestId,
+) -> Result<Tagged<CustomerApiModel>, ApiError> {
+    let customer_id = customer_id?.into_inner();
 
     conn.run(move |c| {
         let customer = customers::table
@@ -65,14 +251,105 @@ pub async fn get_customer(conn: DbConn, customer_id: String) -> Result<Json<Cust
             .select(Customer::as_select())
             .first(c)
             .map_err(|e| match e {
-                diesel::result::Error::NotFound => Status::NotFound,
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
                 _ => {
-                    eprintln!("Database error loading customer {}: {}", customer_id, e);
-                    Status::InternalServerError
+                    error!("[{}] Database error loading customer {}: {}", request_id, customer_id, e);
+                    ApiError::from(e)
                 }
             })?;
 
-        Ok(Json(customer.to_api_model()))
+        let etag = row_etag(customer.updated_at);
+        if if_none_match.matches(&etag) {
+            return Ok(Tagged { etag, body: None });
+        }
+
+        Ok(Tagged { etag, body: Some(customer.to_api_model()) })
+    })
+    .await
+}
+
+/// Get a customer's order history
+/// 
+/// Returns every order placed by the customer, newest first (ties broken by id),
+/// optionally only those in `?status=`. Unlike GET /orders?customer_id=, the
+/// customer itself is checked first, so an unknown id is a 404 rather than an
+/// empty list.
+/// Requires a token: staff may read any customer's orders, a customer token
+/// (POST /auth/customer-token) only the orders of its own customer.
+/// Returns 400 if customer_id is not a UUID or status is not a known order status.
+/// Returns 401 without a valid token, 403 for another customer's token.
+/// Returns 404 if customer not found or is soft-deleted.
+/// Returns an empty array if the customer has no (matching) orders.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(
+        ("customer_id" = String, Path, description = "Customer id"),
+        ("status" = Option<OrderStatus>, Query, description = "Only orders in this status"),
+    ),
+    responses(
+        (status = 200, description = "The customer's orders, newest first", body = [OrderApiModel]),
+        (status = 400, description = "Unknown status", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token of another customer", body = ErrorApiModel),
+        (status = 404, description = "Customer not found or soft-deleted", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[get("/customers/<customer_id>/orders?<status>")]
+pub async fn get_customer_orders(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    status: Option<String>,
+    request_id: RequestId,
+) -> Result<ApiJson<Vec<OrderApiModel>>, ApiError> {
+    let customer_id = customer_id?.into_inner();
+    if !user.can_access_customer(&customer_id) {
+        warn!("[{}] Authorization error: customer {} cannot read orders of {}", request_id, user.subject, customer_id);
+        return Err(ApiError::new(Status::Forbidden, "Customers may only read their own orders."));
+    }
+    let status = match status.as_deref().map(OrderStatus::from_str).transpose() {
+        Ok(status) => status.map(SqliteOrderStatus::from),
+        Err(message) => {
+            warn!("[{}] Validation error: {}", request_id, message);
+            return Err(ApiError::bad_request(message));
+        }
+    };
+
+    conn.run(move |c| {
+        let customer_exists = customers::table
+            .filter(customers::id.eq(&customer_id))
+            .filter(customers::deleted_at.is_null())
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("[{}] Database error loading customer {}: {}", request_id, customer_id, e);
+                ApiError::from(e)
+            })?
+            > 0;
+        if !customer_exists {
+            return Err(Status::NotFound.into());
+        }
+
+        let mut query = orders::table
+            .inner_join(customers::table)
+            .filter(customers::id.eq(&customer_id))
+            .select(Order::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(orders::status.eq(status));
+        }
+
+        let results = query
+            .order((orders::created_at.desc(), orders::id.desc()))
+            .load(c)
+            .map_err(|e| {
+                error!("[{}] Database error loading orders of customer {}: {}", request_id, customer_id, e);
+                ApiError::from(e)
+            })?;
+
+        Ok(ApiJson(results.into_iter().map(|order| order.to_api_model()).collect()))
     })
     .await
 }
@@ -81,34 +358,41 @@ pub async fn get_customer(conn: DbConn, customer_id: String) -> Result<Json<Cust
 /// 
 /// Creates a new customer with the provided information.
 /// Generates a UUID for the customer ID automatically.
-/// Returns 400 if name or email is empty.
-/// Returns the created customer with all fields populated.
+/// Returns 400 if name or email is empty, or the email is malformed.
+/// Returns 409 naming the email if an active customer already uses it.
+/// Returns 201 with the created customer (all fields populated) and its Location.
 /// Note: created_at and updated_at are handled automatically server-side.
+/// Requires a staff token; returns 403 for a customer token.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    request_body = NewCustomer,
+    responses(
+        (status = 201, description = "Customer created", body = CustomerApiModel, headers(("Location" = String, description = "URL of the new customer"))),
+        (status = 400, description = "Invalid name or email", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 409, description = "Email already in use", body = ErrorApiModel),
+        (status = 422, description = "Malformed body", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
 #[post("/customers", data = "<new_customer>")]
 pub async fn create_customer(
+    _staff: StaffUser,
     conn: DbConn,
     new_customer: Json<NewCustomer>,
-) -> Result<Json<CustomerApiModel>, Status> {
+    request_id: RequestId,
+) -> Result<status::Created<Json<CustomerApiModel>>, ApiError> {
     // Validate input fields
-    let customer_data = new_customer.into_inner();
-    
-    if customer_data.name.trim().is_empty() {
-        eprintln!("Validation error: customer name cannot be empty");
-        return Err(Status::BadRequest);
-    }
-    
-    if customer_data.email.trim().is_empty() {
-        eprintln!("Validation error: customer email cannot be empty");
-        return Err(Status::BadRequest);
-    }
-    
-    // Basic email validation
-    if !customer_data.email.contains('@') {
-        eprintln!("Validation error: invalid email format");
-        return Err(Status::BadRequest);
-    }
+    let mut customer_data = new_customer.into_inner();
+    customer_data.email = normalize_email(&customer_data.email);
+    validate_new_customer(&customer_data)?;
 
     conn.run(move |c| {
+        // Reject an email already used by an active customer with a specific message
+        ensure_email_available(c, &customer_data.email, None)?;
+
         // Generate a new UUID for the customer
         let customer_id = Uuid::new_v4().to_string();
         
@@ -120,17 +404,14 @@ pub async fn create_customer(
             .values(&db_customer)
             .execute(c)
             .map_err(|e| {
-                eprintln!("Database error creating customer: {}", e);
-                // Check for unique constraint violation (email)
-                if e.to_string().contains("UNIQUE constraint failed") {
-                    Status::Conflict
-                } else {
-                    Status::InternalServerError
-                }
+                error!("[{}] Database error creating customer: {}", request_id, e);
+                // A unique constraint violation (email) becomes a 409
+                ApiError::from(e)
             })?;
 
         // Return the created customer
-        Ok(Json(db_customer.to_api_model()))
+        let location = format!("/api/customers/{}", db_customer.id);
+        Ok(status::Created::new(location).body(Json(db_customer.to_api_model())))
     })
     .await
 }
@@ -138,78 +419,372 @@ pub async fn create_customer(
 /// Update customer by ID
 /// 
 /// Updates an existing customer with new information.
-/// Returns 400 if customer_id is empty or if name/email are empty.
+/// Returns 400 if customer_id is not a UUID, if name/email are empty or the email is malformed.
 /// Returns 422 if request contains unknown fields (only name and email are allowed).
 /// Returns 404 if customer not found or is soft-deleted.
+/// Returns 409 naming the email if another active customer already uses it
+/// (compared case-insensitively). The UNIQUE constraint remains as a backstop.
 /// Note: updated_at is handled automatically server-side, created_at remains unchanged.
+/// A customer token may only change its own customer; returns 403 otherwise.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    request_body = UpdateCustomer,
+    responses(
+        (status = 200, description = "Customer updated", body = CustomerApiModel),
+        (status = 400, description = "Invalid name or email", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token of another customer", body = ErrorApiModel),
+        (status = 404, description = "Customer not found or soft-deleted", body = ErrorApiModel),
+        (status = 409, description = "Email already in use", body = ErrorApiModel),
+        (status = 422, description = "Malformed body or unknown fields", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
 #[put("/customers/<customer_id>", data = "<update_customer>")]
 pub async fn update_customer(
+    user: AuthenticatedUser,
     conn: DbConn,
-    customer_id: String,
+    customer_id: Result<UuidParam, InvalidUuid>,
     update_customer: Json<UpdateCustomer>,
-) -> Result<Json<CustomerApiModel>, Status> {
-    // Validate customer_id is not empty
-    if customer_id.trim().is_empty() {
-        eprintln!("Validation error: customer_id cannot be empty");
-        return Err(Status::BadRequest);
+    request_id: RequestId,
+) -> Result<Json<CustomerApiModel>, ApiError> {
+    let customer_id = customer_id?.into_inner();
+    if !user.can_access_customer(&customer_id) {
+        warn!("[{}] Authorization error: customer {} cannot change customer {}", request_id, user.subject, customer_id);
+        return Err(ApiError::new(Status::Forbidden, "Customers may only change their own details."));
     }
 
     // Validate input fields
-    let update_data = update_customer.into_inner();
+    let mut update_data = update_customer.into_inner();
+    update_data.email = normalize_email(&update_data.email);
     
     if update_data.name.trim().is_empty() {
-        eprintln!("Validation error: customer name cannot be empty");
-        return Err(Status::BadRequest);
+        warn!("[{}] Validation error: customer name cannot be empty", request_id);
+        return Err(ApiError::bad_request("customer name cannot be empty"));
     }
     
     if update_data.email.trim().is_empty() {
-        eprintln!("Validation error: customer email cannot be empty");
-        return Err(Status::BadRequest);
+        warn!("[{}] Validation error: customer email cannot be empty", request_id);
+        return Err(ApiError::bad_request("customer email cannot be empty"));
     }
     
-    // Basic email validation
-    if !update_data.email.contains('@') {
-        eprintln!("Validation error: invalid email format");
-        return Err(Status::BadRequest);
+    validate_email(&update_data.email)?;
+
+    conn.run(move |c| {
+        save_customer_changes(c, &customer_id, |customer| customer.update_from_input(update_data), &request_id).map(Json)
+    })
+    .await
+}
+
+/// Partially update customer by ID
+/// 
+/// Changes only the fields present in the body (`name`, `email`, `phone`); the
+/// others keep their current value, and `"phone": null` clears the phone. Use PUT
+/// to replace both name and email.
+/// Returns 400 if customer_id is not a UUID, no field is given, or a given name/email
+/// is empty or the email or phone is malformed.
+/// Returns 422 if request contains unknown fields.
+/// Returns 404 if customer not found or is soft-deleted.
+/// Returns 409 naming the email if another active customer already uses it.
+/// A customer token may only change its own customer; returns 403 otherwise.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    request_body = PatchCustomer,
+    responses(
+        (status = 200, description = "Customer updated", body = CustomerApiModel),
+        (status = 400, description = "Invalid name or email", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token of another customer", body = ErrorApiModel),
+        (status = 404, description = "Customer not found or soft-deleted", body = ErrorApiModel),
+        (status = 409, description = "Email already in use", body = ErrorApiModel),
+        (status = 422, description = "Malformed body or unknown fields", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[patch("/customers/<customer_id>", data = "<patch_customer>")]
+pub async fn patch_customer(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    patch_customer: Json<PatchCustomer>,
+    request_id: RequestId,
+) -> Result<Json<CustomerApiModel>, ApiError> {
+    let customer_id = customer_id?.into_inner();
+    if !user.can_access_customer(&customer_id) {
+        warn!("[{}] Authorization error: customer {} cannot change customer {}", request_id, user.subject, customer_id);
+        return Err(ApiError::new(Status::Forbidden, "Customers may only change their own details."));
+    }
+
+    let mut patch_data = patch_customer.into_inner();
+    patch_data.email = patch_data.email.as_deref().map(normalize_email);
+
+    if patch_data.name.is_none() && patch_data.email.is_none() && patch_data.phone == Patch::Absent {
+        warn!("[{}] Validation error: customer patch has no fields", request_id);
+        return Err(ApiError::bad_request("give at least one of name, email and phone"));
+    }
+
+    if let Patch::Value(phone) = &patch_data.phone {
+        validate_phone(phone)?;
+    }
+
+    if patch_data.name.as_ref().is_some_and(|name| name.trim().is_empty()) {
+        warn!("[{}] Validation error: customer name cannot be empty", request_id);
+        return Err(ApiError::bad_request("customer name cannot be empty"));
+    }
+
+    if let Some(email) = &patch_data.email {
+        if email.trim().is_empty() {
+            warn!("[{}] Validation error: customer email cannot be empty", request_id);
+            return Err(ApiError::bad_request("customer email cannot be empty"));
+        }
+
+        validate_email(email)?;
     }
 
     conn.run(move |c| {
-        // First, find the existing customer
+        save_customer_changes(c, &customer_id, |customer| customer.apply_patch(patch_data), &request_id).map(Json)
+    })
+    .await
+}
+
+/// Load an active customer, apply `change` and save it
+///
+/// Rejects the result with 409 when its email is already used by another
+/// active customer (compared case-insensitively); the UNIQUE constraint remains
+/// as a backstop. 404 if the customer is missing or soft-deleted.
+fn save_customer_changes(
+    c: &mut diesel::SqliteConnection,
+    customer_id: &str,
+    change: impl FnOnce(&mut Customer),
+    request_id: &RequestId,
+) -> Result<CustomerApiModel, ApiError> {
+    let mut existing_customer = customers::table
+        .filter(customers::id.eq(customer_id))
+        .filter(customers::deleted_at.is_null())
+        .select(Customer::as_select())
+        .first(c)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+            _ => {
+                error!("[{}] Database error loading customer {}: {}", request_id, customer_id, e);
+                ApiError::from(e)
+            }
+        })?;
+
+    change(&mut existing_customer);
+
+    // Reject an email already used by another active customer before touching the row
+    ensure_email_available(c, &existing_customer.email, Some(customer_id))?;
+
+    diesel::update(customers::table.filter(customers::id.eq(customer_id)))
+        .set((
+            customers::name.eq(&existing_customer.name),
+            customers::email.eq(&existing_customer.email),
+            customers::phone.eq(&existing_customer.phone),
+            customers::updated_at.eq(&existing_customer.updated_at),
+        ))
+        .execute(c)
+        .map_err(|e| {
+            error!("[{}] Database error updating customer {}: {}", request_id, customer_id, e);
+            // A unique constraint violation (email) becomes a 409
+            ApiError::from(e)
+        })?;
+
+    Ok(existing_customer.to_api_model())
+}
+
+/// Soft delete customer by ID
+/// 
+/// Sets deleted_at instead of removing the row, so the customer's orders keep
+/// resolving. The customer disappears from lists and lookups afterwards.
+/// Returns 400 if customer_id is not a UUID.
+/// Returns 404 if customer not found or already soft-deleted.
+/// Returns 204 No Content on success.
+/// Requires a staff token; returns 403 for a customer token.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    responses(
+        (status = 204, description = "Customer soft-deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Customer not found or already deleted", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[delete("/customers/<customer_id>")]
+pub async fn delete_customer(
+    _staff: StaffUser,
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    request_id: RequestId,
+) -> Result<Status, ApiError> {
+    let customer_id = customer_id?.into_inner();
+
+    conn.run(move |c| {
+        let now = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+
+        // Only active customers match, so a second delete finds nothing
+        let deleted = diesel::update(
+            customers::table
+                .filter(customers::id.eq(&customer_id))
+                .filter(customers::deleted_at.is_null()),
+        )
+        .set(customers::deleted_at.eq(Some(now)))
+        .execute(c);
+
+        match deleted {
+            Ok(0) => Err(Status::NotFound.into()),
+            Ok(_) => Ok(Status::NoContent),
+            Err(e) => {
+                error!("[{}] Database error deleting customer {}: {}", request_id, customer_id, e);
+                Err(ApiError::from(e))
+            }
+        }
+    })
+    .await
+}
+
+/// Restore a soft-deleted customer
+/// 
+/// Clears deleted_at and bumps updated_at, so the customer shows up in lists and
+/// lookups again.
+/// Returns 400 if customer_id is not a UUID.
+/// Returns 404 if customer not found.
+/// Returns 409 if the customer is not deleted.
+/// Returns the restored customer.
+/// Requires a staff token; returns 403 for a customer token.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    responses(
+        (status = 200, description = "Customer restored", body = CustomerApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Customer not found", body = ErrorApiModel),
+        (status = 409, description = "Customer is not deleted", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/customers/<customer_id>/restore")]
+pub async fn restore_customer(
+    _staff: StaffUser,
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    request_id: RequestId,
+) -> Result<Json<CustomerApiModel>, ApiError> {
+    let customer_id = customer_id?.into_inner();
+
+    conn.run(move |c| {
+        c.transaction::<_, ApiError, _>(|c| {
+            let mut customer = customers::table
+                .filter(customers::id.eq(&customer_id))
+                .select(Customer::as_select())
+                .first(c)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                    _ => {
+                        error!("[{}] Database error loading customer {}: {}", request_id, customer_id, e);
+                        ApiError::from(e)
+                    }
+                })?;
+
+            if customer.deleted_at.is_none() {
+                warn!("[{}] Conflict: customer {} is not deleted", request_id, customer_id);
+                return Err(ApiError::conflict("customer is not deleted"));
+            }
+
+            customer.restore();
+
+            diesel::update(
+                customers::table
+                    .filter(customers::id.eq(&customer_id))
+                    .filter(customers::deleted_at.is_not_null()),
+            )
+            .set((
+                customers::updated_at.eq(&customer.updated_at),
+                customers::deleted_at.eq(&customer.deleted_at),
+            ))
+            .execute(c)
+            .map_err(|e| {
+                error!("[{}] Database error restoring customer {}: {}", request_id, customer_id, e);
+                ApiError::from(e)
+            })?;
+
+            Ok(Json(customer.to_api_model()))
+        })
+    })
+    .await
+}
+
+/// Anonymize customer by ID
+/// 
+/// Erases personal data for GDPR deletion requests: name and email are replaced
+/// with placeholders, the phone is cleared and the customer is soft-deleted. Unlike a hard delete the row
+/// (and its id) stays in place so existing orders keep resolving; unlike a plain
+/// soft delete no PII is left behind. Already soft-deleted customers can be anonymized.
+/// Requires the admin token (401/403 otherwise).
+/// Returns 400 if customer_id is not a UUID.
+/// Returns 404 if customer not found.
+/// Returns the anonymized customer.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "customers",
+    params(("customer_id" = String, Path, description = "Customer id")),
+    responses(
+        (status = 200, description = "Customer anonymized", body = CustomerApiModel),
+        (status = 401, description = "Missing token or admin token", body = ErrorApiModel),
+        (status = 403, description = "Wrong admin token", body = ErrorApiModel),
+        (status = 404, description = "Customer not found", body = ErrorApiModel),
+    ),
+    security(("bearer" = [], "admin_token" = []))
+)]
+#[post("/customers/<customer_id>/anonymize")]
+pub async fn anonymize_customer(
+    _user: AuthenticatedUser,
+    _admin: AdminUser,
+    conn: DbConn,
+    customer_id: Result<UuidParam, InvalidUuid>,
+    request_id: RequestId,
+) -> Result<Json<CustomerApiModel>, ApiError> {
+    let customer_id = customer_id?.into_inner();
+
+    conn.run(move |c| {
+        // Soft-deleted customers are included: they still hold PII
         let mut existing_customer = customers::table
             .filter(customers::id.eq(&customer_id))
-            .filter(customers::deleted_at.is_null())
             .select(Customer::as_select())
             .first(c)
             .map_err(|e| match e {
-                diesel::result::Error::NotFound => Status::NotFound,
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
                 _ => {
-                    eprintln!("Database error loading customer {}: {}", customer_id, e);
-                    Status::InternalServerError
+                    error!("[{}] Database error loading customer {}: {}", request_id, customer_id, e);
+                    ApiError::from(e)
                 }
             })?;
 
-        // Update the customer data
-        existing_customer.update_from_input(update_data);
+        existing_customer.anonymize();
 
-        // Save the updated customer back to the database
         diesel::update(customers::table.filter(customers::id.eq(&customer_id)))
             .set((
                 customers::name.eq(&existing_customer.name),
                 customers::email.eq(&existing_customer.email),
+                customers::phone.eq(&existing_customer.phone),
                 customers::updated_at.eq(&existing_customer.updated_at),
+                customers::deleted_at.eq(&existing_customer.deleted_at),
             ))
             .execute(c)
             .map_err(|e| {
-                eprintln!("Database error updating customer {}: {}", customer_id, e);
-                // Check for unique constraint violation (email)
-                if e.to_string().contains("UNIQUE constraint failed") {
-                    Status::Conflict
-                } else {
-                    Status::InternalServerError
-                }
+                error!("[{}] Database error anonymizing customer {}: {}", request_id, customer_id, e);
+                ApiError::from(e)
             })?;
 
-        // Return the updated customer
         Ok(Json(existing_customer.to_api_model()))
     })
     .await
@@ -220,5 +795,15 @@ pub async fn update_customer(
 /// This function returns all customer-related routes that should be mounted
 /// on the Rocket application. Mount these under "/api" prefix.
 pub fn routes() -> Vec<Route> {
-    routes![get_customers, get_customer, create_customer, update_customer]
+    routes![
+        get_customers,
+        get_customer,
+        get_customer_orders,
+        create_customer,
+        update_customer,
+        patch_customer,
+        delete_customer,
+        restore_customer,
+        anonymize_customer,
+    ]
 }
@@ -1,38 +1,129 @@
-/// Drink Catalog Routes
-/// 
-/// This module provides REST API endpoints for drink catalog operations:
-/// - GET /drinks - List all available drinks
-/// - GET /drinks/{id} - Get drink by ID
-/// - POST /drinks - Create new drink
-/// - PUT /drinks/{id} - Update drink price (name is immutable)
-/// - DELETE /drinks/{id} - Soft delete drink (future implementation)
+//! Drink Catalog Routes
+//! 
+//! This module provides REST API endpoints for drink catalog operations:
+//! - GET /drinks - List all available drinks (deleted ones too for staff)
+//! - GET /drinks/recent - Drinks changed since a point in time
+//! - GET /drinks/{id} - Get drink by ID
+//! - POST /drinks - Create new drink
+//! - POST /drinks/batch - Create several drinks in one transaction
+//! - PUT /drinks/{id} - Update drink price and sizes (name is immutable)
+//! - PATCH /drinks/{id} - Change only the given fields (price, sizes, category)
+//! - PUT /drinks/{id}/availability - Mark a drink as orderable or not
+//! - GET /drinks/{id}/sizes - Price of a drink in each size it is offered in
+//! - GET /drinks/{id}/extras - List extras available for a drink
+//! - POST /drinks/bulk-adjust - Change prices by a percentage (admin only)
+//! - DELETE /drinks/{id} - Soft delete drink
+//! - POST /drinks/{id}/restore - Undo a soft delete
+
+use std::collections::HashSet;
 
 use rocket::serde::json::Json;
-use rocket::{get, post, put, routes, Route};
+use rocket::response::status;
+use rocket::{delete, get, patch, post, put, routes, Route, State};
 use rocket::http::Status;
 use diesel::prelude::*;
+use diesel::dsl::sql;
+use diesel::sql_types::Double;
+use diesel::sqlite::Sqlite;
 use uuid::Uuid;
 use rust_decimal::Decimal;
 
 use crate::DbConn;
-use crate::models::drink::{Drink, DrinkApiModel, NewDrink, UpdateDrink};
-use crate::schema::drinks;
+use crate::auth::StaffUser;
+use crate::db::{like_contains, lower, LIKE_ESCAPE};
+use crate::cache::{row_etag, CatalogVersion};
+use crate::error::{ApiError, ErrorApiModel};
+use crate::logging::RequestId;
+use crate::guards::{AdminUser, IfNoneMatch, IncludeDeleted, InvalidUuid, JsonBody, UuidParam};
+use crate::logic::drinks::{adjust_price, ensure_drink_names_available, record_price_change};
+use crate::responders::{ApiJson, Paginated, Tagged, page_window};
+use crate::models::drink::{
+    normalize_category, BulkPriceAdjustment, BulkPriceAdjustmentApiModel, Drink, DrinkApiModel,
+    DrinkSizePriceApiModel, NewDrink, PatchDrink, UpdateDrink, UpdateDrinkAvailability,
+};
+use crate::models::extra::{Extra, ExtraApiModel};
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteDecimal};
+use crate::schema::{drink_extras, drinks, extras};
+use crate::validation::validate_price;
 
 /// Get all drinks
 /// 
-/// Returns a list of all available drinks (not soft-deleted).
-/// This endpoint supports filtering and pagination in future iterations.
-#[get("/drinks")]
-pub async fn get_drinks(conn: DbConn) -> Result<Json<Vec<DrinkApiModel>>, Status> {
-    conn.run(|c| {
-        // Query all drinks where deleted_at is NULL (available drinks only)
-        let results = drinks::table
-            .filter(drinks::deleted_at.is_null())
+/// Returns a page of available drinks (not soft-deleted), newest first unless
+/// `?sort_by=` (`name`, `base_price` or `created_at`) and `?order=` (`asc` or `desc`,
+/// default `asc` once sort_by is given) say otherwise. Prices are compared as numbers.
+/// Pagination is driven by `?limit=` (default `DEFAULT_PAGE_LIMIT`, 50) and `?offset=`
+/// (default 0); the body stays a bare array while `X-Total-Count` and `Link`
+/// headers describe the other pages.
+/// `?search=` narrows the list to drinks whose name contains the term, ignoring
+/// case; `%` and `_` in the term match literally. A blank term is ignored.
+/// With `?available_only=true` only drinks that can currently be ordered are listed.
+/// `?include_deleted=true` lists soft-deleted drinks too (their `deleted_at` is
+/// set); it requires a staff caller, 401/403 otherwise.
+/// Returns 400 if limit is above `MAX_PAGE_LIMIT` (200) or below 1, offset is negative,
+/// or sort_by/order is not one of the values above.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(
+        ("limit" = Option<i64>, Query, description = "Page size, 1 to 200 (default 50)"),
+        ("offset" = Option<i64>, Query, description = "Rows to skip (default 0)"),
+        ("search" = Option<String>, Query, description = "Case-insensitive substring of the name"),
+        ("sort_by" = Option<String>, Query, description = "name, base_price or created_at"),
+        ("order" = Option<String>, Query, description = "asc or desc"),
+        ("available_only" = Option<bool>, Query, description = "Only drinks that can currently be ordered"),
+        ("include_deleted" = Option<bool>, Query, description = "List soft-deleted drinks too (staff only)"),
+    ),
+    responses(
+        (status = 200, description = "One page of drinks", body = [DrinkApiModel], headers(
+            ("X-Total-Count" = i64, description = "Drinks matching across all pages"),
+            ("Link" = String, description = "next/prev page links"),
+        )),
+        (status = 400, description = "Invalid pagination or sort", body = ErrorApiModel),
+        (status = 401, description = "include_deleted without a valid token", body = ErrorApiModel),
+        (status = 403, description = "include_deleted with a non-staff token", body = ErrorApiModel),
+    )
+)]
+#[get("/drinks?<limit>&<offset>&<search>&<sort_by>&<order>&<available_only>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_drinks(
+    conn: DbConn,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    search: Option<String>,
+    sort_by: Option<String>,
+    order: Option<String>,
+    available_only: Option<bool>,
+    include_deleted: IncludeDeleted,
+    request_id: RequestId,
+) -> Result<Paginated<DrinkApiModel>, ApiError> {
+    let (limit, offset) = page_window(limit, offset)?;
+    let IncludeDeleted(include_deleted) = include_deleted;
+    let available_only = available_only.unwrap_or(false);
+    let sort = DrinkSort::parse(sort_by.as_deref(), order.as_deref(), &request_id)?;
+    let search = search
+        .map(|term| term.trim().to_lowercase())
+        .filter(|term| !term.is_empty());
+
+    conn.run(move |c| {
+        // Count all matching drinks so clients know how many pages exist
+        let total = listed_drinks(include_deleted, available_only, search.as_deref())
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("[{}] Database error counting drinks: {}", request_id, e);
+                ApiError::from(e)
+            })?;
+
+        // Query one page of drinks, available ones only unless include_deleted is set
+        let results = sort
+            .apply(listed_drinks(include_deleted, available_only, search.as_deref()))
+            .limit(limit)
+            .offset(offset)
             .select(Drink::as_select())
             .load(c)
             .map_err(|e| {
-                eprintln!("Database error loading drinks: {}", e);
-                Status::InternalServerError
+                error!("[{}] Database error loading drinks: {}", request_id, e);
+                ApiError::from(e)
             })?;
 
         // Convert database models to API models
@@ -41,23 +132,207 @@ pub async fn get_drinks(conn: DbConn) -> Result<Json<Vec<DrinkApiModel>>, Status
             .map(|drink| drink.to_api_model())
             .collect();
 
-        Ok(Json(api_drinks))
+        Ok(Paginated { items: api_drinks, total, limit, offset })
+    })
+    .await
+}
+
+/// Ordering of the drinks list
+#[derive(Debug, Clone, Copy)]
+struct DrinkSort {
+    field: DrinkSortField,
+    descending: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DrinkSortField {
+    Name,
+    BasePrice,
+    CreatedAt,
+}
+
+impl DrinkSort {
+    /// Read `?sort_by=` and `?order=`; `created_at desc` when both are absent
+    fn parse(sort_by: Option<&str>, order: Option<&str>, request_id: &RequestId) -> Result<Self, ApiError> {
+        let field = match sort_by.map(str::trim) {
+            None => DrinkSortField::CreatedAt,
+            Some("name") => DrinkSortField::Name,
+            Some("base_price") => DrinkSortField::BasePrice,
+            Some("created_at") => DrinkSortField::CreatedAt,
+            Some(other) => {
+                let message = format!("cannot sort drinks by '{}' (use name, base_price or created_at)", other);
+                warn!("[{}] Validation error: {}", request_id, message);
+                return Err(ApiError::bad_request(message));
+            }
+        };
+
+        let descending = match order.map(str::trim) {
+            None => sort_by.is_none(),
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => {
+                let message = format!("order must be asc or desc, not '{}'", other);
+                warn!("[{}] Validation error: {}", request_id, message);
+                return Err(ApiError::bad_request(message));
+            }
+        };
+
+        Ok(DrinkSort { field, descending })
+    }
+
+    /// Order `query` by the chosen field, then by id so pages stay stable
+    ///
+    /// `base_price` is stored as text, so it is cast to a number; a text sort
+    /// would put "10.00" before "9.00".
+    fn apply(self, query: drinks::BoxedQuery<'static, Sqlite>) -> drinks::BoxedQuery<'static, Sqlite> {
+        let query = match (self.field, self.descending) {
+            (DrinkSortField::Name, false) => query.order(drinks::name.asc()),
+            (DrinkSortField::Name, true) => query.order(drinks::name.desc()),
+            (DrinkSortField::BasePrice, false) => query.order(sql::<Double>("CAST(base_price AS REAL)").asc()),
+            (DrinkSortField::BasePrice, true) => query.order(sql::<Double>("CAST(base_price AS REAL)").desc()),
+            (DrinkSortField::CreatedAt, false) => query.order(drinks::created_at.asc()),
+            (DrinkSortField::CreatedAt, true) => query.order(drinks::created_at.desc()),
+        };
+
+        query.then_order_by(drinks::id.asc())
+    }
+}
+
+/// Drinks that are not soft-deleted (all of them with `include_deleted`),
+/// narrowed to available ones with `available_only` and to names containing `search`
+fn listed_drinks(
+    include_deleted: bool,
+    available_only: bool,
+    search: Option<&str>,
+) -> drinks::BoxedQuery<'static, Sqlite> {
+    let mut query = drinks::table.into_boxed();
+
+    if !include_deleted {
+        query = query.filter(drinks::deleted_at.is_null());
+    }
+    if available_only {
+        query = query.filter(drinks::is_available.eq(true));
+    }
+
+    if let Some(term) = search {
+        query = query.filter(lower(drinks::name).like(like_contains(term)).escape(LIKE_ESCAPE));
+    }
+
+    query
+}
+
+/// Get recently updated drinks
+/// 
+/// Returns active drinks whose `updated_at` is strictly after `?since=`, most
+/// recently updated first, so clients can sync menu changes incrementally.
+/// `since` accepts RFC 3339, a naive UTC `YYYY-MM-DDTHH:MM:SS` timestamp or epoch
+/// milliseconds, and defaults to 24 hours ago.
+/// Returns 400 if since cannot be parsed.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("since" = Option<String>, Query, description = "Timestamp to sync from (default 24 hours ago)")),
+    responses(
+        (status = 200, description = "Drinks updated after since", body = [DrinkApiModel]),
+        (status = 400, description = "since is not a timestamp", body = ErrorApiModel),
+    )
+)]
+#[get("/drinks/recent?<since>")]
+pub async fn get_recent_drinks(conn: DbConn, since: Option<String>, request_id: RequestId) -> Result<ApiJson<Vec<DrinkApiModel>>, ApiError> {
+    let since = match since {
+        Some(raw) => SqliteDateTime::parse(&raw).ok_or_else(|| {
+            warn!("[{}] Validation error: '{}' is not a valid timestamp", request_id, raw);
+            ApiError::from(Status::BadRequest)
+        })?,
+        None => SqliteDateTime::from(chrono::Utc::now().naive_utc() - chrono::Duration::hours(24)),
+    };
+
+    conn.run(move |c| {
+        // ISO 8601 text sorts chronologically, so the bound compares directly
+        let results = drinks::table
+            .filter(drinks::deleted_at.is_null())
+            .filter(drinks::updated_at.gt(since))
+            .order((drinks::updated_at.desc(), drinks::id.asc()))
+            .select(Drink::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("[{}] Database error loading recent drinks: {}", request_id, e);
+                ApiError::from(e)
+            })?;
+
+        Ok(ApiJson(results.into_iter().map(|drink| drink.to_api_model()).collect()))
     })
     .await
 }
 
 /// Get drink by ID
 /// 
-/// Returns a single drink by its unique ID.
-/// Returns 400 if drink_id is empty.
+/// Returns a single drink by its unique ID, with a weak ETag derived from its
+/// `updated_at`. Answers 304 Not Modified when `If-None-Match` carries that ETag.
+/// Returns 400 if drink_id is not a UUID.
 /// Returns 404 if drink not found or is soft-deleted.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    responses(
+        (status = 200, description = "The drink", body = DrinkApiModel, headers(("ETag" = String, description = "Weak entity tag"))),
+        (status = 304, description = "Unchanged since the If-None-Match tag"),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+    )
+)]
 #[get("/drinks/<drink_id>")]
-pub async fn get_drink(conn: DbConn, drink_id: String) -> Result<Json<DrinkApiModel>, Status> {
-    // Validate drink_id is not empty
-    if drink_id.trim().is_empty() {
-        eprintln!("Validation error: drink_id cannot be empty");
-        return Err(Status::BadRequest);
-    }
+pub async fn get_drink(
+    conn: DbConn,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    if_none_match: IfNoneMatch,
+    request_id: RequestId,
+) -> Result<Tagged<DrinkApiModel>, ApiError> {
+    let drink_id = drink_id?.into_inner();
+
+    conn.run(move |c| {
+        let drink = drinks::table
+            .filter(drinks::id.eq(&drink_id))
+            .filter(drinks::deleted_at.is_null())
+            .select(Drink::as_select())
+            .first(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("[{}] Database error loading drink {}: {}", request_id, drink_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+
+        let etag = row_etag(drink.updated_at);
+        if if_none_match.matches(&etag) {
+            return Ok(Tagged { etag, body: None });
+        }
+
+        Ok(Tagged { etag, body: Some(drink.to_api_model()) })
+    })
+    .await
+}
+
+/// Get a drink's price per size
+/// 
+/// Lists `{"size", "price"}` for every size the drink is offered in, in menu
+/// order. Prices are the drink's base_price times the size multiplier, the same
+/// computation order placement uses, rendered with 2 decimals.
+/// Returns 400 if drink_id is not a UUID.
+/// Returns 404 if drink not found or is soft-deleted.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    responses(
+        (status = 200, description = "Price in each offered size", body = [DrinkSizePriceApiModel]),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+    )
+)]
+#[get("/drinks/<drink_id>/sizes")]
+pub async fn get_drink_sizes(conn: DbConn, drink_id: Result<UuidParam, InvalidUuid>, request_id: RequestId) -> Result<ApiJson<Vec<DrinkSizePriceApiModel>>, ApiError> {
+    let drink_id = drink_id?.into_inner();
 
     conn.run(move |c| {
         let drink = drinks::table
@@ -66,14 +341,87 @@ pub async fn get_drink(conn: DbConn, drink_id: String) -> Result<Json<DrinkApiMo
             .select(Drink::as_select())
             .first(c)
             .map_err(|e| match e {
-                diesel::result::Error::NotFound => Status::NotFound,
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("[{}] Database error loading drink {}: {}", request_id, drink_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+
+        Ok(ApiJson(drink.size_prices()))
+    })
+    .await
+}
+
+/// Get extras available for a drink
+/// 
+/// Returns the extras a customer can add to the given drink, with their prices.
+/// Compatibility is configured in the `drink_extras` table:
+/// - a drink with rows there only accepts the listed extras
+/// - a drink without any rows is unrestricted and accepts every extra
+/// 
+/// Only currently available extras are returned, so the result may be an
+/// empty array when every compatible extra is out of stock.
+/// Returns 400 if drink_id is not a UUID.
+/// Returns 404 if drink not found or is soft-deleted.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    responses(
+        (status = 200, description = "Extras available for the drink", body = [ExtraApiModel]),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+    )
+)]
+#[get("/drinks/<drink_id>/extras")]
+pub async fn get_drink_extras(conn: DbConn, drink_id: Result<UuidParam, InvalidUuid>, request_id: RequestId) -> Result<ApiJson<Vec<ExtraApiModel>>, ApiError> {
+    let drink_id = drink_id?.into_inner();
+
+    conn.run(move |c| {
+        // The drink must exist and be active
+        drinks::table
+            .filter(drinks::id.eq(&drink_id))
+            .filter(drinks::deleted_at.is_null())
+            .select(drinks::id)
+            .first::<String>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
                 _ => {
-                    eprintln!("Database error loading drink {}: {}", drink_id, e);
-                    Status::InternalServerError
+                    error!("[{}] Database error loading drink {}: {}", request_id, drink_id, e);
+                    ApiError::from(e)
                 }
             })?;
 
-        Ok(Json(drink.to_api_model()))
+        let restricted_count = drink_extras::table
+            .filter(drink_extras::drink_id.eq(&drink_id))
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("[{}] Database error loading extras compatibility for drink {}: {}", request_id, drink_id, e);
+                ApiError::from(e)
+            })?;
+
+        let results = if restricted_count > 0 {
+            drink_extras::table
+                .inner_join(extras::table)
+                .filter(drink_extras::drink_id.eq(&drink_id))
+                .filter(extras::is_available.eq(true))
+                .order(extras::name.asc())
+                .select(Extra::as_select())
+                .load(c)
+        } else {
+            extras::table
+                .filter(extras::is_available.eq(true))
+                .order(extras::name.asc())
+                .select(Extra::as_select())
+                .load(c)
+        }
+        .map_err(|e| {
+            error!("[{}] Database error loading extras for drink {}: {}", request_id, drink_id, e);
+            ApiError::from(e)
+        })?;
+
+        Ok(ApiJson(results.into_iter().map(|extra| extra.to_api_model()).collect()))
     })
     .await
 }
@@ -82,28 +430,47 @@ pub async fn get_drink(conn: DbConn, drink_id: String) -> Result<Json<DrinkApiMo
 /// 
 /// Creates a new drink with the provided information.
 /// Generates a UUID for the drink ID automatically.
-/// Returns 400 if name is empty or price is negative/zero.
-/// Returns the created drink with all fields populated.
+/// `available_sizes` defaults to every size when omitted.
+/// Returns 400 if name is empty, price is negative/zero or has more than 2 decimal
+/// places, or available_sizes is empty. A price like "3.9" is stored as 3.90.
+/// Returns 409 if an active drink already has this name (surrounding whitespace ignored).
+/// Returns 201 with the created drink (all fields populated) and its Location.
 /// Note: created_at and updated_at are handled automatically server-side.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    request_body = NewDrink,
+    responses(
+        (status = 201, description = "Drink created", body = DrinkApiModel, headers(("Location" = String, description = "URL of the new drink"))),
+        (status = 400, description = "Invalid name, price or sizes", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 409, description = "Name already in use", body = ErrorApiModel),
+        (status = 422, description = "Malformed body", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
 #[post("/drinks", data = "<new_drink>")]
 pub async fn create_drink(
+    _staff: StaffUser,
     conn: DbConn,
-    new_drink: Json<NewDrink>,
-) -> Result<Json<DrinkApiModel>, Status> {
+    catalog: &State<CatalogVersion>,
+    new_drink: JsonBody<NewDrink>,
+    request_id: RequestId,
+) -> Result<status::Created<Json<DrinkApiModel>>, ApiError> {
     // Validate input fields
-    let drink_data = new_drink.into_inner();
-    
-    if drink_data.name.trim().is_empty() {
-        eprintln!("Validation error: drink name cannot be empty");
-        return Err(Status::BadRequest);
-    }
-    
-    if drink_data.base_price <= Decimal::ZERO {
-        eprintln!("Validation error: drink price must be greater than zero");
-        return Err(Status::BadRequest);
+    let mut drink_data = new_drink.into_inner();
+    drink_data.name = drink_data.name.trim().to_string();
+
+    if let Some(problem) = new_drink_problem(&drink_data) {
+        warn!("[{}] Validation error: {}", request_id, problem);
+        return Err(ApiError::bad_request(problem));
     }
+    drink_data.base_price = validate_price("base_price", drink_data.base_price)?;
 
     conn.run(move |c| {
+        ensure_drink_names_available(c, &[drink_data.name.as_str()])?;
+
         // Generate a new UUID for the drink
         let drink_id = Uuid::new_v4().to_string();
         
@@ -115,77 +482,551 @@ pub async fn create_drink(
             .values(&db_drink)
             .execute(c)
             .map_err(|e| {
-                eprintln!("Database error creating drink: {}", e);
-                Status::InternalServerError
+                error!("[{}] Database error creating drink: {}", request_id, e);
+                ApiError::from(e)
             })?;
 
         // Return the created drink
-        Ok(Json(db_drink.to_api_model()))
+        let location = format!("/api/drinks/{}", db_drink.id);
+        Ok(status::Created::new(location).body(Json(db_drink.to_api_model())))
     })
     .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Largest number of drinks accepted by POST /drinks/batch
+const MAX_DRINK_BATCH: usize = 500;
+
+/// Create several drinks at once
+/// 
+/// Validates every entry with the rules of POST /drinks, then inserts them all
+/// in a single transaction: either the whole batch is created or none of it.
+/// Returns 400 if the array is empty, holds more than `MAX_DRINK_BATCH` (500)
+/// drinks, or an entry is invalid; the message names the first invalid index.
+/// A name given twice in the batch counts as invalid.
+/// Returns 409 if an active drink already has one of the names.
+/// Returns 201 with the created drinks, in the order given.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    request_body = [NewDrink],
+    responses(
+        (status = 201, description = "Drinks created, in the order given", body = [DrinkApiModel]),
+        (status = 400, description = "Empty or oversized batch, or an invalid drink", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 409, description = "A name already in use", body = ErrorApiModel),
+        (status = 422, description = "Malformed body", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/drinks/batch", data = "<new_drinks>")]
+pub async fn create_drinks_batch(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    new_drinks: JsonBody<Vec<NewDrink>>,
+    request_id: RequestId,
+) -> Result<status::Created<Json<Vec<DrinkApiModel>>>, ApiError> {
+    let mut drinks_data = new_drinks.into_inner();
+    for drink in &mut drinks_data {
+        drink.name = drink.name.trim().to_string();
+    }
+
+    if drinks_data.is_empty() {
+        warn!("[{}] Validation error: a drink batch cannot be empty", request_id);
+        return Err(ApiError::bad_request("a drink batch needs at least one drink"));
+    }
+
+    if drinks_data.len() > MAX_DRINK_BATCH {
+        let message = format!("a drink batch holds at most {} drinks, got {}", MAX_DRINK_BATCH, drinks_data.len());
+        warn!("[{}] Validation error: {}", request_id, message);
+        return Err(ApiError::bad_request(message));
+    }
+
+    if let Some((index, problem)) = drinks_data
+        .iter()
+        .enumerate()
+        .find_map(|(index, drink)| new_drink_problem(drink).map(|problem| (index, problem)))
+    {
+        let message = format!("drinks[{}]: {}", index, problem);
+        warn!("[{}] Validation error: {}", request_id, message);
+        return Err(ApiError::bad_request(message));
+    }
+
+    let mut seen_names = HashSet::new();
+    if let Some(index) = drinks_data.iter().position(|drink| !seen_names.insert(drink.name.as_str())) {
+        let message = format!("drinks[{}]: name '{}' appears more than once", index, drinks_data[index].name);
+        warn!("[{}] Validation error: {}", request_id, message);
+        return Err(ApiError::bad_request(message));
+    }
+
+    for (index, drink) in drinks_data.iter_mut().enumerate() {
+        drink.base_price = validate_price(&format!("drinks[{}].base_price", index), drink.base_price)?;
+    }
+
+    conn.run(move |c| {
+        let names: Vec<&str> = drinks_data.iter().map(|drink| drink.name.as_str()).collect();
+        ensure_drink_names_available(c, &names)?;
+
+        let db_drinks: Vec<Drink> = drinks_data
+            .into_iter()
+            .map(|drink| Drink::from_new(drink, Uuid::new_v4().to_string()))
+            .collect();
+
+        c.transaction(|tx| {
+            diesel::insert_into(drinks::table)
+                .values(&db_drinks)
+                .execute(tx)
+        })
+        .map_err(|e| {
+            error!("[{}] Database error creating drink batch: {}", request_id, e);
+            ApiError::from(e)
+        })?;
+
+        let api_drinks = db_drinks.into_iter().map(|drink| drink.to_api_model()).collect();
+        Ok(status::Created::new("/api/drinks").body(Json(api_drinks)))
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Why a new drink cannot be created, if it cannot
+fn new_drink_problem(drink: &NewDrink) -> Option<&'static str> {
+    if drink.name.trim().is_empty() {
+        Some("drink name cannot be empty")
+    } else if drink.base_price <= Decimal::ZERO {
+        Some("drink price must be greater than zero")
+    } else if drink.available_sizes.is_empty() {
+        Some("a drink must be offered in at least one size")
+    } else {
+        None
+    }
 }
 
 /// Update drink by ID
 /// 
-/// Updates an existing drink's price only. Drink names are immutable after creation.
-/// Returns 400 if drink_id is empty or if price is negative/zero.
+/// Updates an existing drink's price and, optionally, its available sizes.
+/// Drink names are immutable after creation.
+/// Returns 400 if drink_id is not a UUID, price is negative/zero or has more than 2
+/// decimal places, or available_sizes is empty.
 /// Returns 422 if request contains unknown fields (e.g., "name" field is not allowed).
 /// Returns 404 if drink not found or is soft-deleted.
+/// A price change is recorded in the drink price history.
 /// Note: updated_at is handled automatically server-side, created_at remains unchanged.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    request_body = UpdateDrink,
+    responses(
+        (status = 200, description = "Drink updated", body = DrinkApiModel),
+        (status = 400, description = "Invalid price or sizes", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+        (status = 422, description = "Malformed body or unknown fields", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
 #[put("/drinks/<drink_id>", data = "<update_drink>")]
 pub async fn update_drink(
+    _staff: StaffUser,
     conn: DbConn,
-    drink_id: String,
-    update_drink: Json<UpdateDrink>,
-) -> Result<Json<DrinkApiModel>, Status> {
-    // Validate drink_id is not empty
-    if drink_id.trim().is_empty() {
-        eprintln!("Validation error: drink_id cannot be empty");
-        return Err(Status::BadRequest);
-    }
+    catalog: &State<CatalogVersion>,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    update_drink: JsonBody<UpdateDrink>,
+    request_id: RequestId,
+) -> Result<Json<DrinkApiModel>, ApiError> {
+    let drink_id = drink_id?.into_inner();
 
     // Validate input fields
-    let update_data = update_drink.into_inner();
+    let mut update_data = update_drink.into_inner();
     
     if update_data.base_price <= Decimal::ZERO {
-        eprintln!("Validation error: drink price must be greater than zero");
-        return Err(Status::BadRequest);
+        warn!("[{}] Validation error: drink price must be greater than zero", request_id);
+        return Err(Status::BadRequest.into());
+    }
+    update_data.base_price = validate_price("base_price", update_data.base_price)?;
+
+    if update_data.available_sizes.as_ref().is_some_and(|sizes| sizes.is_empty()) {
+        warn!("[{}] Validation error: a drink must be offered in at least one size", request_id);
+        return Err(Status::BadRequest.into());
     }
 
     conn.run(move |c| {
-        // First, find the existing drink
-        let mut existing_drink = drinks::table
-            .filter(drinks::id.eq(&drink_id))
-            .filter(drinks::deleted_at.is_null())
-            .select(Drink::as_select())
-            .first(c)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => Status::NotFound,
-                _ => {
-                    eprintln!("Database error loading drink {}: {}", drink_id, e);
-                    Status::InternalServerError
-                }
-            })?;
+        save_drink_changes(c, &drink_id, |drink| drink.update_from_input(update_data), &request_id).map(Json)
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Partially update drink by ID
+/// 
+/// Changes only the fields present in the body: `base_price`, `available_sizes`
+/// and `category`. An omitted field is left unchanged, while `"category": null`
+/// (or a blank string) removes the drink from its category. Drink names are
+/// immutable after creation.
+/// Returns 400 if drink_id is not a UUID, price is negative/zero or has more than 2
+/// decimal places, or available_sizes is empty.
+/// Returns 422 if request contains unknown fields (e.g., "name" field is not allowed).
+/// Returns 404 if drink not found or is soft-deleted.
+/// A price change is recorded in the drink price history.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    request_body = PatchDrink,
+    responses(
+        (status = 200, description = "Drink updated", body = DrinkApiModel),
+        (status = 400, description = "Invalid price or sizes", body = ErrorApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+        (status = 422, description = "Malformed body or unknown fields", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[patch("/drinks/<drink_id>", data = "<patch_drink>")]
+pub async fn patch_drink(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    patch_drink: JsonBody<PatchDrink>,
+    request_id: RequestId,
+) -> Result<Json<DrinkApiModel>, ApiError> {
+    let drink_id = drink_id?.into_inner();
+
+    let mut patch_data = patch_drink.into_inner();
 
-        // Update the drink data
-        existing_drink.update_from_input(update_data);
+    if patch_data.base_price.is_some_and(|price| price <= Decimal::ZERO) {
+        warn!("[{}] Validation error: drink price must be greater than zero", request_id);
+        return Err(Status::BadRequest.into());
+    }
+    patch_data.base_price = patch_data.base_price.map(|price| validate_price("base_price", price)).transpose()?;
+
+    if patch_data.available_sizes.as_ref().is_some_and(|sizes| sizes.is_empty()) {
+        warn!("[{}] Validation error: a drink must be offered in at least one size", request_id);
+        return Err(Status::BadRequest.into());
+    }
+
+    conn.run(move |c| {
+        save_drink_changes(c, &drink_id, |drink| drink.apply_patch(patch_data), &request_id).map(Json)
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Switch drink availability
+/// 
+/// Marks a drink as orderable or not (e.g. out of stock) from
+/// `{"is_available": false}`, without touching its price or sizes. The drink
+/// stays listed, unlike a soft delete; `?available_only=true` on the list hides it.
+/// Returns 400 if drink_id is not a UUID.
+/// Returns 422 if the body has unknown fields or is_available is missing.
+/// Returns 404 if drink not found or is soft-deleted.
+/// Returns the updated drink.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    request_body = UpdateDrinkAvailability,
+    responses(
+        (status = 200, description = "Drink updated", body = DrinkApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Drink not found or soft-deleted", body = ErrorApiModel),
+        (status = 422, description = "Malformed body or unknown fields", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[put("/drinks/<drink_id>/availability", data = "<body>")]
+pub async fn update_drink_availability(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    body: JsonBody<UpdateDrinkAvailability>,
+    request_id: RequestId,
+) -> Result<Json<DrinkApiModel>, ApiError> {
+    let drink_id = drink_id?.into_inner();
 
-        // Save the updated drink back to the database
-        diesel::update(drinks::table.filter(drinks::id.eq(&drink_id)))
+    let is_available = body.into_inner().is_available;
+
+    conn.run(move |c| {
+        save_drink_changes(c, &drink_id, |drink| drink.set_availability(is_available), &request_id).map(Json)
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Soft delete drink by ID
+/// 
+/// Sets deleted_at and leaves the row in place, so the drink drops off the
+/// catalog and menu and can no longer be ordered. Drinks referenced by existing
+/// order items can be deleted too: those orders keep pointing at the row and
+/// their history stays intact.
+/// Returns 400 if drink_id is not a UUID.
+/// Returns 404 if drink not found or already soft-deleted.
+/// Returns 204 No Content on success.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    responses(
+        (status = 204, description = "Drink soft-deleted"),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Drink not found or already deleted", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[delete("/drinks/<drink_id>")]
+pub async fn delete_drink(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    request_id: RequestId,
+) -> Result<Status, ApiError> {
+    let drink_id = drink_id?.into_inner();
+
+    conn.run(move |c| {
+        let now = SqliteDateTime::from(chrono::Utc::now().naive_utc());
+
+        // Only active drinks match, so a second delete finds nothing
+        let deleted = diesel::update(
+            drinks::table
+                .filter(drinks::id.eq(&drink_id))
+                .filter(drinks::deleted_at.is_null()),
+        )
+        .set(drinks::deleted_at.eq(Some(now)))
+        .execute(c);
+
+        match deleted {
+            Ok(0) => Err(Status::NotFound.into()),
+            Ok(_) => Ok(Status::NoContent),
+            Err(e) => {
+                error!("[{}] Database error deleting drink {}: {}", request_id, drink_id, e);
+                Err(ApiError::from(e))
+            }
+        }
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Restore a soft-deleted drink
+/// 
+/// Clears deleted_at and bumps updated_at, so the drink shows up in lists and
+/// lookups again.
+/// Returns 400 if drink_id is not a UUID.
+/// Returns 404 if drink not found.
+/// Returns 409 if the drink is not deleted, or an active drink has taken its name since.
+/// Returns the restored drink.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    params(("drink_id" = String, Path, description = "Drink id")),
+    responses(
+        (status = 200, description = "Drink restored", body = DrinkApiModel),
+        (status = 401, description = "Missing or invalid token", body = ErrorApiModel),
+        (status = 403, description = "Token without the employee role", body = ErrorApiModel),
+        (status = 404, description = "Drink not found", body = ErrorApiModel),
+        (status = 409, description = "Drink is not deleted, or its name is taken", body = ErrorApiModel),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/drinks/<drink_id>/restore")]
+pub async fn restore_drink(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    drink_id: Result<UuidParam, InvalidUuid>,
+    request_id: RequestId,
+) -> Result<Json<DrinkApiModel>, ApiError> {
+    let drink_id = drink_id?.into_inner();
+
+    conn.run(move |c| {
+        c.transaction::<_, ApiError, _>(|c| {
+            let mut drink = drinks::table
+                .filter(drinks::id.eq(&drink_id))
+                .select(Drink::as_select())
+                .first(c)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                    _ => {
+                        error!("[{}] Database error loading drink {}: {}", request_id, drink_id, e);
+                        ApiError::from(e)
+                    }
+                })?;
+
+            if drink.deleted_at.is_none() {
+                warn!("[{}] Conflict: drink {} is not deleted", request_id, drink_id);
+                return Err(ApiError::conflict("drink is not deleted"));
+            }
+            ensure_drink_names_available(c, &[drink.name.as_str()])?;
+
+            drink.restore();
+
+            diesel::update(
+                drinks::table
+                    .filter(drinks::id.eq(&drink_id))
+                    .filter(drinks::deleted_at.is_not_null()),
+            )
             .set((
-                drinks::base_price.eq(&existing_drink.base_price),
-                drinks::updated_at.eq(&existing_drink.updated_at),
+                drinks::updated_at.eq(&drink.updated_at),
+                drinks::deleted_at.eq(&drink.deleted_at),
             ))
             .execute(c)
             .map_err(|e| {
-                eprintln!("Database error updating drink {}: {}", drink_id, e);
-                Status::InternalServerError
+                error!("[{}] Database error restoring drink {}: {}", request_id, drink_id, e);
+                ApiError::from(e)
             })?;
 
-        // Return the updated drink
-        Ok(Json(existing_drink.to_api_model()))
+            Ok(Json(drink.to_api_model()))
+        })
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Load an active drink, apply `change` and save it
+///
+/// Writes the price, sizes, category, availability and updated_at together with
+/// the price history entry in one transaction. 404 if the drink is missing or soft-deleted.
+fn save_drink_changes(
+    c: &mut diesel::SqliteConnection,
+    drink_id: &str,
+    change: impl FnOnce(&mut Drink),
+    request_id: &RequestId,
+) -> Result<DrinkApiModel, ApiError> {
+    let mut drink = drinks::table
+        .filter(drinks::id.eq(drink_id))
+        .filter(drinks::deleted_at.is_null())
+        .select(Drink::as_select())
+        .first(c)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+            _ => {
+                error!("[{}] Database error loading drink {}: {}", request_id, drink_id, e);
+                ApiError::from(e)
+            }
+        })?;
+
+    let old_price = drink.base_price.into_decimal();
+    change(&mut drink);
+
+    c.transaction(|tx| {
+        diesel::update(drinks::table.filter(drinks::id.eq(drink_id)))
+            .set((
+                drinks::base_price.eq(&drink.base_price),
+                drinks::available_sizes.eq(&drink.available_sizes),
+                drinks::category.eq(&drink.category),
+                drinks::is_available.eq(drink.is_available),
+                drinks::updated_at.eq(&drink.updated_at),
+            ))
+            .execute(tx)?;
+
+        record_price_change(tx, drink_id, old_price, drink.base_price.into_decimal(), drink.updated_at)
+    })
+    .map_err(|e: diesel::result::Error| {
+        error!("[{}] Database error updating drink {}: {}", request_id, drink_id, e);
+        ApiError::from(e)
+    })?;
+
+    Ok(drink.to_api_model())
+}
+
+/// Adjust drink prices in bulk
+/// 
+/// Multiplies the price of every active drink, or only those in `category`
+/// (case-insensitive), by `1 + percent / 100`, rounding to 2 decimal places. All
+/// prices change in a single transaction and each change is recorded in the
+/// drink price history. Admin only (`X-Admin-Token`).
+/// Returns 400 if percent is -100 or lower.
+/// Returns 401/403 without a valid admin token.
+/// Returns 422 if a price would round down to zero (nothing is changed).
+/// Returns the number of drinks updated.
+#[utoipa::path(
+    context_path = "/api",
+    tag = "drinks",
+    request_body = BulkPriceAdjustment,
+    responses(
+        (status = 200, description = "Number of drinks updated", body = BulkPriceAdjustmentApiModel),
+        (status = 400, description = "percent is -100 or lower", body = ErrorApiModel),
+        (status = 401, description = "Missing token or admin token", body = ErrorApiModel),
+        (status = 403, description = "Non-staff token or wrong admin token", body = ErrorApiModel),
+        (status = 422, description = "A price would round down to zero", body = ErrorApiModel),
+    ),
+    security(("bearer" = [], "admin_token" = []))
+)]
+#[post("/drinks/bulk-adjust", data = "<adjustment>")]
+pub async fn bulk_adjust_drink_prices(
+    _staff: StaffUser,
+    _admin: AdminUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    adjustment: JsonBody<BulkPriceAdjustment>,
+    request_id: RequestId,
+) -> Result<Json<BulkPriceAdjustmentApiModel>, ApiError> {
+    let adjustment = adjustment.into_inner();
+
+    if adjustment.percent <= -Decimal::ONE_HUNDRED {
+        warn!("[{}] Validation error: percent must be greater than -100", request_id);
+        return Err(Status::BadRequest.into());
+    }
+
+    let category = normalize_category(adjustment.category);
+    let percent = adjustment.percent;
+
+    conn.run(move |c| {
+        c.transaction(|tx| {
+            let mut query = drinks::table.filter(drinks::deleted_at.is_null()).into_boxed();
+            if let Some(category) = &category {
+                query = query.filter(drinks::category.eq(category));
+            }
+            let matching_drinks = query.select(Drink::as_select()).load(tx)?;
+
+            let mut updated = 0;
+            for mut drink in matching_drinks {
+                let old_price = drink.base_price.into_decimal();
+                let new_price = adjust_price(old_price, percent);
+                if new_price <= Decimal::ZERO {
+                    warn!("[{}] Validation error: price of drink {} would drop to {}", request_id, drink.id, new_price);
+                    return Err(diesel::result::Error::RollbackTransaction);
+                }
+                if new_price == old_price {
+                    continue;
+                }
+
+                drink.base_price = SqliteDecimal::from(new_price);
+                drink.updated_at = SqliteDateTime::updated_now(drink.created_at);
+
+                diesel::update(drinks::table.filter(drinks::id.eq(&drink.id)))
+                    .set((
+                        drinks::base_price.eq(&drink.base_price),
+                        drinks::updated_at.eq(&drink.updated_at),
+                    ))
+                    .execute(tx)?;
+                record_price_change(tx, &drink.id, old_price, new_price, drink.updated_at)?;
+                updated += 1;
+            }
+
+            Ok(updated)
+        })
+        .map(|updated| Json(BulkPriceAdjustmentApiModel { updated }))
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => ApiError::from(Status::UnprocessableEntity),
+            _ => {
+                error!("[{}] Database error adjusting drink prices: {}", request_id, e);
+                ApiError::from(e)
+            }
+        })
     })
     .await
+    .inspect(|_| catalog.bump())
 }
 
 /// Export all drink routes
@@ -193,5 +1034,19 @@ pub async fn update_drink(
 /// This function returns all drink-related routes that should be mounted
 /// on the Rocket application. Mount these under "/api" prefix.
 pub fn routes() -> Vec<Route> {
-    routes![get_drinks, get_drink, create_drink, update_drink]
+    routes![
+        get_drinks,
+        get_recent_drinks,
+        get_drink,
+        get_drink_sizes,
+        get_drink_extras,
+        create_drink,
+        create_drinks_batch,
+        update_drink,
+        patch_drink,
+        update_drink_availability,
+        delete_drink,
+        restore_drink,
+        bulk_adjust_drink_prices
+    ]
 }
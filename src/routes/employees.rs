@@ -0,0 +1,120 @@
+//! Employee Routes
+//! 
+//! This module provides REST API endpoints for employee operations:
+//! - GET /employees/{id}/orders - Every order assigned to an employee, newest first
+//! - GET /employees/{id}/orders/active - Orders an employee is currently working on
+
+use rocket::{get, routes, Route};
+use rocket::http::Status;
+use diesel::prelude::*;
+
+use crate::DbConn;
+use crate::logic::orders::load_order_details;
+use crate::models::infra::sqlite_types::SqliteOrderStatus;
+use crate::models::order::{Order, OrderApiModel, OrderDetailApiModel};
+use crate::models::order_status::OrderStatus;
+use crate::responders::ApiJson;
+use crate::schema::{employees, orders};
+
+/// Get an employee's orders
+/// 
+/// Returns every order assigned to the employee, whatever its status, newest
+/// first (ties broken by id). An employee without orders gets an empty array.
+/// Returns 400 if employee_id is empty.
+/// Returns 404 if employee not found or is soft-deleted.
+#[get("/employees/<employee_id>/orders")]
+pub async fn get_employee_orders(
+    conn: DbConn,
+    employee_id: String,
+) -> Result<ApiJson<Vec<OrderApiModel>>, Status> {
+    if employee_id.trim().is_empty() {
+        warn!("Validation error: employee_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    conn.run(move |c| {
+        find_active_employee(c, &employee_id)?;
+
+        orders::table
+            .filter(orders::employee_id.eq(&employee_id))
+            .order((orders::created_at.desc(), orders::id.desc()))
+            .select(Order::as_select())
+            .load(c)
+            .map(|orders| ApiJson(orders.into_iter().map(|order| order.to_api_model()).collect()))
+            .map_err(|e| {
+                error!("Database error loading orders for employee {}: {}", employee_id, e);
+                Status::InternalServerError
+            })
+    })
+    .await
+}
+
+/// Get an employee's active orders
+/// 
+/// Returns the orders assigned to the employee that are not in a terminal state
+/// (anything but `completed`/`cancelled`), oldest first, with their items nested.
+/// This is a barista's current workload; an idle employee gets an empty array.
+/// Returns 400 if employee_id is empty.
+/// Returns 404 if employee not found or is soft-deleted.
+#[get("/employees/<employee_id>/orders/active")]
+pub async fn get_employee_active_orders(
+    conn: DbConn,
+    employee_id: String,
+) -> Result<ApiJson<Vec<OrderDetailApiModel>>, Status> {
+    // Validate employee_id is not empty
+    if employee_id.trim().is_empty() {
+        warn!("Validation error: employee_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    conn.run(move |c| {
+        find_active_employee(c, &employee_id)?;
+
+        let active_statuses: Vec<SqliteOrderStatus> =
+            OrderStatus::active().into_iter().map(SqliteOrderStatus::from).collect();
+
+        let active_orders = orders::table
+            .filter(orders::employee_id.eq(&employee_id))
+            .filter(orders::status.eq_any(active_statuses))
+            .order((orders::created_at.asc(), orders::id.asc()))
+            .select(Order::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading orders for employee {}: {}", employee_id, e);
+                Status::InternalServerError
+            })?;
+
+        load_order_details(c, active_orders)
+            .map(ApiJson)
+            .map_err(|e| {
+                error!("Database error loading order items for employee {}: {}", employee_id, e);
+                Status::InternalServerError
+            })
+    })
+    .await
+}
+
+/// Check that the employee exists and is not soft-deleted, 404 otherwise
+fn find_active_employee(c: &mut diesel::SqliteConnection, employee_id: &str) -> Result<(), Status> {
+    employees::table
+        .filter(employees::id.eq(employee_id))
+        .filter(employees::deleted_at.is_null())
+        .select(employees::id)
+        .first::<String>(c)
+        .map(|_| ())
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => Status::NotFound,
+            _ => {
+                error!("Database error loading employee {}: {}", employee_id, e);
+                Status::InternalServerError
+            }
+        })
+}
+
+/// Export all employee routes
+/// 
+/// This function returns all employee-related routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![get_employee_orders, get_employee_active_orders]
+}
@@ -0,0 +1,282 @@
+//! Extra Catalog Routes
+//! 
+//! This module provides REST API endpoints for extra catalog operations:
+//! - GET /extras - List extras (optionally only the available ones)
+//! - GET /extras/{id} - Get extra by ID
+//! - POST /extras - Create new extra
+//! - PUT /extras/{id} - Update extra price and availability (name is immutable)
+//! - PUT /extras/by-name - Create or update an extra identified by its name
+
+use rocket::serde::json::Json;
+use rocket::response::status;
+use rocket::{get, post, put, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::DbConn;
+use crate::auth::StaffUser;
+use crate::cache::CatalogVersion;
+use crate::error::ApiError;
+use crate::models::extra::{Extra, ExtraApiModel, NewExtra, UpdateExtra};
+use crate::responders::{page_window, ApiJson, Paginated};
+use crate::schema::extras;
+use crate::validation::validate_price;
+
+/// Get all extras
+/// 
+/// Returns a page of extras sorted by name, ties broken by id. With
+/// `?available_only=true` only extras currently offered are listed.
+/// Pagination works like the other list endpoints (`?limit=`, default 50, and
+/// `?offset=`, default 0).
+/// Returns 400 if limit is above `MAX_PAGE_LIMIT` (200) or below 1, or offset is negative.
+#[get("/extras?<available_only>&<limit>&<offset>")]
+pub async fn get_extras(
+    conn: DbConn,
+    available_only: Option<bool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Paginated<ExtraApiModel>, Status> {
+    let (limit, offset) = page_window(limit, offset)?;
+    let available_only = available_only.unwrap_or(false);
+
+    conn.run(move |c| {
+        let filtered = || {
+            let mut query = extras::table.into_boxed();
+            if available_only {
+                query = query.filter(extras::is_available.eq(true));
+            }
+            query
+        };
+
+        let total = filtered().count().get_result::<i64>(c).map_err(|e| {
+            error!("Database error counting extras: {}", e);
+            Status::InternalServerError
+        })?;
+
+        let results = filtered()
+            .order((extras::name.asc(), extras::id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select(Extra::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading extras: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let items = results.into_iter().map(|extra| extra.to_api_model()).collect();
+        Ok(Paginated { items, total, limit, offset })
+    })
+    .await
+}
+
+/// Get extra by ID
+/// 
+/// Returns a single extra, available or not.
+/// Returns 400 if extra_id is empty.
+/// Returns 404 if extra not found.
+#[get("/extras/<extra_id>")]
+pub async fn get_extra(conn: DbConn, extra_id: String) -> Result<ApiJson<ExtraApiModel>, Status> {
+    if extra_id.trim().is_empty() {
+        warn!("Validation error: extra_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    conn.run(move |c| {
+        find_extra(c, &extra_id).map(|extra| ApiJson(extra.to_api_model()))
+    })
+    .await
+}
+
+/// Create new extra
+/// 
+/// Creates an extra with a new UUID, available unless `is_available` says otherwise.
+/// Returns 400 if name is empty, or price is negative/zero or has more than 2
+/// decimal places. A price like "0.5" is stored as 0.50.
+/// Returns 409 if an extra with this name already exists.
+/// Returns 201 with the created extra.
+#[post("/extras", data = "<new_extra>")]
+pub async fn create_extra(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    new_extra: Json<NewExtra>,
+) -> Result<status::Created<Json<ExtraApiModel>>, ApiError> {
+    let mut extra_data = new_extra.into_inner();
+    extra_data.name = extra_data.name.trim().to_string();
+
+    if extra_data.name.is_empty() {
+        warn!("Validation error: extra name cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    if extra_data.extra_price <= Decimal::ZERO {
+        warn!("Validation error: extra price must be greater than zero");
+        return Err(Status::BadRequest.into());
+    }
+    extra_data.extra_price = validate_price("extra_price", extra_data.extra_price)?;
+
+    conn.run(move |c| {
+        let db_extra = Extra::from_new(extra_data, Uuid::new_v4().to_string());
+
+        diesel::insert_into(extras::table)
+            .values(&db_extra)
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error creating extra: {}", e);
+                // A unique constraint violation (name) becomes a 409
+                ApiError::from(e)
+            })?;
+
+        let location = format!("/api/extras/{}", db_extra.id);
+        Ok(status::Created::new(location).body(Json(db_extra.to_api_model())))
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Update extra by ID
+/// 
+/// Sets the extra's price and, when `is_available` is given, its availability.
+/// Extra names are immutable after creation.
+/// Returns 400 if extra_id is empty, or price is negative/zero or has more than
+/// 2 decimal places.
+/// Returns 422 if request contains unknown fields (e.g., "name" field is not allowed).
+/// Returns 404 if extra not found.
+#[put("/extras/<extra_id>", data = "<update_extra>")]
+pub async fn update_extra(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    extra_id: String,
+    update_extra: Json<UpdateExtra>,
+) -> Result<Json<ExtraApiModel>, ApiError> {
+    if extra_id.trim().is_empty() {
+        warn!("Validation error: extra_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    let mut update_data = update_extra.into_inner();
+
+    if update_data.extra_price <= Decimal::ZERO {
+        warn!("Validation error: extra price must be greater than zero");
+        return Err(Status::BadRequest.into());
+    }
+    update_data.extra_price = validate_price("extra_price", update_data.extra_price)?;
+
+    conn.run(move |c| {
+        let mut extra = find_extra(c, &extra_id)?;
+        extra.update_from_input(update_data);
+
+        diesel::update(extras::table.filter(extras::id.eq(&extra_id)))
+            .set((
+                extras::extra_price.eq(&extra.extra_price),
+                extras::is_available.eq(extra.is_available),
+            ))
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error updating extra {}: {}", extra_id, e);
+                Status::InternalServerError
+            })?;
+
+        Ok(Json(extra.to_api_model()))
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Load an extra by id, 404 if it does not exist
+fn find_extra(c: &mut diesel::SqliteConnection, extra_id: &str) -> Result<Extra, Status> {
+    extras::table
+        .filter(extras::id.eq(extra_id))
+        .select(Extra::as_select())
+        .first(c)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => Status::NotFound,
+            _ => {
+                error!("Database error loading extra {}: {}", extra_id, e);
+                Status::InternalServerError
+            }
+        })
+}
+
+/// Create or update an extra by name
+/// 
+/// Idempotent import of an extra: when no extra has this name (exact match,
+/// surrounding whitespace ignored) it is created with a new UUID, available unless
+/// `is_available` says otherwise; when one exists its price is overwritten, and
+/// its availability too when `is_available` is given. Running the same request
+/// twice leaves the catalog unchanged.
+/// Returns 400 if name is empty, or price is negative or has more than 2 decimal
+/// places.
+/// Returns 201 with the created extra, or 200 with the updated one.
+#[put("/extras/by-name", data = "<new_extra>")]
+pub async fn upsert_extra_by_name(
+    _staff: StaffUser,
+    conn: DbConn,
+    catalog: &State<CatalogVersion>,
+    new_extra: Json<NewExtra>,
+) -> Result<status::Custom<Json<ExtraApiModel>>, ApiError> {
+    let mut extra_data = new_extra.into_inner();
+    extra_data.name = extra_data.name.trim().to_string();
+
+    if extra_data.name.is_empty() {
+        warn!("Validation error: extra name cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    if extra_data.extra_price < Decimal::ZERO {
+        warn!("Validation error: extra price cannot be negative");
+        return Err(Status::BadRequest.into());
+    }
+    extra_data.extra_price = validate_price("extra_price", extra_data.extra_price)?;
+
+    let keep_availability = extra_data.is_available.is_none();
+    let candidate = Extra::from_new(extra_data, Uuid::new_v4().to_string());
+
+    conn.run(move |c| {
+        c.transaction(|tx| {
+            let upsert = diesel::insert_into(extras::table)
+                .values(&candidate)
+                .on_conflict(extras::name);
+            if keep_availability {
+                upsert.do_update().set(extras::extra_price.eq(excluded(extras::extra_price))).execute(tx)?;
+            } else {
+                upsert
+                    .do_update()
+                    .set((
+                        extras::extra_price.eq(excluded(extras::extra_price)),
+                        extras::is_available.eq(excluded(extras::is_available)),
+                    ))
+                    .execute(tx)?;
+            }
+
+            // Still inside the transaction, so the row is the one just written
+            extras::table
+                .filter(extras::name.eq(&candidate.name))
+                .select(Extra::as_select())
+                .first(tx)
+        })
+        .map(|extra: Extra| {
+            let status = if extra.id == candidate.id { Status::Created } else { Status::Ok };
+            status::Custom(status, Json(extra.to_api_model()))
+        })
+        .map_err(|e: diesel::result::Error| {
+            error!("Database error upserting extra {}: {}", candidate.name, e);
+            ApiError::from(Status::InternalServerError)
+        })
+    })
+    .await
+    .inspect(|_| catalog.bump())
+}
+
+/// Export all extra routes
+/// 
+/// This function returns all extra-related routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![get_extras, get_extra, create_extra, update_extra, upsert_extra_by_name]
+}
@@ -0,0 +1,185 @@
+//! Menu Routes
+//! 
+//! This module provides read-only REST API endpoints summarizing the catalog:
+//! - GET /menu - Orderable drinks (with their sizes), extras and modifiers
+//! - GET /menu/version - Catalog version, bumped on every drink/extra change
+//! - GET /menu/stats - Aggregate drink and extra statistics
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rocket::{get, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+
+use crate::DbConn;
+use crate::cache::CatalogVersion;
+use crate::config::AppConfig;
+use crate::db::run_report;
+use crate::error::ApiError;
+use crate::models::infra::sqlite_types::SqliteDecimal;
+use crate::models::drink::Drink;
+use crate::models::extra::Extra;
+use crate::models::menu::{MenuApiModel, MenuStatsApiModel, MenuVersionApiModel};
+use crate::models::modifier::{Modifier, ModifierApiModel, ModifierOption};
+use crate::guards::IfNoneMatch;
+use crate::responders::{ApiJson, Cached};
+use crate::schema::{drink_modifiers, drinks, extras, modifier_options, modifiers};
+
+/// Get the menu
+/// 
+/// Returns every active drink, including the sizes it can be ordered in, every
+/// available extra and every modifier with its available options and the active
+/// drinks offering it, all sorted by name.
+/// Cacheable for `MENU_CACHE_MAX_AGE_SECONDS`; the ETag changes whenever a drink
+/// or extra changes, and a matching `If-None-Match` returns 304 without a body.
+#[get("/menu")]
+pub async fn get_menu(
+    conn: DbConn,
+    config: &State<AppConfig>,
+    catalog: &State<CatalogVersion>,
+    if_none_match: IfNoneMatch,
+) -> Result<Cached<MenuApiModel>, Status> {
+    // Read the version before loading, so a concurrent change can only make the
+    // ETag older than the body, never newer
+    let etag = catalog.etag();
+    let max_age = config.menu_cache_max_age_seconds;
+
+    if if_none_match.matches(&etag) {
+        return Ok(Cached { etag, max_age, body: None });
+    }
+
+    conn.run(move |c| {
+        let drinks = drinks::table
+            .filter(drinks::deleted_at.is_null())
+            .order((drinks::name.asc(), drinks::id.asc()))
+            .select(Drink::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading drinks: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let extras = extras::table
+            .filter(extras::is_available.eq(true))
+            .order((extras::name.asc(), extras::id.asc()))
+            .select(Extra::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading extras: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let modifiers = load_menu_modifiers(c).map_err(|e| {
+            error!("Database error loading modifiers: {}", e);
+            Status::InternalServerError
+        })?;
+
+        let menu = MenuApiModel {
+            drinks: drinks.into_iter().map(|drink| drink.to_api_model()).collect(),
+            extras: extras.into_iter().map(|extra| extra.to_api_model()).collect(),
+            modifiers,
+        };
+
+        Ok(Cached { etag, max_age, body: Some(menu) })
+    })
+    .await
+}
+
+/// Modifiers with their available options and the active drinks offering them
+fn load_menu_modifiers(c: &mut diesel::SqliteConnection) -> QueryResult<Vec<ModifierApiModel>> {
+    let all_modifiers = modifiers::table
+        .order((modifiers::name.asc(), modifiers::id.asc()))
+        .select(Modifier::as_select())
+        .load(c)?;
+
+    let mut options: HashMap<String, Vec<ModifierOption>> = HashMap::new();
+    for option in modifier_options::table
+        .filter(modifier_options::is_available.eq(true))
+        .order((modifier_options::name.asc(), modifier_options::id.asc()))
+        .select(ModifierOption::as_select())
+        .load(c)?
+    {
+        options.entry(option.modifier_id.clone()).or_default().push(option);
+    }
+
+    let mut drink_ids: HashMap<String, Vec<String>> = HashMap::new();
+    for (modifier_id, drink_id) in drink_modifiers::table
+        .inner_join(drinks::table)
+        .filter(drinks::deleted_at.is_null())
+        .order(drink_modifiers::drink_id.asc())
+        .select((drink_modifiers::modifier_id, drink_modifiers::drink_id))
+        .load::<(String, String)>(c)?
+    {
+        drink_ids.entry(modifier_id).or_default().push(drink_id);
+    }
+
+    Ok(all_modifiers
+        .into_iter()
+        .map(|modifier| ModifierApiModel {
+            options: options
+                .remove(&modifier.id)
+                .unwrap_or_default()
+                .iter()
+                .map(ModifierOption::to_api_model)
+                .collect(),
+            drink_ids: drink_ids.remove(&modifier.id).unwrap_or_default(),
+            id: modifier.id,
+            name: modifier.name,
+        })
+        .collect())
+}
+
+/// Get the catalog version
+/// 
+/// Returns `{"version": n}`, a number that grows every time a drink or extra is
+/// created or changed (and across server restarts). Clients poll this cheap
+/// endpoint and refetch GET /menu only when it changes; the menu ETag carries
+/// the same version.
+#[get("/menu/version")]
+pub fn get_menu_version(catalog: &State<CatalogVersion>) -> ApiJson<MenuVersionApiModel> {
+    ApiJson(MenuVersionApiModel { version: catalog.current() })
+}
+
+/// Get menu statistics
+/// 
+/// Returns drink/extra counts and drink price aggregates (average, min, max)
+/// computed from the current catalog. Soft-deleted drinks are excluded.
+/// Price aggregates are null when there are no active drinks.
+/// Returns 503 if the computation exceeds `REPORT_TIMEOUT_SECONDS`.
+#[get("/menu/stats")]
+pub async fn get_menu_stats(conn: DbConn, config: &State<AppConfig>) -> Result<ApiJson<MenuStatsApiModel>, ApiError> {
+    let timeout = Duration::from_secs(config.report_timeout_seconds);
+    run_report(&conn, timeout, |c, deadline| {
+        let drink_prices = drinks::table
+            .filter(drinks::deleted_at.is_null())
+            .select(drinks::base_price)
+            .load::<SqliteDecimal>(c)
+            .map_err(|e| {
+                error!("Database error loading drink prices: {}", e);
+                Status::InternalServerError
+            })?;
+        deadline.check()?;
+
+        let extra_availability = extras::table
+            .select(extras::is_available)
+            .load::<bool>(c)
+            .map_err(|e| {
+                error!("Database error loading extras: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let drink_prices: Vec<_> = drink_prices.into_iter().map(|price| price.into_decimal()).collect();
+
+        Ok(ApiJson(MenuStatsApiModel::from_catalog(&drink_prices, &extra_availability)))
+    })
+    .await
+}
+
+/// Export all menu routes
+/// 
+/// This function returns all menu-related routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![get_menu, get_menu_version, get_menu_stats]
+}
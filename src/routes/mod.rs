@@ -1,9 +1,22 @@
-/// Route modules for the Coffee Shop API
-/// 
-/// This module organizes all API endpoints by domain:
-/// - customers: Customer management endpoints
-/// - drinks: Drink catalog endpoints
-/// - orders: Order processing endpoints (future implementation)
+//! Route modules for the Coffee Shop API
+//! 
+//! This module organizes all API endpoints by domain:
+//! - auth: Token issuing endpoint
+//! - checkout: Walk-in customer + order endpoint
+//! - customers: Customer management endpoints
+//! - drinks: Drink catalog endpoints
+//! - employees: Employee workload endpoints
+//! - extras: Extra catalog endpoints
+//! - menu: Catalog summary endpoints
+//! - orders: Order processing endpoints
+//! - reports: Order aggregate endpoints
 
+pub mod auth;
+pub mod checkout;
 pub mod customers;
 pub mod drinks;
+pub mod employees;
+pub mod extras;
+pub mod menu;
+pub mod orders;
+pub mod reports;
@@ -0,0 +1,1210 @@
+//! Order Management Routes
+//! 
+//! This module provides REST API endpoints for order operations:
+//! - POST /orders - Place a new order with its items and extras
+//! - GET /orders?status=&customer_id= - Order queue, oldest first (paginated)
+//! - POST /orders/validate - Dry-run every order check and list the problems
+//! - GET /orders/unpaid - Pending orders waiting too long for payment
+//! - GET /orders/queue - Paid, preparing and ready orders for the kitchen display
+//! - GET /orders/recent - Latest orders of every customer (activity feed)
+//! - GET /orders/export.csv?date= - A day's orders as CSV for accounting
+//! - GET /orders/{id} - Order with its items and their extras
+//! - GET /orders/{id}/invoice - Business invoice with line breakdown and totals
+//! - GET /orders/{id}/total - Sum of the item prices, with the extras broken out
+//! - POST /orders/{id}/items - Add an item to a pending order
+//! - POST /order-items/{id}/extras - Attach an extra to an item of a pending order
+//! - DELETE /order-items/{id}/extras/{extra_id} - Detach an extra from an item of a pending order
+//! - PATCH /orders/{id}/customer - Attach an unpaid order to another customer
+//! - PUT /orders/{id}/status - Move an order to its next status
+//! - POST /orders/{id}/cancel - Cancel a pending or paid order
+//! - POST /orders/complete-ready - Complete every ready order (end of shift)
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use rocket::serde::json::Json;
+use rocket::response::status;
+use rocket::{delete, get, patch, post, put, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::DbConn;
+use crate::auth::{AuthenticatedUser, StaffUser};
+use crate::models::auth::Role;
+use crate::db::run_report;
+use crate::error::ApiError;
+use crate::config::AppConfig;
+use crate::guards::{IdempotencyKey, ShopOpen};
+use crate::logic::orders::{
+    allowed_extras, check_minimum_total, check_references, extra_allowed, find_idempotent_response, insert_order,
+    insert_order_items, item_problems, load_order_details, minimum_total_problem, order_problems, price_items,
+    record_idempotency_key, reference_problems, reject_first, transition_order,
+};
+use crate::models::customer::Customer;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteDecimal};
+use crate::models::invoice::{InvoiceApiModel, InvoiceLineApiModel};
+use crate::models::infra::sqlite_types::SqliteOrderStatus;
+use crate::models::order::{
+    BatchTransitionApiModel, IncomingOrder, IncomingOrderItem, NewOrder, Order, OrderApiModel, OrderDetailApiModel,
+    OrderTotalApiModel, OrderValidationApiModel, SkippedOrderApiModel,
+    UpdateOrderCustomer, UpdateOrderStatus,
+};
+use crate::models::order_item_extras::{
+    AttachOrderItemExtra, NewOrderItemExtra, OrderItemExtra, OrderItemExtraApiModel,
+};
+use crate::models::order_items::OrderItem;
+use crate::models::order_status::OrderStatus;
+use crate::responders::{ApiJson, Csv, Paginated, page_window};
+use crate::schema::{customers, drinks, employees, extras, order_item_extras, order_items, orders};
+
+/// Largest `limit` accepted by GET /orders/recent
+const MAX_RECENT_ORDERS: i64 = 100;
+
+/// Create new order
+/// 
+/// Places an order with its items and, for each item, its extras.
+/// Each item is priced server-side: the drink's base_price times the size
+/// multiplier (small 0.8, medium 1.0, large 1.3, standard 1.0) plus its extras'
+/// prices. A `total_price` sent by the client is ignored.
+/// The referenced customer, employee, drinks and extras are checked and the order,
+/// every order item and every item extra inserted inside a single transaction, so
+/// a failure on any row rolls the whole order back.
+/// Generates UUIDs for all created rows automatically.
+/// Returns 423 outside the configured opening hours.
+/// Returns 403 if a customer token places an order for another customer.
+/// An `Idempotency-Key` header (1-255 characters) makes retries safe: a request
+/// repeating a key used in the last 24 hours gets the response of the first
+/// request again (the same 201, Location and body, even after closing time), and
+/// nothing new is inserted.
+/// `placed_at` (RFC 3339, optional) records when the order was actually taken,
+/// e.g. for orders synced from an offline register; it becomes created_at.
+/// Returns 400 if customer_id/employee_id/drink_id is empty, the order has no items,
+/// or placed_at is more than `MAX_CLOCK_SKEW_SECONDS`
+/// in the future.
+/// Returns 422 if the initial status is not `pending` or `paid`, the subtotal (sum of
+/// item prices) is below `MIN_ORDER_TOTAL`, the customer or employee doesn't
+/// exist, an item references an unknown, deleted or unavailable drink or a size
+/// the drink doesn't offer, an extra is duplicated, unknown, unavailable or not
+/// allowed on its drink, or a modifier option (`modifiers`, optional) is unknown,
+/// unavailable, not offered by the drink or a second pick of the same modifier.
+/// POST /orders/validate reports all of these at once. Validation errors name the
+/// offending field in the JSON error message.
+/// Returns 201 with the created order and its Location.
+/// Returns 400 if the Idempotency-Key header is blank or too long, and 409 if a
+/// concurrent request with the same key won the race.
+/// Returns 500 if the transaction fails.
+/// Note: created_at and updated_at are handled automatically server-side.
+#[post("/orders", data = "<incoming_order>")]
+pub async fn create_order(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    config: &State<AppConfig>,
+    idempotency_key: IdempotencyKey,
+    incoming_order: Json<IncomingOrder>,
+) -> Result<status::Created<Json<OrderApiModel>>, ApiError> {
+    // Validate input fields
+    let order_data = incoming_order.into_inner();
+
+    reject_first(order_problems(&order_data, config.max_clock_skew_seconds))?;
+    if !user.can_access_customer(&order_data.customer_id) {
+        warn!("Authorization error: customer {} cannot order for {}", user.subject, order_data.customer_id);
+        return Err(ApiError::new(Status::Forbidden, "Customers may only place their own orders."));
+    }
+
+    let min_order_total = config.min_order_total;
+    let shop_hours = config.shop_hours;
+    let created_order = conn.run(move |c| {
+        // Checked inside the transaction so nothing can be deleted between the checks and the inserts
+        c.transaction::<_, ApiError, _>(|tx| {
+            if let Some(key) = &idempotency_key.0 {
+                let replayed = find_idempotent_response(tx, key).map_err(|e| {
+                    error!("Database error looking up idempotency key: {}", e);
+                    ApiError::from(e)
+                })?;
+                if let Some(order) = replayed {
+                    return Ok(order);
+                }
+            }
+
+            // After the replay lookup, so a retry of an order placed before closing still succeeds
+            if let Some(hours) = shop_hours.filter(|hours| !hours.is_open_at(chrono::Utc::now())) {
+                warn!("Validation error: order rejected outside opening hours");
+                return Err(ApiError::new(Status::Locked, hours.closed_message()));
+            }
+
+            check_references(tx, Some(&order_data.customer_id), &order_data.employee_id, &order_data.items)?;
+            check_minimum_total(tx, &order_data.items, min_order_total)?;
+
+            let order = insert_order(
+                tx,
+                NewOrder {
+                    customer_id: order_data.customer_id,
+                    employee_id: order_data.employee_id,
+                    status: order_data.status,
+                    placed_at: order_data.placed_at,
+                },
+                order_data.items,
+            )
+            .map_err(|e| {
+                error!("Database error creating order: {}", e);
+                ApiError::from(Status::InternalServerError)
+            })?
+            .to_api_model();
+
+            if let Some(key) = idempotency_key.0 {
+                record_idempotency_key(tx, key, &order).map_err(|e| {
+                    error!("Database error recording idempotency key: {}", e);
+                    ApiError::from(e)
+                })?;
+            }
+
+            Ok(order)
+        })
+    })
+    .await?;
+
+    let location = format!("/api/orders/{}", created_order.id);
+    Ok(status::Created::new(location).body(Json(created_order)))
+}
+
+/// Validate an order without placing it
+/// 
+/// Runs every check POST /orders performs (ids present, customer and employee
+/// exist, at least one item, computed subtotal at least `MIN_ORDER_TOTAL`,
+/// drinks active, available and offered in the chosen size, extras unique, available and
+/// allowed on the drink, placed_at not in the future) and reports all problems at once instead of stopping at the
+/// first. Nothing is persisted, and opening hours are not enforced.
+/// Returns 200 with `{"valid": true}` or `{"valid": false, "problems": [{"field", "message"}]}`.
+/// Returns 422 if the body is not a well-formed order (e.g. unknown size or status).
+#[post("/orders/validate", data = "<incoming_order>")]
+pub async fn validate_order(
+    conn: DbConn,
+    config: &State<AppConfig>,
+    incoming_order: Json<IncomingOrder>,
+) -> Result<Json<OrderValidationApiModel>, Status> {
+    let order_data = incoming_order.into_inner();
+    let mut problems = order_problems(&order_data, config.max_clock_skew_seconds);
+
+    let min_order_total = config.min_order_total;
+    conn.run(move |c| {
+        let references = reference_problems(c, Some(&order_data.customer_id), &order_data.employee_id, &order_data.items)
+            .map_err(|e| {
+                error!("Database error validating order: {}", e);
+                Status::InternalServerError
+            })?;
+        problems.extend(references);
+
+        let prices = price_items(c, &order_data.items).map_err(|e| {
+            error!("Database error pricing order: {}", e);
+            Status::InternalServerError
+        })?;
+        problems.extend(minimum_total_problem(prices.into_iter().sum(), min_order_total));
+
+        Ok(Json(OrderValidationApiModel::from(problems)))
+    })
+    .await
+}
+
+/// List orders
+///
+/// The order queue for baristas: orders oldest first by `created_at` (ties broken
+/// by id), so the longest-waiting order comes first. `?status=` keeps only orders
+/// in that status and `?customer_id=` only those of one customer; both are optional.
+/// Paginated like the other list endpoints (`?limit=`, `?offset=`).
+/// Returns 400 if status is not a known order status, customer_id is empty, limit
+/// is above `MAX_PAGE_LIMIT` (200) or below 1, or offset is negative.
+/// Returns 401 without a valid token. A customer token must pass its own id as
+/// `?customer_id=`; any other listing returns 403.
+#[get("/orders?<status>&<customer_id>&<limit>&<offset>")]
+pub async fn get_orders(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    status: Option<String>,
+    customer_id: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Paginated<OrderApiModel>, ApiError> {
+    let (limit, offset) = page_window(limit, offset)?;
+    let status = match status.as_deref().map(OrderStatus::from_str).transpose() {
+        Ok(status) => status.map(SqliteOrderStatus::from),
+        Err(message) => {
+            warn!("Validation error: {}", message);
+            return Err(ApiError::bad_request(message));
+        }
+    };
+    if customer_id.as_deref().is_some_and(|id| id.trim().is_empty()) {
+        warn!("Validation error: customer_id cannot be empty");
+        return Err(ApiError::bad_request("customer_id cannot be empty"));
+    }
+    let allowed = match customer_id.as_deref() {
+        Some(customer_id) => user.can_access_customer(customer_id),
+        None => user.role == Role::Employee,
+    };
+    if !allowed {
+        warn!("Authorization error: customer {} cannot list these orders", user.subject);
+        return Err(ApiError::new(Status::Forbidden, "Customers may only read their own orders."));
+    }
+
+    conn.run(move |c| {
+        let filtered = || {
+            let mut query = orders::table.into_boxed();
+            if let Some(status) = status {
+                query = query.filter(orders::status.eq(status));
+            }
+            if let Some(customer_id) = &customer_id {
+                query = query.filter(orders::customer_id.eq(customer_id.clone()));
+            }
+            query
+        };
+
+        let total = filtered().count().get_result::<i64>(c).map_err(|e| {
+            error!("Database error counting orders: {}", e);
+            ApiError::from(e)
+        })?;
+
+        let results = filtered()
+            .order((orders::created_at.asc(), orders::id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select(Order::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading orders: {}", e);
+                ApiError::from(e)
+            })?;
+
+        let items = results.into_iter().map(|order| order.to_api_model()).collect();
+        Ok(Paginated { items, total, limit, offset })
+    })
+    .await
+}
+
+/// Get unpaid orders
+/// 
+/// Housekeeping view of abandoned carts: returns `pending` orders created more
+/// than `UNPAID_ORDER_AGE_MINUTES` minutes ago (default 30), oldest first, so
+/// staff can follow up or cancel them. Age is measured from `created_at`.
+/// Paginated like the other list endpoints (`?limit=`, `?offset=`); 400 if
+/// limit is above `MAX_PAGE_LIMIT` (200) or below 1, or offset is negative.
+#[get("/orders/unpaid?<limit>&<offset>")]
+pub async fn get_unpaid_orders(
+    conn: DbConn,
+    config: &State<AppConfig>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Paginated<OrderApiModel>, Status> {
+    let (limit, offset) = page_window(limit, offset)?;
+    let cutoff = SqliteDateTime::from(
+        chrono::Utc::now().naive_utc() - chrono::Duration::minutes(config.unpaid_order_age_minutes),
+    );
+    let pending = SqliteOrderStatus::from(OrderStatus::Pending);
+
+    conn.run(move |c| {
+        // ISO 8601 text sorts chronologically, so the cutoff compares directly
+        let total = orders::table
+            .filter(orders::status.eq(pending))
+            .filter(orders::created_at.lt(cutoff))
+            .count()
+            .get_result::<i64>(c)
+            .map_err(|e| {
+                error!("Database error counting unpaid orders: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let results = orders::table
+            .filter(orders::status.eq(pending))
+            .filter(orders::created_at.lt(cutoff))
+            .order((orders::created_at.asc(), orders::id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .select(Order::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading unpaid orders: {}", e);
+                Status::InternalServerError
+            })?;
+
+        let items = results.into_iter().map(|order| order.to_api_model()).collect();
+        Ok(Paginated { items, total, limit, offset })
+    })
+    .await
+}
+
+/// Get the kitchen queue
+///
+/// Orders the kitchen display shows: `paid`, `preparing` and `ready` ones, oldest
+/// first by `created_at` with ties broken by id, each with its items (drink name,
+/// size, extras and modifier options) nested like GET /orders/{id}. Pending orders
+/// have not been paid yet; completed and cancelled ones are left out.
+#[get("/orders/queue")]
+pub async fn get_kitchen_queue(conn: DbConn) -> Result<ApiJson<Vec<OrderDetailApiModel>>, Status> {
+    let kitchen_statuses: Vec<SqliteOrderStatus> =
+        OrderStatus::in_kitchen().into_iter().map(SqliteOrderStatus::from).collect();
+
+    conn.run(move |c| {
+        let queue = orders::table
+            .filter(orders::status.eq_any(kitchen_statuses))
+            .order((orders::created_at.asc(), orders::id.asc()))
+            .select(Order::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading the kitchen queue: {}", e);
+                Status::InternalServerError
+            })?;
+
+        load_order_details(c, queue).map(ApiJson).map_err(|e| {
+            error!("Database error loading items of the kitchen queue: {}", e);
+            Status::InternalServerError
+        })
+    })
+    .await
+}
+
+/// Get the most recent orders
+/// 
+/// Activity feed for dashboards: the latest orders across all customers, newest
+/// first by `created_at` with ties broken by id, each with its items and their
+/// extras nested like GET /orders/{id}. Unlike the list endpoints there is no
+/// pagination: `?limit=` (default 20) is capped at `MAX_RECENT_ORDERS`.
+/// Returns 400 if limit is not positive.
+#[get("/orders/recent?<limit>")]
+pub async fn get_recent_orders(conn: DbConn, limit: Option<i64>) -> Result<ApiJson<Vec<OrderDetailApiModel>>, Status> {
+    let limit = limit.unwrap_or(20);
+    if limit <= 0 {
+        warn!("Validation error: limit must be positive");
+        return Err(Status::BadRequest);
+    }
+    let limit = limit.min(MAX_RECENT_ORDERS);
+
+    conn.run(move |c| {
+        let recent = orders::table
+            .order((orders::created_at.desc(), orders::id.desc()))
+            .limit(limit)
+            .select(Order::as_select())
+            .load(c)
+            .map_err(|e| {
+                error!("Database error loading recent orders: {}", e);
+                Status::InternalServerError
+            })?;
+
+        load_order_details(c, recent).map(ApiJson).map_err(|e| {
+            error!("Database error loading items of recent orders: {}", e);
+            Status::InternalServerError
+        })
+    })
+    .await
+}
+
+/// Change the customer of an order
+/// 
+/// Operational correction for orders attached to the wrong customer.
+/// Only orders that are still `pending` can be moved; once an order is paid
+/// (or has progressed further, or was cancelled) the customer is fixed.
+/// Returns 400 if order_id or customer_id is empty.
+/// Returns 422 if request contains unknown fields, or if the new customer
+/// doesn't exist or is soft-deleted.
+/// Returns 404 if order not found.
+/// Returns 409 if the order is no longer pending.
+/// Requires a staff token; returns 403 for a customer token.
+/// Note: updated_at is handled automatically server-side.
+#[patch("/orders/<order_id>/customer", data = "<update_customer>")]
+pub async fn update_order_customer(
+    _staff: StaffUser,
+    conn: DbConn,
+    order_id: String,
+    update_customer: Json<UpdateOrderCustomer>,
+) -> Result<Json<OrderApiModel>, Status> {
+    // Validate order_id is not empty
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    let update_data = update_customer.into_inner();
+
+    if update_data.customer_id.trim().is_empty() {
+        warn!("Validation error: customer_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    conn.run(move |c| {
+        // First, find the existing order
+        let mut existing_order = orders::table
+            .filter(orders::id.eq(&order_id))
+            .select(Order::as_select())
+            .first(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => Status::NotFound,
+                _ => {
+                    error!("Database error loading order {}: {}", order_id, e);
+                    Status::InternalServerError
+                }
+            })?;
+
+        if existing_order.status.into_order_status() != OrderStatus::Pending {
+            warn!("Conflict: order {} is no longer pending, customer cannot change", order_id);
+            return Err(Status::Conflict);
+        }
+
+        // The new customer must exist and be active
+        customers::table
+            .filter(customers::id.eq(&update_data.customer_id))
+            .filter(customers::deleted_at.is_null())
+            .select(Customer::as_select())
+            .first(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    warn!("Validation error: customer {} not found", update_data.customer_id);
+                    Status::UnprocessableEntity
+                }
+                _ => {
+                    error!("Database error loading customer {}: {}", update_data.customer_id, e);
+                    Status::InternalServerError
+                }
+            })?;
+
+        existing_order.reassign_customer(update_data.customer_id);
+
+        diesel::update(orders::table.filter(orders::id.eq(&order_id)))
+            .set((
+                orders::customer_id.eq(&existing_order.customer_id),
+                orders::updated_at.eq(&existing_order.updated_at),
+            ))
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error updating order {}: {}", order_id, e);
+                Status::InternalServerError
+            })?;
+
+        Ok(Json(existing_order.to_api_model()))
+    })
+    .await
+}
+
+/// Change the status of an order
+/// 
+/// Moves the order along Pending → Paid → Preparing → Ready → Completed, or to
+/// Cancelled while it is still pending or paid, following
+/// `OrderStatus::can_transition_to`. The change is recorded in the status history.
+/// Returns 400 if order_id is empty.
+/// Returns 422 if the status is unknown or the body has unknown fields.
+/// Returns 404 if order not found.
+/// Returns 409 naming both statuses if the transition is not allowed
+/// (e.g. completed back to pending).
+/// Requires a staff token; returns 403 for a customer token.
+#[put("/orders/<order_id>/status", data = "<body>")]
+pub async fn update_order_status(
+    _staff: StaffUser,
+    conn: DbConn,
+    order_id: String,
+    body: Json<UpdateOrderStatus>,
+) -> Result<Json<OrderApiModel>, ApiError> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(ApiError::bad_request("order_id cannot be empty"));
+    }
+
+    let next = body.into_inner().status;
+
+    conn.run(move |c| {
+        let mut existing_order = orders::table
+            .filter(orders::id.eq(&order_id))
+            .select(Order::as_select())
+            .first(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("Database error loading order {}: {}", order_id, e);
+                    ApiError::from(Status::InternalServerError)
+                }
+            })?;
+
+        let current = existing_order.status.into_order_status();
+        if !current.can_transition_to(next) {
+            warn!("Conflict: order {} cannot move from {} to {}", order_id, current, next);
+            return Err(ApiError::conflict(format!("cannot transition from {} to {}", current, next)));
+        }
+
+        c.transaction(|tx| transition_order(tx, &mut existing_order, next))
+            .map_err(|e| {
+                error!("Database error updating status of order {}: {}", order_id, e);
+                ApiError::from(Status::InternalServerError)
+            })?;
+
+        Ok(Json(existing_order.to_api_model()))
+    })
+    .await
+}
+
+/// Cancel an order
+/// 
+/// For customers changing their mind before preparation starts: moves a
+/// `pending` or `paid` order to `cancelled` through the regular transition rules
+/// and records it in the status history. The items stay in place; updated_at is
+/// bumped.
+/// Returns 400 if order_id is empty.
+/// Returns 404 if order not found.
+/// Returns 409 naming the current status if the order is already being prepared,
+/// ready, completed or cancelled.
+/// Returns 403 if a customer token cancels another customer's order.
+/// Returns the cancelled order.
+#[post("/orders/<order_id>/cancel")]
+pub async fn cancel_order(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    order_id: String,
+) -> Result<Json<OrderApiModel>, ApiError> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(ApiError::bad_request("order_id cannot be empty"));
+    }
+
+    conn.run(move |c| {
+        c.transaction::<_, ApiError, _>(|tx| {
+            let mut existing_order = orders::table
+                .filter(orders::id.eq(&order_id))
+                .select(Order::as_select())
+                .first(tx)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                    _ => {
+                        error!("Database error loading order {}: {}", order_id, e);
+                        ApiError::from(Status::InternalServerError)
+                    }
+                })?;
+
+            if !user.can_access_customer(&existing_order.customer_id) {
+                warn!("Authorization error: customer {} cannot cancel order {}", user.subject, order_id);
+                return Err(ApiError::new(Status::Forbidden, "Customers may only cancel their own orders."));
+            }
+
+            let current = existing_order.status.into_order_status();
+            let cancelled = transition_order(tx, &mut existing_order, OrderStatus::Cancelled).map_err(|e| {
+                error!("Database error cancelling order {}: {}", order_id, e);
+                ApiError::from(Status::InternalServerError)
+            })?;
+
+            if !cancelled {
+                warn!("Conflict: order {} cannot be cancelled while {}", order_id, current);
+                return Err(ApiError::conflict(format!("cannot cancel an order that is {}", current)));
+            }
+
+            Ok(Json(existing_order.to_api_model()))
+        })
+    })
+    .await
+}
+
+/// Complete all ready orders
+/// 
+/// End-of-shift cleanup: moves every order currently in `ready` to `completed`
+/// in a single transaction, oldest first. Each order goes through the regular
+/// transition rules (and is recorded in the status history); any order whose transition is illegal is left untouched and
+/// reported under `skipped` instead of failing the batch.
+/// Returns the ids of the completed orders under `updated`.
+/// Returns 500 (and rolls back every change) if the transaction fails.
+/// Requires a staff token; returns 403 for a customer token.
+#[post("/orders/complete-ready")]
+pub async fn complete_ready_orders(
+    _staff: StaffUser,
+    conn: DbConn,
+) -> Result<Json<BatchTransitionApiModel>, Status> {
+    conn.run(|c| {
+        c.transaction::<_, diesel::result::Error, _>(|tx| {
+            let ready_orders = orders::table
+                .filter(orders::status.eq(SqliteOrderStatus::from(OrderStatus::Ready)))
+                .order((orders::created_at.asc(), orders::id.asc()))
+                .select(Order::as_select())
+                .load(tx)?;
+
+            let mut result = BatchTransitionApiModel { updated: Vec::new(), skipped: Vec::new() };
+
+            for mut order in ready_orders {
+                let current = order.status.into_order_status();
+                if !transition_order(tx, &mut order, OrderStatus::Completed)? {
+                    result.skipped.push(SkippedOrderApiModel {
+                        id: order.id,
+                        status: current,
+                        reason: format!("cannot transition from {} to {}", current, OrderStatus::Completed),
+                    });
+                    continue;
+                }
+
+                result.updated.push(order.id);
+            }
+
+            Ok(result)
+        })
+        .map(Json)
+        .map_err(|e| {
+            error!("Database error completing ready orders: {}", e);
+            Status::InternalServerError
+        })
+    })
+    .await
+}
+
+/// Export a day's orders as CSV
+/// 
+/// Returns every order created on `date` (`YYYY-MM-DD`, default today) as a CSV
+/// attachment for accounting, oldest first. Days follow the shop's local time
+/// (`SHOP_UTC_OFFSET`, UTC when opening hours are not configured).
+/// Columns: order id, short code, customer, employee, status, total (sum of item
+/// prices, 2 decimals), created_at. A day without orders yields only the header row.
+/// Returns 400 if date is not a valid `YYYY-MM-DD` date.
+/// Returns 503 if the export exceeds `REPORT_TIMEOUT_SECONDS`.
+#[get("/orders/export.csv?<date>")]
+pub async fn export_orders_csv(
+    conn: DbConn,
+    config: &State<AppConfig>,
+    date: Option<String>,
+) -> Result<Csv, ApiError> {
+    let offset = config.local_offset();
+    let date = match date {
+        Some(raw) => NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| {
+            warn!("Validation error: '{}' is not a valid YYYY-MM-DD date", raw);
+            ApiError::bad_request(format!("'{}' is not a valid YYYY-MM-DD date", raw))
+        })?,
+        None => chrono::Utc::now().with_timezone(&offset).date_naive(),
+    };
+    let (start, end) = SqliteDateTime::day_bounds(date, offset);
+
+    let timeout = Duration::from_secs(config.report_timeout_seconds);
+    let rows = run_report(&conn, timeout, move |c, deadline| {
+        let day_orders = orders::table
+            .inner_join(customers::table)
+            .inner_join(employees::table)
+            .filter(orders::created_at.ge(start))
+            .filter(orders::created_at.lt(end))
+            .order((orders::created_at.asc(), orders::id.asc()))
+            .select((Order::as_select(), customers::name, employees::name))
+            .load::<(Order, String, String)>(c)
+            .map_err(|e| {
+                error!("Database error loading orders for {}: {}", date, e);
+                Status::InternalServerError
+            })?;
+        deadline.check()?;
+
+        let order_ids: Vec<&String> = day_orders.iter().map(|(order, _, _)| &order.id).collect();
+        let mut totals: HashMap<String, Decimal> = HashMap::new();
+        for (order_id, price) in order_items::table
+            .filter(order_items::order_id.eq_any(order_ids))
+            .select((order_items::order_id, order_items::total_price))
+            .load::<(String, SqliteDecimal)>(c)
+            .map_err(|e| {
+                error!("Database error loading order totals for {}: {}", date, e);
+                Status::InternalServerError
+            })?
+        {
+            *totals.entry(order_id).or_default() += price.into_decimal();
+        }
+
+        Ok(day_orders
+            .into_iter()
+            .map(|(order, customer_name, employee_name)| {
+                let total = totals.get(&order.id).copied().unwrap_or_default();
+                vec![
+                    order.id.clone(),
+                    order.short_code(),
+                    customer_name,
+                    employee_name,
+                    order.status.into_order_status().to_string(),
+                    format!("{:.2}", total.round_dp(2)),
+                    order.created_at.into_naive_date_time().format("%Y-%m-%dT%H:%M:%S").to_string(),
+                ]
+            })
+            .collect())
+    })
+    .await?;
+
+    Ok(Csv {
+        filename: format!("orders-{}.csv", date),
+        header: vec!["order_id", "short_code", "customer", "employee", "status", "total", "created_at"],
+        rows,
+    })
+}
+
+/// Get order by ID
+/// 
+/// Returns the order with its items nested, each with the extras attached to it.
+/// Items are listed in the sequence they were placed (their `position`), ties
+/// broken by item id; extras are sorted by name.
+/// Returns 400 if order_id is empty.
+/// Returns 404 if the order does not exist.
+#[get("/orders/<order_id>")]
+pub async fn get_order(conn: DbConn, order_id: String) -> Result<ApiJson<OrderDetailApiModel>, Status> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(Status::BadRequest);
+    }
+
+    conn.run(move |c| {
+        let order = orders::table
+            .filter(orders::id.eq(&order_id))
+            .select(Order::as_select())
+            .first(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => Status::NotFound,
+                _ => {
+                    error!("Database error loading order {}: {}", order_id, e);
+                    Status::InternalServerError
+                }
+            })?;
+
+        load_order_details(c, vec![order])
+            .map(|mut details| ApiJson(details.remove(0)))
+            .map_err(|e| {
+                error!("Database error loading items for order {}: {}", order_id, e);
+                Status::InternalServerError
+            })
+    })
+    .await
+}
+
+/// Get an order's invoice
+/// 
+/// Returns a formal invoice: number (`INV-` + the order's short code), issue date
+/// (the order date), seller details from configuration, customer details and one
+/// line per order item, followed by subtotal, tax (`TAX_RATE_PERCENT`), discount
+/// and total. All money is formatted with 2 decimals.
+/// Returns 400 if order_id is empty.
+/// Returns 404 if the order does not exist.
+/// Returns 401 without a valid token, 403 for another customer's token.
+#[get("/orders/<order_id>/invoice")]
+pub async fn get_order_invoice(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    config: &State<AppConfig>,
+    order_id: String,
+) -> Result<ApiJson<InvoiceApiModel>, ApiError> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    let seller = config.seller.clone();
+    let tax_rate_percent = config.tax_rate_percent;
+
+    conn.run(move |c| {
+        let (order, customer) = orders::table
+            .inner_join(customers::table)
+            .filter(orders::id.eq(&order_id))
+            .select((Order::as_select(), Customer::as_select()))
+            .first::<(Order, Customer)>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => Status::NotFound,
+                _ => {
+                    error!("Database error loading order {}: {}", order_id, e);
+                    Status::InternalServerError
+                }
+            })?;
+
+        if !user.can_access_customer(&order.customer_id) {
+            warn!("Authorization error: customer {} cannot read invoice of order {}", user.subject, order_id);
+            return Err(ApiError::new(Status::Forbidden, "Customers may only read their own invoices."));
+        }
+
+        let items = order_items::table
+            .inner_join(drinks::table)
+            .filter(order_items::order_id.eq(&order_id))
+            .order((order_items::position.asc(), order_items::id.asc()))
+            .select((OrderItem::as_select(), drinks::name))
+            .load::<(OrderItem, String)>(c)
+            .map_err(|e| {
+                error!("Database error loading items for order {}: {}", order_id, e);
+                Status::InternalServerError
+            })?;
+
+        let item_ids: Vec<&String> = items.iter().map(|(item, _)| &item.id).collect();
+        let item_extras = order_item_extras::table
+            .inner_join(extras::table)
+            .filter(order_item_extras::order_item_id.eq_any(item_ids))
+            .order(extras::name.asc())
+            .select((order_item_extras::order_item_id, extras::name))
+            .load::<(String, String)>(c)
+            .map_err(|e| {
+                error!("Database error loading extras for order {}: {}", order_id, e);
+                Status::InternalServerError
+            })?;
+
+        let lines = items
+            .iter()
+            .map(|(item, drink_name)| {
+                let extras = item_extras
+                    .iter()
+                    .filter(|(item_id, _)| *item_id == item.id)
+                    .map(|(_, name)| name.clone())
+                    .collect();
+                InvoiceLineApiModel::new(item, drink_name, extras)
+            })
+            .collect();
+
+        Ok(ApiJson(InvoiceApiModel::build(&order, &customer, &seller, tax_rate_percent, lines)))
+    })
+    .await
+}
+
+/// Get an order's total
+/// 
+/// `subtotal` is the sum of the items' `total_price`, which already includes
+/// their extras, and `total` equals it (the invoice total before tax). `extras`
+/// is the price every extra attached to those items was charged at and
+/// `drinks` the rest of the subtotal (`subtotal - extras`). All arithmetic is done
+/// in `Decimal`; amounts are rendered with 2 decimals, so an order without items
+/// has a total of "0.00".
+/// Returns 400 if order_id is empty.
+/// Returns 404 if the order does not exist.
+#[get("/orders/<order_id>/total")]
+pub async fn get_order_total(conn: DbConn, order_id: String) -> Result<ApiJson<OrderTotalApiModel>, ApiError> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    conn.run(move |c| {
+        orders::table
+            .filter(orders::id.eq(&order_id))
+            .select(orders::id)
+            .first::<String>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("Database error loading order {}: {}", order_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+
+        let item_prices = order_items::table
+            .filter(order_items::order_id.eq(&order_id))
+            .select(order_items::total_price)
+            .load::<SqliteDecimal>(c)
+            .map_err(|e| {
+                error!("Database error loading items for order {}: {}", order_id, e);
+                ApiError::from(e)
+            })?;
+
+        let extra_prices = order_item_extras::table
+            .inner_join(order_items::table)
+            .filter(order_items::order_id.eq(&order_id))
+            .select(order_item_extras::extra_price)
+            .load::<SqliteDecimal>(c)
+            .map_err(|e| {
+                error!("Database error loading extras for order {}: {}", order_id, e);
+                ApiError::from(e)
+            })?;
+
+        let subtotal: Decimal = item_prices.into_iter().map(SqliteDecimal::into_decimal).sum();
+        let extras: Decimal = extra_prices.into_iter().map(SqliteDecimal::into_decimal).sum();
+
+        Ok(ApiJson(OrderTotalApiModel { drinks: subtotal - extras, extras, subtotal, total: subtotal }))
+    })
+    .await
+}
+
+/// Add an item to an order
+/// 
+/// Adds one drink, with its extras and modifier options, to a `pending` order,
+/// e.g. when a customer orders another drink before paying. The item is checked
+/// and priced like the items of POST /orders and placed after the existing ones;
+/// validation messages name it `items[0]`. The order's updated_at is bumped.
+/// Returns 400 if order_id or drink_id is empty.
+/// Returns 404 if order not found.
+/// Returns 409 if the order is no longer pending.
+/// Returns 422 if the drink, a size, an extra or a modifier option is invalid, as
+/// for POST /orders.
+/// Returns 423 outside the configured opening hours.
+/// Returns 403 if a customer token adds to another customer's order.
+/// Returns the order with all its items.
+#[post("/orders/<order_id>/items", data = "<item>")]
+pub async fn add_order_item(
+    user: AuthenticatedUser,
+    _open: ShopOpen,
+    conn: DbConn,
+    order_id: String,
+    item: Json<IncomingOrderItem>,
+) -> Result<Json<OrderDetailApiModel>, ApiError> {
+    if order_id.trim().is_empty() {
+        warn!("Validation error: order_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    let items = vec![item.into_inner()];
+    reject_first(item_problems(&items))?;
+
+    conn.run(move |c| {
+        c.transaction::<_, ApiError, _>(|tx| {
+            let mut order = orders::table
+                .filter(orders::id.eq(&order_id))
+                .select(Order::as_select())
+                .first(tx)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                    _ => {
+                        error!("Database error loading order {}: {}", order_id, e);
+                        ApiError::from(e)
+                    }
+                })?;
+
+            if !user.can_access_customer(&order.customer_id) {
+                warn!("Authorization error: customer {} cannot change order {}", user.subject, order_id);
+                return Err(ApiError::new(Status::Forbidden, "Customers may only change their own orders."));
+            }
+
+            let status = order.status.into_order_status();
+            if status != OrderStatus::Pending {
+                warn!("Conflict: order {} is {}, items cannot be added", order_id, status);
+                return Err(ApiError::conflict(format!("items can only be added to pending orders, this one is {}", status)));
+            }
+
+            check_references(tx, None, &order.employee_id, &items)?;
+
+            let next_position = order_items::table
+                .filter(order_items::order_id.eq(&order_id))
+                .select(diesel::dsl::max(order_items::position))
+                .first::<Option<i32>>(tx)
+                .map_err(|e| {
+                    error!("Database error loading items for order {}: {}", order_id, e);
+                    ApiError::from(e)
+                })?
+                .map_or(0, |position| position + 1);
+
+            insert_order_items(tx, &order_id, items, next_position).map_err(|e| {
+                error!("Database error adding item to order {}: {}", order_id, e);
+                ApiError::from(Status::InternalServerError)
+            })?;
+
+            order.touch();
+            diesel::update(orders::table.filter(orders::id.eq(&order_id)))
+                .set(orders::updated_at.eq(&order.updated_at))
+                .execute(tx)
+                .map_err(|e| {
+                    error!("Database error updating order {}: {}", order_id, e);
+                    ApiError::from(e)
+                })?;
+
+            load_order_details(tx, vec![order])
+                .map(|mut details| Json(details.remove(0)))
+                .map_err(|e| {
+                    error!("Database error loading items for order {}: {}", order_id, e);
+                    ApiError::from(e)
+                })
+        })
+    })
+    .await
+}
+
+/// Attach an extra to an order item
+/// 
+/// Adds one extra to an item of a `pending` order. The extra's current price is
+/// stored on the link and added to the item's `total_price`, as for extras listed when the order was placed, so
+/// the order total follows. Each extra can be attached to an item only once
+/// (enforced by a UNIQUE index on `order_item_extras(order_item_id, extra_id)`).
+/// Returns 400 if order_item_id or extra_id is empty.
+/// Returns 404 if the order item does not exist.
+/// Returns 409 if the extra is already attached or the order is no longer pending.
+/// Returns 422 if the extra does not exist or is unavailable, or if the item's drink
+/// restricts its extras in `drink_extras` and this is not one of them (as for POST /orders).
+/// Returns 403 if a customer token changes another customer's order.
+/// Returns 201 with the created attachment.
+#[post("/order-items/<order_item_id>/extras", data = "<attach_extra>")]
+pub async fn attach_order_item_extra(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    order_item_id: String,
+    attach_extra: Json<AttachOrderItemExtra>,
+) -> Result<status::Created<Json<OrderItemExtraApiModel>>, ApiError> {
+    let attach_data = attach_extra.into_inner();
+
+    if order_item_id.trim().is_empty() || attach_data.extra_id.trim().is_empty() {
+        warn!("Validation error: order_item_id and extra_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    let created_extra = conn.run(move |c| c.transaction::<_, ApiError, _>(|c| {
+        let (customer_id, order_status, drink_id, item_price) = order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::id.eq(&order_item_id))
+            .select((orders::customer_id, orders::status, order_items::drink_id, order_items::total_price))
+            .first::<(String, SqliteOrderStatus, String, SqliteDecimal)>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("Database error loading order item {}: {}", order_item_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+        if !user.can_access_customer(&customer_id) {
+            warn!("Authorization error: customer {} cannot change order item {}", user.subject, order_item_id);
+            return Err(ApiError::new(Status::Forbidden, "Customers may only change their own orders."));
+        }
+        let order_status = order_status.into_order_status();
+
+        if order_status != OrderStatus::Pending {
+            warn!("Conflict: order item {} belongs to a {} order", order_item_id, order_status);
+            return Err(ApiError::conflict(format!(
+                "extras can only be attached to pending orders, this one is {}",
+                order_status
+            )));
+        }
+
+        let extra_price = extras::table
+            .filter(extras::id.eq(&attach_data.extra_id))
+            .filter(extras::is_available.eq(true))
+            .select(extras::extra_price)
+            .first::<SqliteDecimal>(c)
+            .optional()
+            .map_err(|e| {
+                error!("Database error loading extra {}: {}", attach_data.extra_id, e);
+                ApiError::from(e)
+            })?;
+
+        let Some(extra_price) = extra_price else {
+            warn!("Validation error: extra {} does not exist or is unavailable", attach_data.extra_id);
+            return Err(Status::UnprocessableEntity.into());
+        };
+
+        let allowed = allowed_extras(c, &[&drink_id]).map_err(|e| {
+            error!("Database error loading extras allowed on drink {}: {}", drink_id, e);
+            ApiError::from(e)
+        })?;
+        if !extra_allowed(&allowed, &drink_id, &attach_data.extra_id) {
+            let message = format!("extra_id: extra {} cannot be added to drink {}", attach_data.extra_id, drink_id);
+            warn!("Validation error: {}", message);
+            return Err(ApiError::new(Status::UnprocessableEntity, message));
+        }
+
+        let db_extra = OrderItemExtra::from_new(
+            NewOrderItemExtra {
+                order_item_id: order_item_id.clone(),
+                extra_id: attach_data.extra_id,
+                extra_price: extra_price.into_decimal(),
+            },
+            Uuid::new_v4().to_string(),
+        );
+
+        diesel::insert_into(order_item_extras::table)
+            .values(&db_extra)
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error attaching extra to order item {}: {}", order_item_id, e);
+                // A unique constraint violation (extra already attached) becomes a 409
+                ApiError::from(e)
+            })?;
+
+        let item_price = SqliteDecimal::from(item_price.into_decimal() + db_extra.extra_price.into_decimal());
+        diesel::update(order_items::table.filter(order_items::id.eq(&order_item_id)))
+            .set(order_items::total_price.eq(item_price))
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error repricing order item {}: {}", order_item_id, e);
+                ApiError::from(e)
+            })?;
+
+        Ok(db_extra)
+    }))
+    .await?;
+
+    let location = format!("/api/order-items/{}/extras", created_extra.order_item_id);
+    Ok(status::Created::new(location).body(Json(created_extra.to_api_model())))
+}
+
+/// Detach an extra from an order item
+/// 
+/// Hard-deletes the `order_item_extras` row linking the extra to an item of a
+/// `pending` order (link rows have no `deleted_at`) and takes the price the extra
+/// was charged at, stored on the link, off the item's `total_price`. The UNIQUE index on
+/// `order_item_extras(order_item_id, extra_id)` allows a single link per pair, so
+/// there is never more than one row to remove.
+/// Returns 400 if order_item_id or extra_id is empty.
+/// Returns 404 if the order item does not exist or the extra is not attached to it.
+/// Returns 409 if the order is no longer pending.
+/// Returns 403 if a customer token changes another customer's order.
+/// Returns 204 on success.
+#[delete("/order-items/<order_item_id>/extras/<extra_id>")]
+pub async fn detach_order_item_extra(
+    user: AuthenticatedUser,
+    conn: DbConn,
+    order_item_id: String,
+    extra_id: String,
+) -> Result<Status, ApiError> {
+    if order_item_id.trim().is_empty() || extra_id.trim().is_empty() {
+        warn!("Validation error: order_item_id and extra_id cannot be empty");
+        return Err(Status::BadRequest.into());
+    }
+
+    conn.run(move |c| c.transaction::<_, ApiError, _>(|c| {
+        let (customer_id, order_status, item_price) = order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::id.eq(&order_item_id))
+            .select((orders::customer_id, orders::status, order_items::total_price))
+            .first::<(String, SqliteOrderStatus, SqliteDecimal)>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("Database error loading order item {}: {}", order_item_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+        if !user.can_access_customer(&customer_id) {
+            warn!("Authorization error: customer {} cannot change order item {}", user.subject, order_item_id);
+            return Err(ApiError::new(Status::Forbidden, "Customers may only change their own orders."));
+        }
+        let order_status = order_status.into_order_status();
+
+        if order_status != OrderStatus::Pending {
+            warn!("Conflict: order item {} belongs to a {} order", order_item_id, order_status);
+            return Err(ApiError::conflict(format!(
+                "extras can only be detached from pending orders, this one is {}",
+                order_status
+            )));
+        }
+
+        let extra_price = order_item_extras::table
+            .filter(order_item_extras::order_item_id.eq(&order_item_id))
+            .filter(order_item_extras::extra_id.eq(&extra_id))
+            .select(order_item_extras::extra_price)
+            .first::<SqliteDecimal>(c)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => ApiError::from(Status::NotFound),
+                _ => {
+                    error!("Database error loading extra {} of order item {}: {}", extra_id, order_item_id, e);
+                    ApiError::from(e)
+                }
+            })?;
+
+        diesel::delete(
+            order_item_extras::table
+                .filter(order_item_extras::order_item_id.eq(&order_item_id))
+                .filter(order_item_extras::extra_id.eq(&extra_id)),
+        )
+        .execute(c)
+        .map_err(|e| {
+            error!("Database error detaching extra {} from order item {}: {}", extra_id, order_item_id, e);
+            ApiError::from(e)
+        })?;
+
+        let item_price = SqliteDecimal::from(item_price.into_decimal() - extra_price.into_decimal());
+        diesel::update(order_items::table.filter(order_items::id.eq(&order_item_id)))
+            .set(order_items::total_price.eq(item_price))
+            .execute(c)
+            .map_err(|e| {
+                error!("Database error repricing order item {}: {}", order_item_id, e);
+                ApiError::from(e)
+            })?;
+
+        Ok(Status::NoContent)
+    }))
+    .await
+}
+
+/// Export all order routes
+/// 
+/// This function returns all order-related routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![create_order, get_orders, validate_order, get_unpaid_orders, get_kitchen_queue, get_recent_orders, export_orders_csv, get_order, get_order_invoice, get_order_total, add_order_item, attach_order_item_extra, detach_order_item_extra, update_order_customer, update_order_status, cancel_order, complete_ready_orders]
+}
@@ -0,0 +1,149 @@
+//! Reporting Routes
+//! 
+//! This module provides read-only REST API endpoints aggregating orders:
+//! - GET /reports/daily-order-counts?from=&to= - Orders per day, for charts
+//! - GET /reports/employee-load - Active orders per employee, for staffing
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use rocket::{get, routes, Route, State};
+use rocket::http::Status;
+use diesel::prelude::*;
+
+use crate::DbConn;
+use crate::config::AppConfig;
+use crate::db::run_report;
+use crate::error::ApiError;
+use crate::models::infra::sqlite_types::{SqliteDateTime, SqliteOrderStatus};
+use crate::models::order_status::OrderStatus;
+use crate::models::report::{DailyOrderCountApiModel, EmployeeLoadApiModel};
+use crate::responders::ApiJson;
+use crate::schema::orders;
+
+/// Longest range, in days, accepted by the daily reports
+const MAX_REPORT_DAYS: i64 = 366;
+
+/// Parse a `YYYY-MM-DD` query parameter
+fn parse_date(name: &str, raw: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| {
+        warn!("Validation error: {} '{}' is not a valid YYYY-MM-DD date", name, raw);
+        ApiError::bad_request(format!("{} '{}' is not a valid YYYY-MM-DD date", name, raw))
+    })
+}
+
+/// Get daily order counts
+/// 
+/// Returns `[{"date", "count"}]` with one entry for every date from `from` to `to`
+/// (inclusive, `YYYY-MM-DD`), days without orders included with a count of 0, so
+/// a chart has no gaps. Every order counts, whatever its status. Days follow the
+/// shop's local time (`SHOP_UTC_OFFSET`, UTC when opening hours are not
+/// configured). `to` defaults to today and `from` to 29 days before `to`.
+/// Returns 400 if a date is invalid, `from` is after `to`, or the range spans more
+/// than `MAX_REPORT_DAYS` days.
+/// Returns 503 if the report exceeds `REPORT_TIMEOUT_SECONDS`.
+#[get("/reports/daily-order-counts?<from>&<to>")]
+pub async fn get_daily_order_counts(
+    conn: DbConn,
+    config: &State<AppConfig>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<ApiJson<Vec<DailyOrderCountApiModel>>, ApiError> {
+    let offset = config.local_offset();
+    let to = match to {
+        Some(raw) => parse_date("to", &raw)?,
+        None => chrono::Utc::now().with_timezone(&offset).date_naive(),
+    };
+    let from = match from {
+        Some(raw) => parse_date("from", &raw)?,
+        None => to - chrono::Duration::days(29),
+    };
+
+    if from > to {
+        warn!("Validation error: from {} is after to {}", from, to);
+        return Err(ApiError::bad_request(format!("from {} is after to {}", from, to)));
+    }
+
+    let days = (to - from).num_days() + 1;
+    if days > MAX_REPORT_DAYS {
+        warn!("Validation error: range of {} days exceeds {}", days, MAX_REPORT_DAYS);
+        return Err(ApiError::bad_request(format!(
+            "the range spans {} days, at most {} are allowed",
+            days, MAX_REPORT_DAYS
+        )));
+    }
+
+    let (start, _) = SqliteDateTime::day_bounds(from, offset);
+    let (_, end) = SqliteDateTime::day_bounds(to, offset);
+
+    let timeout = Duration::from_secs(config.report_timeout_seconds);
+    let mut counts = run_report(&conn, timeout, move |c, _deadline| {
+        let created = orders::table
+            .filter(orders::created_at.ge(start))
+            .filter(orders::created_at.lt(end))
+            .select(orders::created_at)
+            .load::<SqliteDateTime>(c)
+            .map_err(|e| {
+                error!("Database error loading orders from {} to {}: {}", from, to, e);
+                Status::InternalServerError
+            })?;
+
+        // Bucket by local calendar day, which SQL can't do on the stored UTC text
+        let mut counts: HashMap<NaiveDate, i64> = HashMap::new();
+        for created_at in created {
+            let local_date = (created_at.into_naive_date_time() + offset).date();
+            *counts.entry(local_date).or_default() += 1;
+        }
+        Ok(counts)
+    })
+    .await?;
+
+    let series = from
+        .iter_days()
+        .take(days as usize)
+        .map(|date| DailyOrderCountApiModel { date, count: counts.remove(&date).unwrap_or(0) })
+        .collect();
+
+    Ok(ApiJson(series))
+}
+
+/// Get the active order load of each employee
+/// 
+/// Returns `[{"employee_id", "active_orders"}]` counting the orders assigned to each
+/// employee that are not in a terminal state (anything but `completed`/`cancelled`),
+/// busiest employee first, ties broken by id. Employees without active orders are
+/// left out. The orders themselves are listed by GET /employees/{id}/orders.
+/// Returns 503 if the report exceeds `REPORT_TIMEOUT_SECONDS`.
+#[get("/reports/employee-load")]
+pub async fn get_employee_load(
+    conn: DbConn,
+    config: &State<AppConfig>,
+) -> Result<ApiJson<Vec<EmployeeLoadApiModel>>, ApiError> {
+    let active_statuses: Vec<SqliteOrderStatus> =
+        OrderStatus::active().into_iter().map(SqliteOrderStatus::from).collect();
+
+    let timeout = Duration::from_secs(config.report_timeout_seconds);
+    run_report(&conn, timeout, move |c, _deadline| {
+        orders::table
+            .filter(orders::status.eq_any(active_statuses))
+            .group_by(orders::employee_id)
+            .select((orders::employee_id, diesel::dsl::count_star()))
+            .order((diesel::dsl::count_star().desc(), orders::employee_id.asc()))
+            .load::<EmployeeLoadApiModel>(c)
+            .map_err(|e| {
+                error!("Database error counting active orders per employee: {}", e);
+                Status::InternalServerError
+            })
+    })
+    .await
+    .map(ApiJson)
+}
+
+/// Export all reporting routes
+/// 
+/// This function returns all report routes that should be mounted
+/// on the Rocket application. Mount these under "/api" prefix.
+pub fn routes() -> Vec<Route> {
+    routes![get_daily_order_counts, get_employee_load]
+}
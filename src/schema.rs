@@ -8,6 +8,21 @@ diesel::table! {
         created_at -> Text,
         updated_at -> Text,
         deleted_at -> Nullable<Text>,
+        phone -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    drink_extras (drink_id, extra_id) {
+        drink_id -> Text,
+        extra_id -> Text,
+    }
+}
+
+diesel::table! {
+    drink_modifiers (drink_id, modifier_id) {
+        drink_id -> Text,
+        modifier_id -> Text,
     }
 }
 
@@ -19,6 +34,19 @@ diesel::table! {
         created_at -> Text,
         updated_at -> Text,
         deleted_at -> Nullable<Text>,
+        available_sizes -> Text,
+        category -> Nullable<Text>,
+        is_available -> Bool,
+    }
+}
+
+diesel::table! {
+    drink_price_history (id) {
+        id -> Text,
+        drink_id -> Text,
+        old_price -> Text,
+        new_price -> Text,
+        changed_at -> Text,
     }
 }
 
@@ -43,11 +71,56 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    idempotency_keys (key) {
+        key -> Text,
+        order_id -> Text,
+        created_at -> Text,
+        response -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    modifier_options (id) {
+        id -> Text,
+        modifier_id -> Text,
+        name -> Text,
+        is_available -> Bool,
+    }
+}
+
+diesel::table! {
+    modifiers (id) {
+        id -> Text,
+        name -> Text,
+    }
+}
+
 diesel::table! {
     order_item_extras (id) {
         id -> Text,
         order_item_id -> Text,
         extra_id -> Text,
+        extra_price -> Text,
+    }
+}
+
+diesel::table! {
+    order_item_modifiers (id) {
+        id -> Text,
+        order_item_id -> Text,
+        modifier_id -> Text,
+        modifier_option_id -> Text,
+    }
+}
+
+diesel::table! {
+    order_status_history (id) {
+        id -> Text,
+        order_id -> Text,
+        from_status -> Text,
+        to_status -> Text,
+        changed_at -> Text,
     }
 }
 
@@ -58,6 +131,7 @@ diesel::table! {
         drink_id -> Text,
         size -> Text,
         total_price -> Text,
+        position -> Integer,
     }
 }
 
@@ -72,19 +146,38 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(drink_extras -> drinks (drink_id));
+diesel::joinable!(drink_extras -> extras (extra_id));
+diesel::joinable!(drink_modifiers -> drinks (drink_id));
+diesel::joinable!(drink_modifiers -> modifiers (modifier_id));
+diesel::joinable!(drink_price_history -> drinks (drink_id));
+diesel::joinable!(idempotency_keys -> orders (order_id));
+diesel::joinable!(modifier_options -> modifiers (modifier_id));
 diesel::joinable!(order_item_extras -> extras (extra_id));
 diesel::joinable!(order_item_extras -> order_items (order_item_id));
+diesel::joinable!(order_item_modifiers -> modifier_options (modifier_option_id));
+diesel::joinable!(order_item_modifiers -> modifiers (modifier_id));
+diesel::joinable!(order_item_modifiers -> order_items (order_item_id));
 diesel::joinable!(order_items -> drinks (drink_id));
 diesel::joinable!(order_items -> orders (order_id));
+diesel::joinable!(order_status_history -> orders (order_id));
 diesel::joinable!(orders -> customers (customer_id));
 diesel::joinable!(orders -> employees (employee_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     customers,
+    drink_extras,
+    drink_modifiers,
+    drink_price_history,
     drinks,
     employees,
     extras,
+    idempotency_keys,
+    modifier_options,
+    modifiers,
     order_item_extras,
+    order_item_modifiers,
     order_items,
+    order_status_history,
     orders,
 );
@@ -0,0 +1,165 @@
+//! Input format checks shared by the route modules
+
+use rust_decimal::Decimal;
+
+use crate::error::ApiError;
+
+/// Canonical form of an email address: trimmed and lowercase
+///
+/// Emails are stored in this form so "User@Example.com" and "user@example.com"
+/// are the same customer under the UNIQUE constraint.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Check that an email address is well-formed
+///
+/// Deliberately loose, no full RFC 5322 parsing: a non-empty local part, exactly
+/// one `@`, no whitespace, and a domain of at least two non-empty dot-separated
+/// labels ("a@b.co"), so a leading, trailing or doubled dot in the domain is
+/// rejected. Every path writing a customer email uses it, and employee writes
+/// should too. Fails with 400 saying the email is malformed.
+pub fn validate_email(email: &str) -> Result<(), ApiError> {
+    if is_well_formed_email(email) {
+        Ok(())
+    } else {
+        warn!("Validation error: malformed email {:?}", email);
+        Err(ApiError::bad_request(format!("email '{}' is malformed", email)))
+    }
+}
+
+fn is_well_formed_email(email: &str) -> bool {
+    if email.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && domain.split('.').all(|label| !label.is_empty())
+        }
+        None => false,
+    }
+}
+
+/// Fewest and most digits in a phone number (the latter is the E.164 maximum)
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 6..=15;
+
+/// Check that a phone number is plausible
+///
+/// Loose on purpose, as formats vary by country: an optional leading `+`, then
+/// digits optionally grouped with spaces, dashes, dots or parentheses, 6 to 15
+/// digits in total. Surrounding whitespace is ignored. Fails with 400 saying the
+/// phone is malformed.
+pub fn validate_phone(phone: &str) -> Result<(), ApiError> {
+    let number = phone.trim();
+    let number = number.strip_prefix('+').unwrap_or(number);
+    let digits = number.chars().filter(char::is_ascii_digit).count();
+    let well_formed = PHONE_DIGITS.contains(&digits)
+        && number.chars().all(|ch| ch.is_ascii_digit() || matches!(ch, ' ' | '-' | '.' | '(' | ')'));
+
+    if well_formed {
+        Ok(())
+    } else {
+        warn!("Validation error: malformed phone {:?}", phone);
+        Err(ApiError::bad_request(format!("phone '{}' is malformed", phone)))
+    }
+}
+
+/// Decimal places of a stored price
+pub const PRICE_DECIMALS: u32 = 2;
+
+/// Check that a price is a whole number of cents and write it with two decimals
+///
+/// Trailing zeros don't count, so "3.9", "3.90" and "3.900" are all accepted and
+/// become 3.90. A price with more decimal places ("3.999") fails with 400 naming
+/// `field` rather than being rounded silently.
+pub fn validate_price(field: &str, price: Decimal) -> Result<Decimal, ApiError> {
+    let mut cents = price.normalize();
+    if cents.scale() > PRICE_DECIMALS {
+        warn!("Validation error: {} {} has more than {} decimal places", field, price, PRICE_DECIMALS);
+        return Err(ApiError::bad_request(format!(
+            "{} cannot have more than {} decimal places",
+            field, PRICE_DECIMALS
+        )));
+    }
+
+    cents.rescale(PRICE_DECIMALS);
+    Ok(cents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_email_trims_and_lowercases() {
+        assert_eq!(normalize_email("  User@Example.COM "), "user@example.com");
+        assert_eq!(normalize_email("user@example.com"), "user@example.com");
+    }
+
+    #[test]
+    fn accepts_plain_emails() {
+        for email in ["a@b.co", "john.doe@example.com", "first+tag@mail.example.org", "JOHN@EXAMPLE.COM"] {
+            assert!(validate_email(email).is_ok(), "{:?} should be accepted", email);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_emails() {
+        let cases = [
+            ("john doe@ex ample.com", "internal whitespace"),
+            ("john@example .com", "whitespace in the domain"),
+            ("\tjohn@example.com", "leading tab"),
+            ("john@@example.com", "doubled @"),
+            ("john@doe@example.com", "two @"),
+            ("john.example.com", "no @"),
+            ("@example.com", "empty local part"),
+            ("john@", "empty domain"),
+            ("john@localhost", "single-label domain"),
+            ("john@.example.com", "leading dot in the domain"),
+            ("john@example.com.", "trailing dot in the domain"),
+            ("john@example..com", "doubled dot in the domain"),
+            ("", "empty"),
+        ];
+        for (email, reason) in cases {
+            let error = validate_email(email).unwrap_err();
+            assert_eq!(error.status, rocket::http::Status::BadRequest, "{}", reason);
+            assert_eq!(error.message, format!("email '{}' is malformed", email), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn prices_keep_at_most_two_decimals() {
+        let cases = [("3.9", "3.90"), ("3.90", "3.90"), ("3.900", "3.90"), ("4", "4.00"), ("0.05", "0.05")];
+        for (price, expected) in cases {
+            let validated = validate_price("base_price", price.parse().unwrap()).unwrap();
+            assert_eq!(validated.to_string(), expected, "{}", price);
+        }
+    }
+
+    #[test]
+    fn prices_with_more_decimals_are_rejected() {
+        for price in ["3.999", "3.99999999", "0.001"] {
+            let error = validate_price("extra_price", price.parse().unwrap()).unwrap_err();
+            assert_eq!(error.status, rocket::http::Status::BadRequest);
+            assert_eq!(error.message, "extra_price cannot have more than 2 decimal places", "{}", price);
+        }
+    }
+
+    #[test]
+    fn accepts_common_phone_formats() {
+        for phone in ["+44 20 7946 0958", "(555) 010-0123", "555.0100", "0612345678", " +33612345678 "] {
+            assert!(validate_phone(phone).is_ok(), "{:?} should be accepted", phone);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_phones() {
+        for phone in ["", "call me", "12345", "+1234567890123456", "555-0100 ext 2", "++44 20 7946 0958", "+"] {
+            assert!(validate_phone(phone).is_err(), "{:?} should be rejected", phone);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! POST /api/customers/<id>/anonymize (GDPR erasure)
+
+mod common;
+
+use rocket::http::{Header, Status};
+use serde_json::{json, Value};
+
+use common::{body, test_config, TestApp};
+
+const ADMIN_TOKEN: &str = "admin-secret";
+
+fn start() -> TestApp {
+    let mut config = test_config();
+    config.admin_token = Some(ADMIN_TOKEN.to_string());
+    TestApp::with_config(config)
+}
+
+fn anonymize(app: &TestApp, customer_id: &str, admin_token: Option<&str>) -> (Status, Value) {
+    let mut request = app.client.post(format!("/api/customers/{}/anonymize", customer_id)).header(app.staff());
+    if let Some(token) = admin_token {
+        request = request.header(Header::new("X-Admin-Token", token.to_string()));
+    }
+    let response = request.dispatch();
+    (response.status(), body(response))
+}
+
+#[test]
+fn anonymized_customer_keeps_no_pii_and_orders_still_resolve() {
+    let app = start();
+    let response = app.post(
+        "/api/customers",
+        &json!({ "name": "Ann Example", "email": "ann@example.com", "phone": "+44 20 7946 0958" }),
+    );
+    assert_eq!(response.status(), Status::Created);
+    let customer = body(response);
+    assert_eq!(customer["phone"], "+44 20 7946 0958");
+    let customer_id = customer["id"].as_str().unwrap();
+
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    let order = app.create_order(customer_id, &employee, json!([{ "drink_id": drink["id"], "size": "medium", "extras": [] }]));
+    let order_id = order["id"].as_str().unwrap();
+
+    let (status, anonymized) = anonymize(&app, customer_id, Some(ADMIN_TOKEN));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(anonymized["id"], customer_id);
+    assert!(anonymized["deleted_at"].is_string());
+
+    // Read the stored row back: soft-deleted, so only listed with include_deleted
+    let listed = app.client.get("/api/customers?include_deleted=true").header(app.staff()).dispatch();
+    let listed = body(listed);
+    let stored = listed.as_array().unwrap().iter().find(|c| c["id"] == customer_id).expect("customer row is kept");
+    let serialized = stored.to_string();
+    for pii in ["Ann Example", "ann@example.com", "7946"] {
+        assert!(!serialized.contains(pii), "{} should be erased from {}", pii, serialized);
+    }
+    assert_eq!(stored["name"], format!("anonymized-{}", &customer_id[..8]));
+    assert_eq!(stored["phone"], Value::Null);
+
+    let order = app.get(&format!("/api/orders/{}", order_id));
+    assert_eq!(order.status(), Status::Ok);
+    assert_eq!(body(order)["customer_id"], customer_id);
+}
+
+#[test]
+fn anonymize_requires_the_admin_token() {
+    let app = start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let customer_id = customer["id"].as_str().unwrap();
+
+    assert_eq!(anonymize(&app, customer_id, None).0, Status::Unauthorized);
+    assert_eq!(anonymize(&app, customer_id, Some("admin-secreT")).0, Status::Forbidden);
+    assert_eq!(anonymize(&app, customer_id, Some("admin")).0, Status::Forbidden);
+
+    let response = app.get(&format!("/api/customers/{}", customer_id));
+    assert_eq!(body(response)["name"], "Ann");
+}
+
+#[test]
+fn phone_can_be_patched_and_cleared() {
+    let app = start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let uri = format!("/api/customers/{}", customer["id"].as_str().unwrap());
+
+    let response = app.patch(&uri, &json!({ "phone": " 555-0100 12 " }));
+    assert_eq!(body(response)["phone"], "555-0100 12");
+
+    let response = app.patch(&uri, &json!({ "name": "Ann B" }));
+    assert_eq!(body(response)["phone"], "555-0100 12");
+
+    let response = app.patch(&uri, &json!({ "phone": null }));
+    assert_eq!(body(response)["phone"], Value::Null);
+
+    let response = app.post("/api/customers", &json!({ "name": "Bob", "email": "bob@example.com", "phone": "call me" }));
+    assert_eq!(response.status(), Status::BadRequest);
+}
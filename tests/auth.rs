@@ -0,0 +1,101 @@
+//! Bearer token authentication of the write endpoints
+
+mod common;
+
+use chrono::Utc;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use rocket::error::ErrorKind;
+use rocket::http::{ContentType, Header as HttpHeader, Status};
+use rocket::local::blocking::Client;
+use serde_json::{json, Value};
+
+use common::{body, remove_db, temp_db_path, test_config, test_rocket, TestApp, JWT_SECRET};
+
+/// POST /api/customers with the given `Authorization` header value, if any
+fn create_customer_as(app: &TestApp, authorization: Option<&str>) -> (Status, Value) {
+    let mut request = app
+        .client
+        .post("/api/customers")
+        .header(ContentType::JSON)
+        .body(json!({ "name": "Ann", "email": "ann@example.com" }).to_string());
+    if let Some(value) = authorization {
+        request = request.header(HttpHeader::new("Authorization", value.to_string()));
+    }
+    let response = request.dispatch();
+    (response.status(), body(response))
+}
+
+#[test]
+fn server_refuses_to_start_without_a_secret() {
+    let mut config = test_config();
+    config.auth.jwt_secret = None;
+
+    let db_path = temp_db_path();
+    let Err(error) = Client::untracked(test_rocket(config, &db_path)) else {
+        panic!("server should not start without JWT_SECRET");
+    };
+    match error.kind() {
+        ErrorKind::FailedFairings(failed) => assert_eq!(failed[0].name, "Authentication"),
+        kind => panic!("unexpected launch error: {}", kind),
+    }
+    remove_db(&db_path);
+}
+
+#[test]
+fn auth_disabled_lets_writes_through_without_a_token() {
+    let mut config = test_config();
+    config.auth.jwt_secret = None;
+    config.auth.disabled = true;
+
+    let app = TestApp::with_config(config);
+    let (status, _) = create_customer_as(&app, None);
+    assert_eq!(status, Status::Created);
+}
+
+#[test]
+fn writes_without_a_valid_token_are_rejected_with_the_reason() {
+    let app = TestApp::start();
+
+    let (status, error) = create_customer_as(&app, None);
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(error["message"], "Authentication required: missing Authorization header.");
+
+    let (status, error) = create_customer_as(&app, Some("Token abc"));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(error["message"], "Authentication required: Authorization header must be 'Bearer <token>'.");
+
+    let (status, error) = create_customer_as(&app, Some("Bearer not-a-jwt"));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(error["message"], "Authentication required: token is invalid.");
+
+    let now = Utc::now().timestamp();
+    let claims = json!({ "sub": "staff", "iat": now - 7200, "exp": now - 3600, "role": "employee" });
+    let expired = encode(&Header::default(), &claims, &EncodingKey::from_secret(JWT_SECRET.as_bytes())).unwrap();
+    let (status, error) = create_customer_as(&app, Some(&format!("Bearer {}", expired)));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(error["message"], "Authentication required: token has expired.");
+}
+
+#[test]
+fn writes_with_a_valid_token_succeed_and_reads_stay_public() {
+    let app = TestApp::start();
+
+    let (status, _) = create_customer_as(&app, Some(&format!("Bearer {}", app.staff_token())));
+    assert_eq!(status, Status::Created);
+    assert_eq!(app.get("/api/customers").status(), Status::Ok);
+}
+
+#[test]
+fn token_endpoint_rejects_a_wrong_credential() {
+    let app = TestApp::start();
+    let credentials = [
+        json!({ "username": common::USERNAME, "password": "wrong-password" }),
+        json!({ "username": "someone", "password": common::PASSWORD }),
+        json!({ "username": common::USERNAME, "password": "" }),
+    ];
+    for credential in credentials {
+        let response = app.client.post("/api/auth/token").header(ContentType::JSON).body(credential.to_string()).dispatch();
+        assert_eq!(response.status(), Status::Unauthorized, "{}", credential);
+        assert_eq!(body(response)["message"], "Invalid username or password.");
+    }
+}
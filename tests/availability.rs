@@ -0,0 +1,104 @@
+//! Drinks switched off with PUT /api/drinks/<id>/availability cannot be ordered
+
+mod common;
+
+use rocket::http::Status;
+use serde_json::{json, Value};
+
+use common::{body, TestApp};
+
+/// A drink that exists but is marked unavailable, and the message rejecting it
+fn unavailable_drink(app: &TestApp) -> (Value, String) {
+    let drink = app.create_drink("Latte", "3.00");
+    let drink_id = drink["id"].as_str().unwrap();
+    let response = app.put(&format!("/api/drinks/{}/availability", drink_id), &json!({ "is_available": false }));
+    assert_eq!(response.status(), Status::Ok);
+    let message = format!("drink {} is unavailable", drink_id);
+    (drink, message)
+}
+
+fn assert_rejected(response: rocket::local::blocking::LocalResponse<'_>, message: &str) {
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    let error = body(response);
+    assert!(error["message"].as_str().unwrap().contains(message), "unexpected error {}", error);
+}
+
+#[test]
+fn order_with_an_unavailable_drink_is_rejected() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let (drink, message) = unavailable_drink(&app);
+
+    let response = app.post(
+        "/api/orders",
+        &json!({
+            "customer_id": customer["id"],
+            "employee_id": employee,
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+        }),
+    );
+    assert_rejected(response, &message);
+}
+
+#[test]
+fn validation_reports_an_unavailable_drink() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let (drink, message) = unavailable_drink(&app);
+
+    let response = app.post(
+        "/api/orders/validate",
+        &json!({
+            "customer_id": customer["id"],
+            "employee_id": employee,
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+        }),
+    );
+    assert_eq!(response.status(), Status::Ok);
+    let result = body(response);
+    assert_eq!(result["valid"], false);
+    assert_eq!(result["problems"], json!([{ "field": "items[0].drink_id", "message": message }]));
+}
+
+#[test]
+fn unavailable_drink_cannot_be_added_to_an_order() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let espresso = app.create_drink("Espresso", "2.00");
+    let order = app.create_order(
+        customer["id"].as_str().unwrap(),
+        &employee,
+        json!([{ "drink_id": espresso["id"], "size": "standard", "extras": [] }]),
+    );
+    let (drink, message) = unavailable_drink(&app);
+
+    let response = app.post(
+        &format!("/api/orders/{}/items", order["id"].as_str().unwrap()),
+        &json!({ "drink_id": drink["id"], "size": "medium", "extras": [] }),
+    );
+    assert_rejected(response, &message);
+}
+
+#[test]
+fn checkout_with_an_unavailable_drink_is_rejected() {
+    let app = TestApp::start();
+    let employee = app.insert_employee();
+    let (drink, message) = unavailable_drink(&app);
+
+    let response = app.post(
+        "/api/checkout",
+        &json!({
+            "customer": { "name": "Ann", "email": "ann@example.com" },
+            "employee_id": employee,
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+        }),
+    );
+    assert_rejected(response, &message);
+
+    // The customer is created in the same transaction, so it is rolled back too
+    let customers = body(app.client.get("/api/customers").header(app.staff()).dispatch());
+    assert_eq!(customers, json!([]));
+}
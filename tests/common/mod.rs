@@ -0,0 +1,190 @@
+//! Shared setup of the integration tests
+//!
+//! `TestApp` builds the real server against a fresh SQLite file in the temp
+//! directory (removed again on drop), with token authentication on and rate
+//! limiting and the stale order janitor off. Write helpers send a staff token.
+
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, SqliteConnection};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use coffeeshop_rocket_api::config::AppConfig;
+
+pub const JWT_SECRET: &str = "integration-test-secret";
+pub const USERNAME: &str = "staff";
+pub const PASSWORD: &str = "staff-password";
+
+/// Configuration of the server under test
+///
+/// Read from the environment like the binary does, with authentication set up
+/// and the settings that would make tests flaky (rate limit, janitor) turned off.
+pub fn test_config() -> AppConfig {
+    let mut config = AppConfig::from_env();
+    config.auth.jwt_secret = Some(JWT_SECRET.to_string());
+    config.auth.username = Some(USERNAME.to_string());
+    config.auth.password = Some(PASSWORD.to_string());
+    config.rate_limit_requests = 0;
+    config.stale_order_cancellation = false;
+    config.shop_hours = None;
+    config
+}
+
+/// Server under test with its own database
+pub struct TestApp {
+    pub client: Client,
+    pub db_path: PathBuf,
+    token: String,
+}
+
+impl TestApp {
+    pub fn start() -> TestApp {
+        TestApp::with_config(test_config())
+    }
+
+    pub fn with_config(config: AppConfig) -> TestApp {
+        let db_path = temp_db_path();
+        let issues_tokens = config.auth.jwt_secret.is_some();
+        let client = Client::untracked(test_rocket(config, &db_path)).expect("server should ignite");
+        let mut app = TestApp { client, db_path, token: String::new() };
+        if issues_tokens {
+            app.token = app.staff_token();
+        }
+        app
+    }
+
+    /// Token issued for the configured staff credential
+    pub fn staff_token(&self) -> String {
+        let response = self
+            .client
+            .post("/api/auth/token")
+            .header(ContentType::JSON)
+            .body(json!({ "username": USERNAME, "password": PASSWORD }).to_string())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok, "token endpoint should accept the staff credential");
+        body(response)["access_token"].as_str().expect("access_token").to_string()
+    }
+
+    /// `Authorization` header carrying `token`
+    pub fn bearer(token: &str) -> Header<'static> {
+        Header::new("Authorization", format!("Bearer {}", token))
+    }
+
+    /// `Authorization` header of the staff token
+    pub fn staff(&self) -> Header<'static> {
+        TestApp::bearer(&self.token)
+    }
+
+    pub fn get(&self, uri: &str) -> LocalResponse<'_> {
+        self.client.get(uri.to_string()).dispatch()
+    }
+
+    pub fn post(&self, uri: &str, body: &Value) -> LocalResponse<'_> {
+        self.client.post(uri.to_string()).header(self.staff()).header(ContentType::JSON).body(body.to_string()).dispatch()
+    }
+
+    pub fn put(&self, uri: &str, body: &Value) -> LocalResponse<'_> {
+        self.client.put(uri.to_string()).header(self.staff()).header(ContentType::JSON).body(body.to_string()).dispatch()
+    }
+
+    pub fn patch(&self, uri: &str, body: &Value) -> LocalResponse<'_> {
+        self.client.patch(uri.to_string()).header(self.staff()).header(ContentType::JSON).body(body.to_string()).dispatch()
+    }
+
+    pub fn delete(&self, uri: &str) -> LocalResponse<'_> {
+        self.client.delete(uri.to_string()).header(self.staff()).dispatch()
+    }
+
+    /// Direct connection to the database, for setup the API doesn't offer
+    pub fn db(&self) -> SqliteConnection {
+        let path = self.db_path.to_str().expect("temp path is UTF-8");
+        let mut conn = SqliteConnection::establish(path).expect("test database should open");
+        conn.batch_execute("PRAGMA busy_timeout = 5000; PRAGMA foreign_keys = ON;").expect("pragmas");
+        conn
+    }
+
+    /// Create a customer and return its JSON
+    pub fn create_customer(&self, name: &str, email: &str) -> Value {
+        let response = self.post("/api/customers", &json!({ "name": name, "email": email }));
+        assert_eq!(response.status(), Status::Created, "customer {} should be created", email);
+        body(response)
+    }
+
+    /// Create a drink sold in every size and return its JSON
+    pub fn create_drink(&self, name: &str, base_price: &str) -> Value {
+        let response = self.post(
+            "/api/drinks",
+            &json!({ "name": name, "base_price": base_price, "available_sizes": ["small", "medium", "large", "standard"] }),
+        );
+        assert_eq!(response.status(), Status::Created, "drink {} should be created", name);
+        body(response)
+    }
+
+    /// Create an available extra and return its JSON
+    pub fn create_extra(&self, name: &str, extra_price: &str) -> Value {
+        let response = self.post("/api/extras", &json!({ "name": name, "extra_price": extra_price }));
+        assert_eq!(response.status(), Status::Created, "extra {} should be created", name);
+        body(response)
+    }
+
+    /// Insert an active employee (there is no endpoint for it) and return its id
+    pub fn insert_employee(&self) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.db()
+            .batch_execute(&format!(
+                "INSERT INTO employees (id, name, email, birth_date, created_at, updated_at) \
+                 VALUES ('{id}', 'Barista', '{id}@example.com', '1990-01-01', '2025-01-01T00:00:00', '2025-01-01T00:00:00')"
+            ))
+            .expect("employee insert");
+        id
+    }
+
+    /// Place an order with `items` and return the created order
+    pub fn create_order(&self, customer_id: &str, employee_id: &str, items: Value) -> Value {
+        let response = self.post(
+            "/api/orders",
+            &json!({ "customer_id": customer_id, "employee_id": employee_id, "items": items }),
+        );
+        assert_eq!(response.status(), Status::Created, "order should be created");
+        body(response)
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        remove_db(&self.db_path);
+    }
+}
+
+/// Fresh database file name in the temp directory
+pub fn temp_db_path() -> PathBuf {
+    env::temp_dir().join(format!("coffeeshop-test-{}.db", Uuid::new_v4()))
+}
+
+/// Delete a test database with its WAL files
+pub fn remove_db(db_path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+    }
+}
+
+/// The server built from `config`, using the database at `db_path`, quiet
+pub fn test_rocket(config: AppConfig, db_path: &Path) -> rocket::Rocket<rocket::Build> {
+    let figment = coffeeshop_rocket_api::figment(&config)
+        .merge(("databases.sqlite.url", db_path.display().to_string()))
+        .merge(("log_level", "off"));
+    coffeeshop_rocket_api::rocket(config, figment)
+}
+
+/// JSON body of a response
+pub fn body(response: LocalResponse<'_>) -> Value {
+    response.into_json().expect("response body should be JSON")
+}
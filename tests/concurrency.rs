@@ -0,0 +1,59 @@
+//! Concurrent writes against the pooled SQLite connections, and the pool settings
+
+mod common;
+
+use rocket::futures::future::join_all;
+use rocket::http::{ContentType, Status};
+use rocket::local::asynchronous::Client;
+use serde_json::{json, Value};
+
+use common::{remove_db, temp_db_path, test_config, test_rocket, TestApp, PASSWORD, USERNAME};
+
+const WRITERS: usize = 24;
+
+#[rocket::async_test]
+async fn concurrent_creates_do_not_hit_lock_errors() {
+    let mut config = test_config();
+    config.db_pool_size = Some(8);
+    let db_path = temp_db_path();
+    let client = Client::untracked(test_rocket(config, &db_path)).await.expect("server should ignite");
+
+    let response = client
+        .post("/api/auth/token")
+        .header(ContentType::JSON)
+        .body(json!({ "username": USERNAME, "password": PASSWORD }).to_string())
+        .dispatch()
+        .await;
+    let token = response.into_json::<Value>().await.unwrap()["access_token"].as_str().unwrap().to_string();
+
+    let creates = (0..WRITERS).map(|i| {
+        client
+            .post("/api/customers")
+            .header(TestApp::bearer(&token))
+            .header(ContentType::JSON)
+            .body(json!({ "name": format!("Customer {}", i), "email": format!("customer{}@example.com", i) }).to_string())
+            .dispatch()
+    });
+    for response in join_all(creates).await {
+        let status = response.status();
+        let body = response.into_string().await.unwrap_or_default();
+        assert_eq!(status, Status::Created, "{}", body);
+        assert!(!body.contains("locked"), "{}", body);
+    }
+
+    let response = client.get("/api/customers?limit=100").header(TestApp::bearer(&token)).dispatch().await;
+    let customers = response.into_json::<Value>().await.unwrap();
+    assert_eq!(customers.as_array().unwrap().len(), WRITERS);
+
+    drop(client);
+    remove_db(&db_path);
+}
+
+#[test]
+fn zero_connection_timeout_is_raised_to_the_minimum() {
+    let mut config = test_config();
+    config.db_timeout_seconds = Some(0);
+    let app = TestApp::with_config(config);
+
+    assert_eq!(app.get("/api/drinks").status(), Status::Ok);
+}
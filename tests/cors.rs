@@ -0,0 +1,67 @@
+//! CORS headers for the configured origin allowlist
+
+mod common;
+
+use rocket::http::{Header, Status};
+use rocket::local::blocking::LocalResponse;
+
+use common::{test_config, TestApp};
+
+fn start(origins: &[&str], allow_credentials: bool) -> TestApp {
+    let mut config = test_config();
+    config.cors_allowed_origins = origins.iter().map(|origin| origin.to_string()).collect();
+    config.cors_allow_credentials = allow_credentials;
+    TestApp::with_config(config)
+}
+
+fn get_from<'a>(app: &'a TestApp, origin: &str) -> LocalResponse<'a> {
+    app.client.get("/api/drinks").header(Header::new("Origin", origin.to_string())).dispatch()
+}
+
+#[test]
+fn listed_origins_are_echoed_back() {
+    let app = start(&["https://shop.example", "https://kiosk.example"], true);
+    for origin in ["https://shop.example", "https://kiosk.example"] {
+        let response = get_from(&app, origin);
+        assert_eq!(response.status(), Status::Ok);
+        let headers = response.headers();
+        assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some(origin));
+        assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), Some("true"));
+        assert!(headers.get("Vary").any(|vary| vary == "Origin"));
+    }
+}
+
+#[test]
+fn other_origins_get_no_cors_headers() {
+    let app = start(&["https://shop.example"], true);
+    let response = get_from(&app, "https://evil.example");
+    assert_eq!(response.status(), Status::Ok);
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), None);
+    assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), None);
+}
+
+#[test]
+fn wildcard_is_never_combined_with_credentials() {
+    let app = start(&["*"], true);
+    let response = get_from(&app, "https://anywhere.example");
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("*"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Credentials"), None);
+}
+
+#[test]
+fn preflight_from_a_listed_origin_is_answered() {
+    let app = start(&["https://shop.example"], false);
+    let response = app
+        .client
+        .options("/api/orders")
+        .header(Header::new("Origin", "https://shop.example"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    let headers = response.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://shop.example"));
+    assert!(headers.get_one("Access-Control-Allow-Methods").unwrap().contains("POST"));
+    assert!(headers.get_one("Access-Control-Allow-Headers").unwrap().contains("Authorization"));
+}
@@ -0,0 +1,128 @@
+//! Customer records: creation, updates and the uniqueness of emails
+
+mod common;
+
+use rocket::http::Status;
+use serde_json::{json, Value};
+
+use common::{body, TestApp};
+
+#[test]
+fn creating_a_second_customer_with_the_same_email_conflicts() {
+    let app = TestApp::start();
+    app.create_customer("Ann", "ann@example.com");
+
+    let response = app.post("/api/customers", &json!({ "name": "Another Ann", "email": "ann@example.com" }));
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(
+        body(response),
+        json!({ "error": "Conflict", "message": "A customer with email 'ann@example.com' already exists" })
+    );
+}
+
+#[test]
+fn emails_differing_only_in_case_conflict() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "  Ann.Example@Example.COM ");
+    assert_eq!(customer["email"], "ann.example@example.com");
+
+    let response = app.post("/api/customers", &json!({ "name": "Ann", "email": "ANN.EXAMPLE@example.com" }));
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(body(response)["message"], "A customer with email 'ann.example@example.com' already exists");
+
+    let other = app.create_customer("Bob", "bob@example.com");
+    let response = app.put(
+        &format!("/api/customers/{}", other["id"].as_str().unwrap()),
+        &json!({ "name": "Bob", "email": "Ann.Example@example.com" }),
+    );
+    assert_eq!(response.status(), Status::Conflict);
+}
+
+#[test]
+fn updating_to_another_customers_email_conflicts() {
+    let app = TestApp::start();
+    let ann = app.create_customer("Ann", "ann@example.com");
+    app.create_customer("Bob", "bob@example.com");
+    let uri = format!("/api/customers/{}", ann["id"].as_str().unwrap());
+
+    let response = app.put(&uri, &json!({ "name": "Ann", "email": "bob@example.com" }));
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(body(response)["message"], "A customer with email 'bob@example.com' already exists");
+
+    let response = app.patch(&uri, &json!({ "email": "bob@example.com" }));
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(body(response)["message"], "A customer with email 'bob@example.com' already exists");
+
+    // Keeping one's own email is not a conflict
+    let response = app.put(&uri, &json!({ "name": "Ann Example", "email": "ann@example.com" }));
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(body(response)["name"], "Ann Example");
+}
+
+#[test]
+fn patch_tells_an_absent_phone_from_a_null_one() {
+    let app = TestApp::start();
+    let response = app.post("/api/customers", &json!({ "name": "Ann", "email": "ann@example.com", "phone": "555-0100" }));
+    assert_eq!(response.status(), Status::Created);
+    let uri = format!("/api/customers/{}", body(response)["id"].as_str().unwrap());
+
+    // Absent: the phone is left alone
+    let response = app.patch(&uri, &json!({ "name": "Ann Example" }));
+    assert_eq!(response.status(), Status::Ok);
+    let customer = body(response);
+    assert_eq!(customer["name"], "Ann Example");
+    assert_eq!(customer["phone"], "555-0100");
+
+    // Present: the phone is replaced
+    let response = app.patch(&uri, &json!({ "phone": " 555-0199 " }));
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(body(response)["phone"], "555-0199");
+
+    // Null: the phone is cleared
+    let response = app.patch(&uri, &json!({ "phone": null }));
+    assert_eq!(response.status(), Status::Ok);
+    let customer = body(response);
+    assert_eq!(customer["phone"], Value::Null);
+    assert_eq!(customer["name"], "Ann Example");
+
+    let stored = body(app.client.get(uri.clone()).header(app.staff()).dispatch());
+    assert_eq!(stored["phone"], Value::Null);
+}
+
+#[test]
+fn malformed_emails_are_rejected_on_every_write() {
+    let app = TestApp::start();
+    let customer = app.create_customer("John", "john@example.com");
+    let uri = format!("/api/customers/{}", customer["id"].as_str().unwrap());
+
+    for email in ["john doe@ex ample.com", "john@doe@example.com", "john@example.com."] {
+        let message = format!("email '{}' is malformed", email);
+
+        let response = app.post("/api/customers", &json!({ "name": "John", "email": email }));
+        assert_eq!(response.status(), Status::BadRequest, "POST {:?}", email);
+        assert_eq!(body(response)["message"], message);
+
+        let response = app.put(&uri, &json!({ "name": "John", "email": email }));
+        assert_eq!(response.status(), Status::BadRequest, "PUT {:?}", email);
+        assert_eq!(body(response)["message"], message);
+
+        let response = app.patch(&uri, &json!({ "email": email }));
+        assert_eq!(response.status(), Status::BadRequest, "PATCH {:?}", email);
+        assert_eq!(body(response)["message"], message);
+    }
+}
+
+#[test]
+fn customer_ids_in_paths_must_be_uuids() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let id = customer["id"].as_str().unwrap();
+
+    let response = app.client.get(format!("/api/customers/{}", id.to_uppercase())).header(app.staff()).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(body(response)["id"], id);
+
+    let response = app.client.get("/api/customers/not-a-uuid").header(app.staff()).dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(body(response)["message"], "'not-a-uuid' is not a valid id");
+}
@@ -0,0 +1,126 @@
+//! Drink catalogue: creating drinks and the rules on their prices and names
+
+mod common;
+
+use diesel::connection::SimpleConnection;
+use diesel::sql_types::Text;
+use diesel::{sql_query, QueryableByName, RunQueryDsl};
+use rocket::http::Status;
+use serde_json::json;
+
+use common::{body, TestApp};
+
+#[test]
+fn base_price_is_accepted_as_a_string_or_a_number() {
+    let app = TestApp::start();
+    for (name, base_price) in [("Latte", json!("4.00")), ("Mocha", json!(4.00))] {
+        let response = app.post("/api/drinks", &json!({ "name": name, "base_price": base_price }));
+        assert_eq!(response.status(), Status::Created, "base_price {}", base_price);
+        assert_eq!(body(response)["base_price"], "4.00", "base_price {}", base_price);
+    }
+}
+
+#[test]
+fn prices_are_kept_to_cents() {
+    let app = TestApp::start();
+
+    let response = app.post("/api/drinks", &json!({ "name": "Latte", "base_price": "3.9" }));
+    assert_eq!(response.status(), Status::Created);
+    let drink = body(response);
+    assert_eq!(drink["base_price"], "3.90");
+
+    let response = app.post("/api/drinks", &json!({ "name": "Mocha", "base_price": "3.999" }));
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(body(response)["message"], "base_price cannot have more than 2 decimal places");
+
+    let response = app.put(
+        &format!("/api/drinks/{}", drink["id"].as_str().unwrap()),
+        &json!({ "base_price": "3.99999999" }),
+    );
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let response = app.post("/api/extras", &json!({ "name": "Shot", "extra_price": "0.5" }));
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(body(response)["extra_price"], "0.50");
+
+    let response = app.post("/api/extras", &json!({ "name": "Syrup", "extra_price": "0.555" }));
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(body(response)["message"], "extra_price cannot have more than 2 decimal places");
+}
+
+#[test]
+fn drink_names_are_unique_among_active_drinks() {
+    let app = TestApp::start();
+    let latte = app.create_drink("Latte", "3.00");
+    let taken = json!({ "error": "Conflict", "message": "A drink named 'Latte' already exists" });
+
+    for name in ["Latte", "  Latte "] {
+        let response = app.post("/api/drinks", &json!({ "name": name, "base_price": "3.20" }));
+        assert_eq!(response.status(), Status::Conflict, "{:?}", name);
+        assert_eq!(body(response), taken);
+    }
+
+    let response = app.post(
+        "/api/drinks/batch",
+        &json!([{ "name": "Mocha", "base_price": "3.50" }, { "name": "Latte", "base_price": "3.20" }]),
+    );
+    assert_eq!(response.status(), Status::Conflict);
+    assert_eq!(body(response), taken);
+
+    // A soft-deleted drink frees its name, and cannot be restored while it is taken again
+    let latte_uri = format!("/api/drinks/{}", latte["id"].as_str().unwrap());
+    assert_eq!(app.delete(&latte_uri).status(), Status::NoContent);
+    app.create_drink("Latte", "3.20");
+    let response = app.post(&format!("{}/restore", latte_uri), &json!({}));
+    assert_eq!(response.status(), Status::Conflict);
+}
+
+#[derive(QueryableByName)]
+struct StoredTimestamps {
+    #[diesel(sql_type = Text)]
+    created_at: String,
+    #[diesel(sql_type = Text)]
+    updated_at: String,
+}
+
+#[test]
+fn created_at_survives_the_database_round_trip() {
+    let app = TestApp::start();
+    let before = chrono::Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let drink = app.create_drink("Latte", "3.00");
+    let created_at = drink["created_at"].as_str().unwrap();
+    assert!(created_at >= before.as_str(), "{} is before {}", created_at, before);
+
+    let stored = sql_query("SELECT created_at, updated_at FROM drinks WHERE id = ?")
+        .bind::<Text, _>(drink["id"].as_str().unwrap())
+        .get_result::<StoredTimestamps>(&mut app.db())
+        .expect("drink row");
+    assert!(stored.created_at.starts_with(created_at), "stored {}", stored.created_at);
+    assert_eq!(stored.created_at, stored.updated_at);
+
+    let read = body(app.get(&format!("/api/drinks/{}", drink["id"].as_str().unwrap())));
+    assert_eq!(read["created_at"], created_at);
+    assert_eq!(read["updated_at"], drink["updated_at"]);
+}
+
+#[test]
+fn rows_with_a_zero_fraction_load() {
+    let app = TestApp::start();
+    let id = "22222222-2222-2222-2222-222222222222";
+    app.db()
+        .batch_execute(&format!(
+            "INSERT INTO drinks (id, name, base_price, created_at, updated_at, available_sizes, is_available) \
+             VALUES ('{id}', 'Seeded', '2.50', '2024-01-02T03:04:05.000000', '2024-01-02T03:04:05.000', 'medium', 1)"
+        ))
+        .expect("drink insert");
+
+    let response = app.get(&format!("/api/drinks/{}", id));
+    assert_eq!(response.status(), Status::Ok);
+    let drink = body(response);
+    assert_eq!(drink["created_at"], "2024-01-02T03:04:05");
+    assert_eq!(drink["updated_at"], "2024-01-02T03:04:05");
+
+    let response = app.get("/api/drinks");
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(body(response)[0]["id"], id);
+}
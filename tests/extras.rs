@@ -0,0 +1,40 @@
+//! Extras catalogue: PUT /api/extras/by-name
+
+mod common;
+
+use rocket::http::Status;
+use serde_json::json;
+
+use common::{body, TestApp};
+
+#[test]
+fn upsert_by_name_inserts_then_updates() {
+    let app = TestApp::start();
+
+    let response = app.put("/api/extras/by-name", &json!({ "name": "Oat milk", "extra_price": "0.60" }));
+    assert_eq!(response.status(), Status::Created);
+    let created = body(response);
+    assert_eq!(created["extra_price"], "0.60");
+    assert_eq!(created["is_available"], true);
+
+    // Same name (surrounding whitespace ignored): the existing row is updated in place
+    let response = app.put("/api/extras/by-name", &json!({ "name": " Oat milk ", "extra_price": "0.70", "is_available": false }));
+    assert_eq!(response.status(), Status::Ok);
+    let updated = body(response);
+    assert_eq!(updated["id"], created["id"]);
+    assert_eq!(updated["extra_price"], "0.70");
+    assert_eq!(updated["is_available"], false);
+
+    // Without is_available the availability is kept; repeating the request changes nothing
+    for _ in 0..2 {
+        let response = app.put("/api/extras/by-name", &json!({ "name": "Oat milk", "extra_price": "0.75" }));
+        assert_eq!(response.status(), Status::Ok);
+        let updated = body(response);
+        assert_eq!(updated["id"], created["id"]);
+        assert_eq!(updated["extra_price"], "0.75");
+        assert_eq!(updated["is_available"], false);
+    }
+
+    let extras = body(app.get("/api/extras"));
+    assert_eq!(extras.as_array().unwrap().len(), 1);
+}
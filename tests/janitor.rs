@@ -0,0 +1,62 @@
+//! The stale order janitor
+
+mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::connection::SimpleConnection;
+use rocket::http::Status;
+use serde_json::{json, Value};
+
+use common::{body, test_config, TestApp};
+
+/// Status of order `order_id`
+fn status_of(app: &TestApp, order_id: &str) -> Value {
+    let response = app.get(&format!("/api/orders/{}", order_id));
+    assert_eq!(response.status(), Status::Ok);
+    body(response)["status"].clone()
+}
+
+#[test]
+fn janitor_measures_age_from_the_last_server_change() {
+    let mut config = test_config();
+    config.stale_order_cancellation = true;
+    config.stale_order_ttl_minutes = 120;
+    config.stale_order_sweep_interval_seconds = 1;
+    let app = TestApp::with_config(config);
+
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    let place = |placed_at: Option<String>| {
+        let response = app.post(
+            "/api/orders",
+            &json!({
+                "customer_id": customer["id"],
+                "employee_id": employee,
+                "placed_at": placed_at,
+                "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+            }),
+        );
+        assert_eq!(response.status(), Status::Created);
+        body(response)["id"].as_str().unwrap().to_string()
+    };
+
+    // Taken three hours ago on an offline register, synced just now
+    let synced = place(Some((Utc::now() - chrono::Duration::hours(3)).to_rfc3339()));
+    // Untouched since long before the TTL
+    let abandoned = place(None);
+    app.db()
+        .batch_execute(&format!(
+            "UPDATE orders SET created_at = '2025-01-01T00:00:00', updated_at = '2025-01-01T00:00:00' WHERE id = '{}'",
+            abandoned
+        ))
+        .expect("order update");
+
+    sleep(Duration::from_millis(2500));
+
+    assert_eq!(status_of(&app, &abandoned), "cancelled");
+    assert_eq!(status_of(&app, &synced), "pending");
+}
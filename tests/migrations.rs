@@ -0,0 +1,46 @@
+//! Database migrations run while the server ignites
+
+mod common;
+
+use diesel::connection::SimpleConnection;
+use diesel::{Connection, SqliteConnection};
+use rocket::error::ErrorKind;
+use rocket::local::blocking::Client;
+
+use common::{remove_db, temp_db_path, test_config, test_rocket};
+
+#[test]
+fn failing_migration_aborts_ignition_without_a_panic() {
+    // A table the migrations are about to create, with another shape
+    let db_path = temp_db_path();
+    SqliteConnection::establish(db_path.to_str().unwrap())
+        .expect("database file")
+        .batch_execute("CREATE TABLE drinks (legacy_id INTEGER PRIMARY KEY);")
+        .expect("conflicting table");
+
+    let Err(error) = Client::untracked(test_rocket(test_config(), &db_path)) else {
+        panic!("server should not start when a migration fails");
+    };
+    match error.kind() {
+        ErrorKind::FailedFairings(failed) => {
+            let names: Vec<&str> = failed.iter().map(|fairing| fairing.name).collect();
+            assert_eq!(names, ["Database Initialization"]);
+        }
+        kind => panic!("unexpected launch error: {}", kind),
+    }
+
+    remove_db(&db_path);
+}
+
+#[test]
+fn migrations_apply_to_a_fresh_database() {
+    let db_path = temp_db_path();
+    let client = Client::untracked(test_rocket(test_config(), &db_path)).expect("server should ignite");
+    drop(client);
+
+    // A second start finds nothing left to apply
+    let client = Client::untracked(test_rocket(test_config(), &db_path)).expect("server should ignite again");
+    drop(client);
+
+    remove_db(&db_path);
+}
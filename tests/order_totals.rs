@@ -0,0 +1,109 @@
+//! Server-side item pricing and GET /api/orders/<id>/total
+
+mod common;
+
+use rocket::http::Status;
+use diesel::connection::SimpleConnection;
+use serde_json::json;
+
+use common::{body, TestApp};
+
+#[test]
+fn item_price_follows_the_drink_size() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+
+    for (size, price) in [("small", "2.40"), ("medium", "3.00"), ("large", "3.90"), ("standard", "3.00")] {
+        let order = app.create_order(
+            customer["id"].as_str().unwrap(),
+            &employee,
+            json!([{ "drink_id": drink["id"], "size": size, "extras": [], "total_price": "99.00" }]),
+        );
+        let detail = body(app.get(&format!("/api/orders/{}", order["id"].as_str().unwrap())));
+        assert_eq!(detail["items"][0]["total_price"], price, "{} Latte", size);
+    }
+}
+
+#[test]
+fn total_counts_extras_once_and_matches_the_invoice() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    let shot = app.create_extra("Shot", "0.50");
+    let order = app.create_order(
+        customer["id"].as_str().unwrap(),
+        &employee,
+        json!([{ "drink_id": drink["id"], "size": "medium", "extras": [shot["id"]] }]),
+    );
+    let order_id = order["id"].as_str().unwrap();
+    let detail = body(app.get(&format!("/api/orders/{}", order_id)));
+    assert_eq!(detail["items"][0]["total_price"], "3.50");
+
+    let response = app.get(&format!("/api/orders/{}/total", order_id));
+    assert_eq!(response.status(), Status::Ok);
+    let total = body(response);
+    assert_eq!(total["drinks"], "3.00");
+    assert_eq!(total["extras"], "0.50");
+    assert_eq!(total["subtotal"], "3.50");
+    assert_eq!(total["total"], "3.50");
+
+    let invoice = body(app.client.get(format!("/api/orders/{}/invoice", order_id)).header(app.staff()).dispatch());
+    assert_eq!(invoice["subtotal"], total["subtotal"]);
+    assert_eq!(invoice["tax"], "0.00");
+    assert_eq!(invoice["total"], total["total"]);
+}
+
+#[test]
+fn total_keeps_the_extra_prices_that_were_charged() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    let shot = app.create_extra("Shot", "0.50");
+    let order = app.create_order(
+        customer["id"].as_str().unwrap(),
+        &employee,
+        json!([{ "drink_id": drink["id"], "size": "medium", "extras": [shot["id"]] }]),
+    );
+
+    let response = app.put(&format!("/api/extras/{}", shot["id"].as_str().unwrap()), &json!({ "extra_price": "1.25" }));
+    assert_eq!(response.status(), Status::Ok);
+
+    let total = body(app.get(&format!("/api/orders/{}/total", order["id"].as_str().unwrap())));
+    assert_eq!(total["drinks"], "3.00");
+    assert_eq!(total["extras"], "0.50");
+    assert_eq!(total["subtotal"], "3.50");
+}
+
+#[test]
+fn total_of_an_unknown_order_is_not_found() {
+    let app = TestApp::start();
+    let response = app.get("/api/orders/00000000-0000-0000-0000-000000000000/total");
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn order_without_items_totals_zero() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    // The API refuses orders without items, so insert one directly
+    let order_id = "11111111-1111-1111-1111-111111111111";
+    app.db()
+        .batch_execute(&format!(
+            "INSERT INTO orders (id, customer_id, employee_id, status, created_at, updated_at) \
+             VALUES ('{order_id}', '{}', '{employee}', 'pending', '2025-01-01T00:00:00', '2025-01-01T00:00:00')",
+            customer["id"].as_str().unwrap()
+        ))
+        .expect("order insert");
+
+    let response = app.get(&format!("/api/orders/{}/total", order_id));
+    assert_eq!(response.status(), Status::Ok);
+    let total = body(response);
+    for field in ["drinks", "extras", "subtotal", "total"] {
+        assert_eq!(total[field], "0.00", "{}", field);
+    }
+}
@@ -0,0 +1,317 @@
+//! Placing orders: POST /api/orders, the checks it runs and idempotent
+//! retries, and attaching extras to their items
+
+mod common;
+
+use chrono::{FixedOffset, Utc};
+use diesel::connection::SimpleConnection;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::LocalResponse;
+use serde_json::{json, Value};
+
+use coffeeshop_rocket_api::config::ShopHours;
+use common::{body, test_config, TestApp};
+
+/// A customer, an employee and a 3.00 drink to order
+fn order_setup(app: &TestApp) -> (String, String, Value) {
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    (customer["id"].as_str().unwrap().to_string(), employee, drink)
+}
+
+#[test]
+fn orders_cannot_start_past_paid() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+
+    for status in ["preparing", "ready", "completed", "cancelled"] {
+        let response = app.post(
+            "/api/orders",
+            &json!({
+                "customer_id": customer_id,
+                "employee_id": employee_id,
+                "status": status,
+                "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+            }),
+        );
+        assert_eq!(response.status(), Status::UnprocessableEntity, "initial status {}", status);
+    }
+
+    let orders = body(app.client.get("/api/orders").header(app.staff()).dispatch());
+    assert_eq!(orders, json!([]));
+}
+
+#[test]
+fn orders_may_start_paid() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+
+    let response = app.post(
+        "/api/orders",
+        &json!({
+            "customer_id": customer_id,
+            "employee_id": employee_id,
+            "status": "paid",
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+        }),
+    );
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(body(response)["status"], "paid");
+}
+
+#[test]
+fn an_extra_listed_twice_on_an_item_is_rejected() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let shot = app.create_extra("Shot", "0.50");
+
+    let response = app.post(
+        "/api/orders",
+        &json!({
+            "customer_id": customer_id,
+            "employee_id": employee_id,
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [shot["id"], shot["id"]] }],
+        }),
+    );
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    let message = body(response)["message"].as_str().unwrap().to_string();
+    assert!(message.contains(&format!("extra {} is listed more than once", shot["id"].as_str().unwrap())), "{}", message);
+}
+
+#[test]
+fn an_extra_attached_twice_to_an_item_conflicts() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let shot = app.create_extra("Shot", "0.50");
+    let order = app.create_order(&customer_id, &employee_id, json!([{ "drink_id": drink["id"], "size": "medium", "extras": [] }]));
+    let order_uri = format!("/api/orders/{}", order["id"].as_str().unwrap());
+    let item_id = body(app.get(&order_uri))["items"][0]["id"].as_str().unwrap().to_string();
+    let attach_uri = format!("/api/order-items/{}/extras", item_id);
+
+    let response = app.post(&attach_uri, &json!({ "extra_id": shot["id"] }));
+    assert_eq!(response.status(), Status::Created);
+    let response = app.post(&attach_uri, &json!({ "extra_id": shot["id"] }));
+    assert_eq!(response.status(), Status::Conflict);
+
+    // The extra is charged once
+    let item = &body(app.get(&order_uri))["items"][0];
+    assert_eq!(item["total_price"], "3.50");
+    assert_eq!(item["extras"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn an_extra_not_allowed_on_the_drink_cannot_be_attached() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let oat = app.create_extra("Oat milk", "0.40");
+    let shot = app.create_extra("Shot", "0.50");
+    let drink_id = drink["id"].as_str().unwrap();
+    app.db()
+        .batch_execute(&format!(
+            "INSERT INTO drink_extras (drink_id, extra_id) VALUES ('{}', '{}')",
+            drink_id,
+            oat["id"].as_str().unwrap()
+        ))
+        .expect("drink_extras insert");
+    let order = app.create_order(&customer_id, &employee_id, json!([{ "drink_id": drink_id, "size": "medium", "extras": [] }]));
+    let order_uri = format!("/api/orders/{}", order["id"].as_str().unwrap());
+    let item_id = body(app.get(&order_uri))["items"][0]["id"].as_str().unwrap().to_string();
+    let attach_uri = format!("/api/order-items/{}/extras", item_id);
+
+    let response = app.post(&attach_uri, &json!({ "extra_id": shot["id"] }));
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    let message = body(response)["message"].as_str().unwrap().to_string();
+    assert!(message.contains(&format!("extra {} cannot be added to drink {}", shot["id"].as_str().unwrap(), drink_id)), "{}", message);
+
+    let response = app.post(&attach_uri, &json!({ "extra_id": oat["id"] }));
+    assert_eq!(response.status(), Status::Created);
+
+    let item = &body(app.get(&order_uri))["items"][0];
+    assert_eq!(item["total_price"], "3.40");
+    assert_eq!(item["extras"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn detaching_an_extra_refunds_the_price_it_was_charged_at() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let shot = app.create_extra("Shot", "0.50");
+    let syrup = app.create_extra("Syrup", "0.30");
+    let order = app.create_order(
+        &customer_id,
+        &employee_id,
+        json!([{ "drink_id": drink["id"], "size": "medium", "extras": [syrup["id"]] }]),
+    );
+    let order_uri = format!("/api/orders/{}", order["id"].as_str().unwrap());
+    let item_id = body(app.get(&order_uri))["items"][0]["id"].as_str().unwrap().to_string();
+
+    let response = app.post(&format!("/api/order-items/{}/extras", item_id), &json!({ "extra_id": shot["id"] }));
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(body(response)["extra_price"], "0.50");
+    assert_eq!(body(app.get(&order_uri))["items"][0]["total_price"], "3.80");
+
+    // Raising the catalog prices afterwards changes nothing already charged
+    for (extra, price) in [(&shot, "2.00"), (&syrup, "4.00")] {
+        let response = app.put(&format!("/api/extras/{}", extra["id"].as_str().unwrap()), &json!({ "extra_price": price }));
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    for extra in [&shot, &syrup] {
+        let response = app.delete(&format!("/api/order-items/{}/extras/{}", item_id, extra["id"].as_str().unwrap()));
+        assert_eq!(response.status(), Status::NoContent);
+    }
+    assert_eq!(body(app.get(&order_uri))["items"][0]["total_price"], "3.00");
+}
+
+#[test]
+fn items_come_back_in_the_order_they_were_placed() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, latte) = order_setup(&app);
+    let mocha = app.create_drink("Mocha", "3.50");
+    let tea = app.create_drink("Tea", "2.00");
+    let espresso = app.create_drink("Espresso", "2.20");
+
+    let order = app.create_order(
+        &customer_id,
+        &employee_id,
+        json!([
+            { "drink_id": tea["id"], "size": "small", "extras": [] },
+            { "drink_id": latte["id"], "size": "large", "extras": [] },
+            { "drink_id": mocha["id"], "size": "medium", "extras": [] },
+        ]),
+    );
+    let order_uri = format!("/api/orders/{}", order["id"].as_str().unwrap());
+    let response = app.post(&format!("{}/items", order_uri), &json!({ "drink_id": espresso["id"], "size": "standard", "extras": [] }));
+    assert_eq!(response.status(), Status::Ok);
+
+    let expected = ["Tea", "Latte", "Mocha", "Espresso"];
+    // The same sequence on every read, whatever the item ids
+    for _ in 0..3 {
+        let detail = body(app.get(&order_uri));
+        let names: Vec<&str> = detail["items"].as_array().unwrap().iter().map(|item| item["drink_name"].as_str().unwrap()).collect();
+        assert_eq!(names, expected);
+    }
+}
+
+#[test]
+fn far_future_placed_at_is_rejected() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let order = |placed_at: chrono::DateTime<chrono::Utc>| {
+        json!({
+            "customer_id": customer_id,
+            "employee_id": employee_id,
+            "placed_at": placed_at.to_rfc3339(),
+            "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+        })
+    };
+
+    let tomorrow = chrono::Utc::now() + chrono::Duration::days(1);
+    let response = app.post("/api/orders", &order(tomorrow));
+    assert_eq!(response.status(), Status::BadRequest);
+    let message = body(response)["message"].as_str().unwrap().to_string();
+    assert!(message.contains("placed_at") && message.contains("in the future"), "{}", message);
+
+    // Past times, e.g. from an offline register, are kept as created_at
+    let response = app.post("/api/orders", &order(chrono::DateTime::parse_from_rfc3339("2025-03-01T08:30:00Z").unwrap().into()));
+    assert_eq!(response.status(), Status::Created);
+    assert!(body(response)["created_at"].as_str().unwrap().starts_with("2025-03-01T08:30:00"));
+}
+
+#[test]
+fn orders_below_the_minimum_total_are_rejected_with_the_shortfall() {
+    let mut config = test_config();
+    config.min_order_total = "5.00".parse().unwrap();
+    let app = TestApp::with_config(config);
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let order = |items: Value| json!({ "customer_id": customer_id, "employee_id": employee_id, "items": items });
+    let latte = json!({ "drink_id": drink["id"], "size": "medium", "extras": [] });
+    let shortfall = "order subtotal 3.00 is below the minimum order total of 5.00 (2.00 short)";
+
+    let response = app.post("/api/orders", &order(json!([latte])));
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    let message = body(response)["message"].as_str().unwrap().to_string();
+    assert!(message.contains(shortfall), "{}", message);
+
+    let validation = body(app.post("/api/orders/validate", &order(json!([latte]))));
+    assert_eq!(validation["problems"], json!([{ "field": "items", "message": shortfall }]));
+
+    let response = app.post("/api/orders", &order(json!([latte, latte])));
+    assert_eq!(response.status(), Status::Created);
+}
+
+/// POST /api/orders with an `Idempotency-Key`
+fn place_with_key<'a>(app: &'a TestApp, key: &str, order: &Value) -> LocalResponse<'a> {
+    app.client
+        .post("/api/orders")
+        .header(app.staff())
+        .header(ContentType::JSON)
+        .header(Header::new("Idempotency-Key", key.to_string()))
+        .body(order.to_string())
+        .dispatch()
+}
+
+#[test]
+fn a_replayed_key_gets_the_original_response() {
+    let app = TestApp::start();
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let order = json!({
+        "customer_id": customer_id,
+        "employee_id": employee_id,
+        "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+    });
+
+    let first = place_with_key(&app, "retry-1", &order);
+    assert_eq!(first.status(), Status::Created);
+    let location = first.headers().get_one("Location").map(String::from);
+    let created = body(first);
+
+    let response = app.put(&format!("/api/orders/{}/status", created["id"].as_str().unwrap()), &json!({ "status": "paid" }));
+    assert_eq!(response.status(), Status::Ok);
+
+    let replay = place_with_key(&app, "retry-1", &order);
+    assert_eq!(replay.status(), Status::Created);
+    assert_eq!(replay.headers().get_one("Location").map(String::from), location);
+    assert_eq!(body(replay), created);
+
+    let orders = body(app.client.get("/api/orders").header(app.staff()).dispatch());
+    assert_eq!(orders.as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn a_replayed_key_succeeds_after_closing_time() {
+    let now = Utc::now().time();
+    let mut config = test_config();
+    config.shop_hours = Some(ShopHours {
+        open: now + chrono::Duration::hours(1),
+        close: now + chrono::Duration::hours(2),
+        utc_offset: FixedOffset::east_opt(0).unwrap(),
+    });
+    let app = TestApp::with_config(config);
+    let (customer_id, employee_id, drink) = order_setup(&app);
+    let order = json!({
+        "customer_id": customer_id,
+        "employee_id": employee_id,
+        "items": [{ "drink_id": drink["id"], "size": "medium", "extras": [] }],
+    });
+
+    // Placed under the key before closing; this key predates stored responses
+    let order_id = "22222222-2222-2222-2222-222222222222";
+    let recorded_at = Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S%.f");
+    app.db()
+        .batch_execute(&format!(
+            "INSERT INTO orders (id, customer_id, employee_id, status, created_at, updated_at) \
+             VALUES ('{order_id}', '{customer_id}', '{employee_id}', 'pending', '{recorded_at}', '{recorded_at}'); \
+             INSERT INTO idempotency_keys (key, order_id, created_at) VALUES ('retry-1', '{order_id}', '{recorded_at}');"
+        ))
+        .expect("order insert");
+
+    let replay = place_with_key(&app, "retry-1", &order);
+    assert_eq!(replay.status(), Status::Created);
+    assert_eq!(body(replay)["id"], order_id);
+
+    let response = place_with_key(&app, "retry-2", &order);
+    assert_eq!(response.status(), Status::Locked);
+    assert!(body(response)["message"].as_str().unwrap().starts_with("The shop is closed."));
+}
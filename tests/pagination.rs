@@ -0,0 +1,69 @@
+//! `Link` and `X-Total-Count` headers of the list endpoints
+
+mod common;
+
+use rocket::http::Status;
+
+use common::{body, TestApp};
+
+#[test]
+fn middle_page_links_to_next_and_prev() {
+    let app = TestApp::start();
+    for n in 0..3 {
+        app.create_customer("Customer", &format!("c{}@example.com", n));
+    }
+
+    let response = app.get("/api/customers?limit=1&offset=1");
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Total-Count"), Some("3"));
+    assert_eq!(
+        response.headers().get_one("Link"),
+        Some(r#"</api/customers?limit=1&offset=2>; rel="next", </api/customers?limit=1&offset=0>; rel="prev""#)
+    );
+}
+
+#[test]
+fn first_page_has_no_prev_and_last_page_no_next() {
+    let app = TestApp::start();
+    app.create_drink("Latte", "3.00");
+    app.create_drink("Mocha", "3.50");
+
+    let first = app.get("/api/drinks?limit=1");
+    assert_eq!(first.headers().get_one("Link"), Some(r#"</api/drinks?limit=1&offset=1>; rel="next""#));
+
+    let last = app.get("/api/drinks?limit=1&offset=1");
+    assert_eq!(last.headers().get_one("Link"), Some(r#"</api/drinks?limit=1&offset=0>; rel="prev""#));
+}
+
+#[test]
+fn single_page_has_no_link_header() {
+    let app = TestApp::start();
+    app.create_customer("Only", "only@example.com");
+
+    let response = app.get("/api/customers");
+    assert_eq!(response.headers().get_one("X-Total-Count"), Some("1"));
+    assert_eq!(response.headers().get_one("Link"), None);
+    assert_eq!(body(response).as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn links_keep_the_other_query_parameters() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Customer", "c@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    for _ in 0..2 {
+        app.create_order(
+            customer["id"].as_str().unwrap(),
+            &employee,
+            serde_json::json!([{ "drink_id": drink["id"], "size": "medium", "extras": [] }]),
+        );
+    }
+
+    let response = app.client.get("/api/orders?status=pending&limit=1").header(app.staff()).dispatch();
+    assert_eq!(response.headers().get_one("X-Total-Count"), Some("2"));
+    assert_eq!(
+        response.headers().get_one("Link"),
+        Some(r#"</api/orders?status=pending&limit=1&offset=1>; rel="next""#)
+    );
+}
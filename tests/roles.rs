@@ -0,0 +1,274 @@
+//! Roles carried by tokens: staff credential, customer tokens, the customer
+//! order history and what a customer token may change
+
+mod common;
+
+use rocket::http::{ContentType, Method, Status};
+use serde_json::{json, Value};
+
+use common::{body, TestApp, PASSWORD, USERNAME};
+
+/// Customer token issued by staff for `customer_id`
+fn customer_token(app: &TestApp, customer_id: &str) -> String {
+    let response = app.post("/api/auth/customer-token", &json!({ "customer_id": customer_id }));
+    assert_eq!(response.status(), Status::Ok);
+    let token = body(response);
+    assert_eq!(token["role"], "customer");
+    token["access_token"].as_str().unwrap().to_string()
+}
+
+fn get_as(app: &TestApp, uri: &str, token: &str) -> Status {
+    app.client.get(uri.to_string()).header(TestApp::bearer(token)).dispatch().status()
+}
+
+fn send_as(app: &TestApp, method: Method, uri: &str, token: &str, json: Value) -> Status {
+    app.client
+        .req(method, uri.to_string())
+        .header(TestApp::bearer(token))
+        .header(ContentType::JSON)
+        .body(json.to_string())
+        .dispatch()
+        .status()
+}
+
+/// Ann and Bob with one pending order each, whose single item carries the extra
+struct TwoCustomers {
+    app: TestApp,
+    ann_id: String,
+    bob_id: String,
+    employee: String,
+    drink_id: String,
+    extra_id: String,
+    ann_token: String,
+    ann_order: String,
+    bob_order: String,
+    ann_item: String,
+    bob_item: String,
+}
+
+fn two_customers() -> TwoCustomers {
+    let app = TestApp::start();
+    let ann_id = app.create_customer("Ann", "ann@example.com")["id"].as_str().unwrap().to_string();
+    let bob_id = app.create_customer("Bob", "bob@example.com")["id"].as_str().unwrap().to_string();
+    let employee = app.insert_employee();
+    let drink_id = app.create_drink("Latte", "3.00")["id"].as_str().unwrap().to_string();
+    let extra_id = app.create_extra("Shot", "0.50")["id"].as_str().unwrap().to_string();
+    let items = json!([{ "drink_id": drink_id, "size": "medium", "extras": [extra_id] }]);
+    let ann_order = app.create_order(&ann_id, &employee, items.clone())["id"].as_str().unwrap().to_string();
+    let bob_order = app.create_order(&bob_id, &employee, items)["id"].as_str().unwrap().to_string();
+    let first_item = |order: &str| {
+        body(app.get(&format!("/api/orders/{}", order)))["items"][0]["id"].as_str().unwrap().to_string()
+    };
+    let ann_item = first_item(&ann_order);
+    let bob_item = first_item(&bob_order);
+    let ann_token = customer_token(&app, &ann_id);
+    TwoCustomers { app, ann_id, bob_id, employee, drink_id, extra_id, ann_token, ann_order, bob_order, ann_item, bob_item }
+}
+
+#[test]
+fn staff_credential_always_gets_the_employee_role() {
+    let app = TestApp::start();
+    let response = app
+        .client
+        .post("/api/auth/token")
+        .header(ContentType::JSON)
+        .body(json!({ "username": USERNAME, "password": PASSWORD }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(body(response)["role"], "employee");
+}
+
+#[test]
+fn client_supplied_role_is_rejected() {
+    let app = TestApp::start();
+    let response = app
+        .client
+        .post("/api/auth/token")
+        .header(ContentType::JSON)
+        .body(json!({ "username": USERNAME, "password": PASSWORD, "role": "customer" }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+    assert_eq!(body(response)["field"], "role");
+}
+
+#[test]
+fn customer_tokens_are_issued_by_staff_only() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let customer_id = customer["id"].as_str().unwrap();
+
+    let anonymous = app
+        .client
+        .post("/api/auth/customer-token")
+        .header(ContentType::JSON)
+        .body(json!({ "customer_id": customer_id }).to_string())
+        .dispatch();
+    assert_eq!(anonymous.status(), Status::Unauthorized);
+
+    let token = customer_token(&app, customer_id);
+    let by_customer = app
+        .client
+        .post("/api/auth/customer-token")
+        .header(TestApp::bearer(&token))
+        .header(ContentType::JSON)
+        .body(json!({ "customer_id": customer_id }).to_string())
+        .dispatch();
+    assert_eq!(by_customer.status(), Status::Forbidden);
+
+    let unknown = app.post("/api/auth/customer-token", &json!({ "customer_id": uuid::Uuid::new_v4().to_string() }));
+    assert_eq!(unknown.status(), Status::NotFound);
+}
+
+#[test]
+fn customers_read_only_their_own_orders() {
+    let app = TestApp::start();
+    let ann: Value = app.create_customer("Ann", "ann@example.com");
+    let bob: Value = app.create_customer("Bob", "bob@example.com");
+    let employee = app.insert_employee();
+    let drink = app.create_drink("Latte", "3.00");
+    app.create_order(
+        ann["id"].as_str().unwrap(),
+        &employee,
+        json!([{ "drink_id": drink["id"], "size": "medium", "extras": [] }]),
+    );
+
+    let ann_orders = format!("/api/customers/{}/orders", ann["id"].as_str().unwrap());
+    let bob_orders = format!("/api/customers/{}/orders", bob["id"].as_str().unwrap());
+    let ann_token = customer_token(&app, ann["id"].as_str().unwrap());
+
+    assert_eq!(app.get(&ann_orders).status(), Status::Unauthorized);
+    assert_eq!(get_as(&app, &ann_orders, &ann_token), Status::Ok);
+    assert_eq!(get_as(&app, &bob_orders, &ann_token), Status::Forbidden);
+    assert_eq!(get_as(&app, &bob_orders, &app.staff_token()), Status::Ok);
+
+    let response = app.client.get(ann_orders).header(TestApp::bearer(&ann_token)).dispatch();
+    assert_eq!(body(response).as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn customer_tokens_cannot_change_the_catalog() {
+    let app = TestApp::start();
+    let customer = app.create_customer("Ann", "ann@example.com");
+    let token = customer_token(&app, customer["id"].as_str().unwrap());
+
+    let response = app
+        .client
+        .post("/api/drinks")
+        .header(TestApp::bearer(&token))
+        .header(ContentType::JSON)
+        .body(json!({ "name": "Latte", "base_price": "3.00" }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(body(response)["message"], "Access denied: employee role required.");
+}
+
+#[test]
+fn customer_tokens_cannot_run_the_order_workflow() {
+    let t = two_customers();
+    let ann_order = |path: &str| format!("/api/orders/{}{}", t.ann_order, path);
+
+    let forbidden = [
+        (Method::Put, ann_order("/status"), json!({ "status": "paid" })),
+        (Method::Patch, ann_order("/customer"), json!({ "customer_id": t.ann_id })),
+        (Method::Post, "/api/orders/complete-ready".to_string(), json!({})),
+        (
+            Method::Post,
+            "/api/checkout".to_string(),
+            json!({
+                "customer": { "name": "Cid", "email": "cid@example.com" },
+                "employee_id": t.employee,
+                "items": [{ "drink_id": t.drink_id, "size": "medium", "extras": [] }],
+            }),
+        ),
+        (Method::Post, "/api/customers".to_string(), json!({ "name": "Cid", "email": "cid@example.com" })),
+        (Method::Delete, format!("/api/customers/{}", t.ann_id), json!({})),
+        (Method::Post, format!("/api/customers/{}/restore", t.ann_id), json!({})),
+    ];
+    for (method, uri, json) in forbidden {
+        assert_eq!(send_as(&t.app, method, &uri, &t.ann_token, json), Status::Forbidden, "{} {}", method, uri);
+    }
+
+    // Nothing changed
+    assert_eq!(body(t.app.get(&ann_order("")))["status"], "pending");
+}
+
+#[test]
+fn customers_change_only_their_own_orders() {
+    let t = two_customers();
+    let item = json!({ "drink_id": t.drink_id, "size": "small", "extras": [] });
+    let order = |customer: &str| json!({ "customer_id": customer, "employee_id": t.employee, "items": [item] });
+    let extras_of = |item: &str| format!("/api/order-items/{}/extras", item);
+    let attached = |item: &str| format!("/api/order-items/{}/extras/{}", item, t.extra_id);
+
+    let bob_items = format!("/api/orders/{}/items", t.bob_order);
+    let bob_cancel = format!("/api/orders/{}/cancel", t.bob_order);
+    let forbidden = [
+        (Method::Post, "/api/orders".to_string(), order(&t.bob_id)),
+        (Method::Post, bob_items, item.clone()),
+        (Method::Delete, attached(&t.bob_item), json!({})),
+        (Method::Post, extras_of(&t.bob_item), json!({ "extra_id": t.extra_id })),
+        (Method::Post, bob_cancel, json!({})),
+    ];
+    for (method, uri, json) in forbidden {
+        assert_eq!(send_as(&t.app, method, &uri, &t.ann_token, json), Status::Forbidden, "{} {}", method, uri);
+    }
+
+    let ann_items = format!("/api/orders/{}/items", t.ann_order);
+    let ann_cancel = format!("/api/orders/{}/cancel", t.ann_order);
+    let allowed = [
+        (Method::Post, "/api/orders".to_string(), order(&t.ann_id), Status::Created),
+        (Method::Post, ann_items, item.clone(), Status::Ok),
+        (Method::Delete, attached(&t.ann_item), json!({}), Status::NoContent),
+        (Method::Post, extras_of(&t.ann_item), json!({ "extra_id": t.extra_id }), Status::Created),
+        (Method::Post, ann_cancel, json!({}), Status::Ok),
+    ];
+    for (method, uri, json, status) in allowed {
+        assert_eq!(send_as(&t.app, method, &uri, &t.ann_token, json), status, "{} {}", method, uri);
+    }
+
+    // Bob's order is untouched
+    let bob = body(t.app.get(&format!("/api/orders/{}", t.bob_order)));
+    assert_eq!(bob["status"], "pending");
+    assert_eq!(bob["items"].as_array().unwrap().len(), 1);
+    assert_eq!(bob["items"][0]["extras"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn customers_update_only_their_own_details() {
+    let t = two_customers();
+    let ann = format!("/api/customers/{}", t.ann_id);
+    let bob = format!("/api/customers/{}", t.bob_id);
+    let details = json!({ "name": "Robert", "email": "robert@example.com" });
+
+    assert_eq!(send_as(&t.app, Method::Put, &bob, &t.ann_token, details.clone()), Status::Forbidden);
+    assert_eq!(send_as(&t.app, Method::Patch, &bob, &t.ann_token, json!({ "name": "Robert" })), Status::Forbidden);
+    assert_eq!(send_as(&t.app, Method::Patch, &ann, &t.ann_token, json!({ "name": "Annie" })), Status::Ok);
+    assert_eq!(send_as(&t.app, Method::Put, &ann, &t.ann_token, json!({ "name": "Ann", "email": "ann@example.com" })), Status::Ok);
+}
+
+#[test]
+fn order_lists_and_invoices_need_a_matching_token() {
+    let t = two_customers();
+    let ann_list = format!("/api/orders?customer_id={}", t.ann_id);
+    let bob_list = format!("/api/orders?customer_id={}", t.bob_id);
+    let ann_invoice = format!("/api/orders/{}/invoice", t.ann_order);
+    let bob_invoice = format!("/api/orders/{}/invoice", t.bob_order);
+    let staff = t.app.staff_token();
+
+    assert_eq!(t.app.get("/api/orders").status(), Status::Unauthorized);
+    assert_eq!(t.app.get(&ann_invoice).status(), Status::Unauthorized);
+
+    assert_eq!(get_as(&t.app, "/api/orders", &t.ann_token), Status::Forbidden);
+    assert_eq!(get_as(&t.app, &bob_list, &t.ann_token), Status::Forbidden);
+    assert_eq!(get_as(&t.app, &bob_invoice, &t.ann_token), Status::Forbidden);
+
+    assert_eq!(get_as(&t.app, &ann_invoice, &t.ann_token), Status::Ok);
+    assert_eq!(get_as(&t.app, "/api/orders", &staff), Status::Ok);
+    assert_eq!(get_as(&t.app, &bob_invoice, &staff), Status::Ok);
+
+    let response = t.app.client.get(ann_list).header(TestApp::bearer(&t.ann_token)).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let orders = body(response);
+    assert_eq!(orders.as_array().map(Vec::len), Some(1));
+    assert_eq!(orders[0]["id"], t.ann_order.as_str());
+}
@@ -0,0 +1,98 @@
+//! Pragmas applied to every pooled SQLite connection
+
+mod common;
+
+use diesel::sql_types::{BigInt, Text};
+use diesel::result::{DatabaseErrorKind, Error};
+use diesel::{sql_query, QueryableByName, RunQueryDsl, SqliteConnection};
+use rocket::local::asynchronous::Client;
+
+use coffeeshop_rocket_api::config::SqliteTuning;
+use coffeeshop_rocket_api::db::DbConn;
+use common::{remove_db, temp_db_path, test_config, test_rocket};
+
+#[derive(QueryableByName)]
+struct Pragmas {
+    #[diesel(sql_type = Text)]
+    journal_mode: String,
+    #[diesel(sql_type = BigInt)]
+    foreign_keys: i64,
+    #[diesel(sql_type = BigInt)]
+    busy_timeout: i64,
+    #[diesel(sql_type = BigInt)]
+    synchronous: i64,
+    #[diesel(sql_type = BigInt)]
+    cache_size: i64,
+    #[diesel(sql_type = BigInt)]
+    temp_store: i64,
+}
+
+const READ_PRAGMAS: &str = "SELECT \
+    (SELECT journal_mode FROM pragma_journal_mode) AS journal_mode, \
+    (SELECT foreign_keys FROM pragma_foreign_keys) AS foreign_keys, \
+    (SELECT timeout FROM pragma_busy_timeout) AS busy_timeout, \
+    (SELECT synchronous FROM pragma_synchronous) AS synchronous, \
+    (SELECT cache_size FROM pragma_cache_size) AS cache_size, \
+    (SELECT temp_store FROM pragma_temp_store) AS temp_store";
+
+/// Run `f` on a connection taken from the pool of a server configured with `tuning`
+async fn with_pooled_connection<F, R>(tuning: SqliteTuning, f: F) -> R
+where
+    F: FnOnce(&mut SqliteConnection) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let mut config = test_config();
+    config.sqlite = tuning;
+    let db_path = temp_db_path();
+    let client = Client::untracked(test_rocket(config, &db_path)).await.expect("server should ignite");
+
+    let conn = DbConn::get_one(client.rocket()).await.expect("pooled connection");
+    let result = conn.run(f).await;
+
+    drop(conn);
+    drop(client);
+    remove_db(&db_path);
+    result
+}
+
+async fn pooled_pragmas(tuning: SqliteTuning) -> Pragmas {
+    with_pooled_connection(tuning, |c| sql_query(READ_PRAGMAS).get_result::<Pragmas>(c)).await.expect("pragmas")
+}
+
+#[rocket::async_test]
+async fn default_tuning_is_applied() {
+    let pragmas = pooled_pragmas(SqliteTuning::default()).await;
+    assert_eq!(pragmas.journal_mode, "wal");
+    assert_eq!(pragmas.foreign_keys, 1);
+    assert_eq!(pragmas.busy_timeout, 5000);
+    assert_eq!(pragmas.synchronous, 1, "NORMAL");
+    assert_eq!(pragmas.cache_size, -16384, "16 MiB");
+    assert_eq!(pragmas.temp_store, 2, "MEMORY");
+}
+
+#[rocket::async_test]
+async fn configured_tuning_is_applied() {
+    let tuning = SqliteTuning { synchronous: "FULL", cache_size_kib: 4096, temp_store: "FILE" };
+    let pragmas = pooled_pragmas(tuning).await;
+    assert_eq!(pragmas.synchronous, 2, "FULL");
+    assert_eq!(pragmas.cache_size, -4096);
+    assert_eq!(pragmas.temp_store, 1, "FILE");
+}
+
+#[rocket::async_test]
+async fn foreign_keys_are_enforced() {
+    // The handlers check references first, so only a direct insert can reach the database
+    let result = with_pooled_connection(SqliteTuning::default(), |c| {
+        sql_query(
+            "INSERT INTO order_items (id, order_id, drink_id, size, total_price, position) \
+             VALUES ('item', 'no-such-order', 'no-such-drink', 'medium', '3.00', 0)",
+        )
+        .execute(c)
+    })
+    .await;
+
+    match result {
+        Err(Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {}
+        other => panic!("expected a foreign key violation, got {:?}", other),
+    }
+}